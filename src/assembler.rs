@@ -0,0 +1,1816 @@
+//! Assembling whole programs.
+//!
+//! Assembly happens in two passes. The first pass parses every statement, records the
+//! section-relative offset of each `label:` definition and lays out the instructions and data of
+//! every section. The sections are then placed in memory, after which the second pass resolves
+//! label operands and encodes the instruction words.
+//!
+//! With [`Assembler::relocatable`], references the linker has to resolve are kept as
+//! [`Relocation`]s instead, so the program can be written as an object file.
+//!
+//! Before that, macros, `.rept`/`.irp`/`.irpc` blocks, conditional blocks and `.include`s are
+//! expanded by a preprocessing stage.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::fmt::Display;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+use crate::diagnostic::Diagnostic;
+use crate::encode::check_range;
+use crate::expr::{BinaryOp, Expr, UnaryOp};
+use crate::instruction::Instruction;
+use crate::parse::{
+    is_identifier, parse_operand, split_statement, strip_comment, Operand, ParseError,
+};
+use crate::{encode, pseudo, AsmArgument, AsmRelocation, Xlen};
+
+mod directive;
+mod preprocess;
+
+use directive::{parse_directive, Directive};
+pub use preprocess::Expansion;
+use preprocess::{Preprocessor, SourceLine};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AssembleErrorKind {
+    Parse(ParseError),
+    DuplicateLabel(String),
+    UndefinedSymbol(String),
+    BranchOutOfRange {
+        label: String,
+        offset: i64,
+    },
+    JumpOutOfRange {
+        label: String,
+        offset: i64,
+    },
+    /// Instructions or initialized data in a section without contents, e.g. `.bss`.
+    DataInNobits(String),
+    /// A section that grows past the end of the 32-bit address space.
+    SectionOverflow(String),
+    /// `%pcrel_lo` of a label that is not placed at a `%pcrel_hi`.
+    MissingPcrelHi(String),
+    /// An expression in relocatable output that is not a symbol plus a constant.
+    NotRelocatable(String),
+    /// A `.macro` or `.rept` without its `.endm` or `.endr`.
+    UnterminatedBlock(String),
+    /// An `.endm`, `.endr` or `.exitm` outside of a block.
+    UnmatchedDirective(String),
+    DuplicateMacro(String),
+    MissingMacroArgument {
+        name: String,
+        parameter: String,
+    },
+    UnknownMacroParameter {
+        name: String,
+        parameter: String,
+    },
+    TooManyMacroArguments(String),
+    /// Expansions nested too deeply, usually because a macro invokes itself.
+    ExpansionDepth(String),
+    /// Expansions producing too many lines in total, e.g. a `.rept` with a huge count.
+    ExpansionSize(String),
+    /// An `.include` that is not found in any of the include paths.
+    IncludeNotFound(String),
+    /// A file that includes itself, directly or indirectly.
+    IncludeCycle(PathBuf),
+    ReadFailed {
+        path: PathBuf,
+        message: String,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AssembleError {
+    /// The file containing the line, if the source was read from a file.
+    pub file: Option<PathBuf>,
+    /// 1-based line number in the source, or `0` if the error is not about a single line.
+    pub line: usize,
+    pub kind: AssembleErrorKind,
+    /// The macro invocations and blocks the line was expanded from, innermost first.
+    pub expansion: Vec<Expansion>,
+}
+
+impl Display for AssembleErrorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Parse(err) => err.fmt(f),
+            Self::DuplicateLabel(label) => write!(f, "label `{label}` is already defined"),
+            Self::UndefinedSymbol(symbol) => write!(f, "symbol `{symbol}` is not defined"),
+            Self::BranchOutOfRange { label, offset } => write!(
+                f,
+                "branch to `{label}` is out of range ({offset} bytes, limit is ±4 KiB)"
+            ),
+            Self::JumpOutOfRange { label, offset } => write!(
+                f,
+                "jump to `{label}` is out of range ({offset} bytes, limit is ±1 MiB)"
+            ),
+            Self::DataInNobits(section) => {
+                write!(f, "section `{section}` cannot contain initialized data")
+            }
+            Self::SectionOverflow(section) => {
+                write!(
+                    f,
+                    "section `{section}` is larger than the 32-bit address space"
+                )
+            }
+            Self::MissingPcrelHi(label) => {
+                write!(f, "`%pcrel_lo({label})` does not refer to a `%pcrel_hi`")
+            }
+            Self::NotRelocatable(expr) => {
+                write!(f, "`{expr}` cannot be left to the linker")
+            }
+            Self::UnterminatedBlock(directive) => write!(f, "`{directive}` block is not closed"),
+            Self::UnmatchedDirective(directive) => {
+                write!(f, "`{directive}` is not part of a block")
+            }
+            Self::DuplicateMacro(name) => write!(f, "macro `{name}` is already defined"),
+            Self::MissingMacroArgument { name, parameter } => {
+                write!(f, "macro `{name}` requires a value for `{parameter}`")
+            }
+            Self::UnknownMacroParameter { name, parameter } => {
+                write!(f, "macro `{name}` has no parameter `{parameter}`")
+            }
+            Self::TooManyMacroArguments(name) => {
+                write!(f, "too many arguments for macro `{name}`")
+            }
+            Self::ExpansionDepth(name) => {
+                write!(f, "expansion of `{name}` is nested too deeply")
+            }
+            Self::ExpansionSize(name) => {
+                write!(f, "expansion of `{name}` produces too many lines")
+            }
+            Self::IncludeNotFound(name) => write!(f, "cannot find included file `{name}`"),
+            Self::IncludeCycle(path) => write!(f, "`{}` includes itself", path.display()),
+            Self::ReadFailed { path, message } => {
+                write!(f, "cannot read `{}`: {message}", path.display())
+            }
+        }
+    }
+}
+
+impl Display for AssembleError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}: {}",
+            Location(self.file.as_deref(), self.line),
+            self.kind
+        )?;
+        for expansion in &self.expansion {
+            write!(f, "\n    {expansion}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Display for Expansion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let location = Location(self.file.as_deref(), self.line);
+        match self.name.as_str() {
+            ".include" => write!(f, "included from {location}"),
+            name => write!(f, "in expansion of `{name}` at {location}"),
+        }
+    }
+}
+
+/// `file:line`, or `line N` for source that was not read from a file.
+struct Location<'a>(Option<&'a Path>, usize);
+
+impl Display for Location<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.0 {
+            Some(file) => write!(f, "{}:{}", file.display(), self.1),
+            None => write!(f, "line {}", self.1),
+        }
+    }
+}
+
+impl AssembleError {
+    /// Convert the error into a [`Diagnostic`] pointing at the part of the line it is about.
+    ///
+    /// `source` is the contents of [`AssembleError::file`], or the source passed to
+    /// [`Assembler::assemble`].
+    pub fn to_diagnostic(&self, source: &str) -> Diagnostic {
+        let text = self
+            .line
+            .checked_sub(1)
+            .and_then(|index| source.lines().nth(index))
+            .unwrap_or("");
+
+        let mut diagnostic = Diagnostic::error(self.kind.to_string()).at(
+            self.file.clone(),
+            self.line,
+            self.kind.span(text),
+        );
+        for expansion in &self.expansion {
+            diagnostic = diagnostic.with_note(expansion.to_string());
+        }
+        if let Some(help) = self.kind.help() {
+            diagnostic = diagnostic.with_help(help);
+        }
+
+        diagnostic
+    }
+}
+
+impl AssembleErrorKind {
+    /// Byte range of the part of the source line `text` this error is about, falling back to
+    /// the whole statement.
+    fn span(&self, text: &str) -> Range<usize> {
+        // Every part of the statement is a subslice of `text`
+        let range = |part: &str| {
+            let start = part.as_ptr() as usize - text.as_ptr() as usize;
+            start..start + part.len()
+        };
+
+        let statement = strip_comment(text);
+        let (labels, rest) = split_labels(statement);
+        let (verb, operands) = split_statement(rest);
+        let find = |name: &str| find_word(rest, name).map(range);
+        let all_operands = || match (operands.first(), operands.last()) {
+            (Some(first), Some(last)) => Some(range(first).start..range(last).end),
+            _ => None,
+        };
+
+        let span = match self {
+            Self::Parse(
+                ParseError::ExpectedRegister(index)
+                | ParseError::ExpectedFloatRegister(index)
+                | ParseError::ExpectedImmediate(index)
+                | ParseError::ExpectedOffset(index)
+                | ParseError::ExpectedAddress(index)
+                | ParseError::InvalidFenceSet(index)
+                | ParseError::InvalidRoundingMode(index)
+                | ParseError::InvalidCsr(index),
+            ) => operands.get(*index).map(|operand| range(operand)),
+            Self::Parse(ParseError::UnknownInstruction(_) | ParseError::UnknownDirective(_)) => {
+                Some(range(verb))
+            }
+            Self::Parse(
+                ParseError::UnknownRegister(name)
+                | ParseError::InvalidImmediate(name)
+                | ParseError::InvalidOperand(name)
+                | ParseError::InvalidString(name)
+                | ParseError::InvalidExpression(name)
+                | ParseError::UnresolvedLabel(name),
+            )
+            | Self::UndefinedSymbol(name)
+            | Self::BranchOutOfRange { label: name, .. }
+            | Self::JumpOutOfRange { label: name, .. }
+            | Self::MissingPcrelHi(name)
+            | Self::NotRelocatable(name) => find(name),
+            Self::Parse(
+                ParseError::ImmediateOutOfRange { value, .. } | ParseError::MisalignedOffset(value),
+            ) => operands
+                .iter()
+                .find(|operand| match parse_operand(operand) {
+                    Ok(Operand::Expr(expr) | Operand::Offset(expr, _)) => {
+                        expr.constant() == Ok(*value)
+                    }
+                    _ => false,
+                })
+                .map(|operand| range(operand))
+                .or_else(all_operands),
+            Self::Parse(ParseError::OperandCount { .. }) => all_operands(),
+            Self::DuplicateLabel(label) => labels
+                .into_iter()
+                .find(|defined| defined == label)
+                .map(range),
+            _ => None,
+        };
+
+        span.unwrap_or_else(|| range(statement))
+    }
+
+    /// A suggestion on how to fix the error, if there is an obvious one.
+    fn help(&self) -> Option<&'static str> {
+        Some(match self {
+            Self::Parse(ParseError::UnknownRegister(_)) => {
+                "registers are `x0` to `x31`, or ABI names such as `a0` or `sp`"
+            }
+            Self::BranchOutOfRange { .. } => {
+                "invert the condition and branch over a `j` to the label instead"
+            }
+            Self::UnterminatedBlock(directive) => match directive.as_str() {
+                ".macro" => "close the macro with `.endm`",
+                ".rept" | ".irp" | ".irpc" => "close the block with `.endr`",
+                _ => "close the block with `.endif`",
+            },
+            Self::DuplicateMacro(_) => "remove the previous definition with `.purgem` first",
+            Self::IncludeNotFound(_) => "add its directory with `Assembler::include_path`",
+            Self::NotRelocatable(_) => {
+                "only a symbol plus a constant, or the distance between two labels in the same \
+                 section, can be relocated"
+            }
+            _ => return None,
+        })
+    }
+}
+
+/// Position of `word` in `text` where it is not part of a longer identifier.
+fn find_word<'t>(text: &'t str, word: &str) -> Option<&'t str> {
+    let is_identifier_char = |c: char| c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '$');
+
+    text.match_indices(word)
+        .find(|&(i, _)| {
+            !text[..i].ends_with(is_identifier_char)
+                && !text[i + word.len()..].starts_with(is_identifier_char)
+        })
+        .map(|(i, _)| &text[i..i + word.len()])
+}
+
+impl std::error::Error for AssembleError {}
+
+impl From<ParseError> for AssembleErrorKind {
+    fn from(err: ParseError) -> Self {
+        match err {
+            ParseError::UnresolvedLabel(symbol) => Self::UndefinedSymbol(symbol),
+            err => Self::Parse(err),
+        }
+    }
+}
+
+/// A section of an assembled program, placed at its final address.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Section {
+    name: String,
+    address: u32,
+    alignment: u32,
+    nobits: bool,
+    size: u32,
+    data: Vec<u8>,
+}
+
+impl Section {
+    #[inline]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    #[inline]
+    pub fn address(&self) -> u32 {
+        self.address
+    }
+
+    #[inline]
+    pub fn alignment(&self) -> u32 {
+        self.alignment
+    }
+
+    /// Whether the section occupies no space in a file, like `.bss`.
+    #[inline]
+    pub fn is_nobits(&self) -> bool {
+        self.nobits
+    }
+
+    /// The contents of the section. For sections without contents this is empty.
+    #[inline]
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    #[inline]
+    pub fn size(&self) -> u32 {
+        self.size
+    }
+}
+
+/// How a [`Relocation`] is filled in, after the RISC-V ELF relocation types.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RelocationKind {
+    /// A 32-bit address in data.
+    Abs32,
+    /// The offset of a B-type instruction.
+    Branch,
+    /// The offset of a J-type instruction.
+    Jal,
+    /// The offset of the `auipc`/`jalr` pair of a `call` or `tail`.
+    Call,
+    /// `%hi`, in a U-type instruction.
+    Hi20,
+    /// `%lo`, in an I-type instruction.
+    Lo12I,
+    /// `%lo`, in an S-type instruction.
+    Lo12S,
+    /// `%pcrel_hi`, in an `auipc`.
+    PcrelHi20,
+    /// `%pcrel_lo` in an I-type instruction. The symbol is a label at the `auipc` holding the
+    /// matching [`RelocationKind::PcrelHi20`].
+    PcrelLo12I,
+    /// `%pcrel_lo` in an S-type instruction.
+    PcrelLo12S,
+}
+
+/// A reference to a symbol left for the linker to fill in.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Relocation {
+    /// Index of the section into [`Program::sections`].
+    pub section: usize,
+    /// Offset of the instruction or data within the section.
+    pub offset: u32,
+    pub kind: RelocationKind,
+    pub symbol: String,
+    pub addend: i64,
+}
+
+/// An assembled program.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Program {
+    sections: Vec<Section>,
+    words: Vec<u32>,
+    labels: BTreeMap<String, u32>,
+    label_sections: BTreeMap<String, usize>,
+    globals: BTreeSet<String>,
+    relocations: Vec<Relocation>,
+    xlen: Xlen,
+}
+
+impl Program {
+    /// The lowest address of any section with contents.
+    pub fn base_address(&self) -> u32 {
+        self.sections
+            .iter()
+            .filter(|section| !section.nobits && section.size() > 0)
+            .map(Section::address)
+            .min()
+            .unwrap_or(0)
+    }
+
+    /// The instruction words of the program, in source order.
+    #[inline]
+    pub fn words(&self) -> &[u32] {
+        &self.words
+    }
+
+    #[inline]
+    pub fn sections(&self) -> &[Section] {
+        &self.sections
+    }
+
+    #[inline]
+    pub fn section(&self, name: &str) -> Option<&Section> {
+        self.sections.iter().find(|section| section.name == name)
+    }
+
+    /// Absolute addresses of all labels defined in the program.
+    #[inline]
+    pub fn labels(&self) -> &BTreeMap<String, u32> {
+        &self.labels
+    }
+
+    /// Index into [`Program::sections`] of the section `label` is defined in.
+    #[inline]
+    pub fn label_section(&self, label: &str) -> Option<usize> {
+        self.label_sections.get(label).copied()
+    }
+
+    /// Labels declared with `.globl`.
+    #[inline]
+    pub fn globals(&self) -> &BTreeSet<String> {
+        &self.globals
+    }
+
+    /// References left for the linker, in order of their position within each section. Only
+    /// programs assembled with [`Assembler::relocatable`] have relocations.
+    #[inline]
+    pub fn relocations(&self) -> &[Relocation] {
+        &self.relocations
+    }
+
+    /// The program as a flat little-endian image starting at [`Program::base_address`], with gaps
+    /// between sections filled with zeroes.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let base = self.base_address();
+        let mut bytes = Vec::new();
+
+        for section in self.sections.iter().filter(|section| !section.nobits) {
+            if section.data.is_empty() {
+                continue;
+            }
+
+            let start = (section.address - base) as usize;
+            if bytes.len() < start + section.data.len() {
+                bytes.resize(start + section.data.len(), 0);
+            }
+            bytes[start..start + section.data.len()].copy_from_slice(&section.data);
+        }
+
+        bytes
+    }
+
+    /// The register width the program was assembled for.
+    #[inline]
+    pub fn xlen(&self) -> Xlen {
+        self.xlen
+    }
+
+    pub fn instructions(&self) -> impl Iterator<Item = Instruction> + '_ {
+        self.words
+            .iter()
+            .filter_map(|word| Instruction::decode(*word, self.xlen))
+    }
+}
+
+/// How a label operand is turned into an immediate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LabelUse {
+    /// PC-relative offset of a B-type instruction.
+    Branch,
+    /// PC-relative offset of a J-type instruction.
+    Jump,
+    /// The absolute address of the label.
+    Absolute,
+    /// Identifier operands are keywords rather than labels, e.g. the `iorw` sets of `fence` or
+    /// the rounding modes of floating-point instructions.
+    Keyword,
+}
+
+impl LabelUse {
+    fn of(verb: &str) -> Self {
+        match verb {
+            "beq" | "bne" | "blt" | "bge" | "bltu" | "bgeu" | "beqz" | "bnez" | "bltz" | "bgez"
+            | "bgtz" | "blez" | "bgt" | "ble" | "bgtu" | "bleu" => Self::Branch,
+            "j" | "jal" => Self::Jump,
+            // `fence` and the floating-point instructions
+            _ if verb.starts_with('f') => Self::Keyword,
+            // CSR names
+            _ if verb.starts_with("csr") => Self::Keyword,
+            _ => Self::Absolute,
+        }
+    }
+}
+
+struct Statement<'a> {
+    verb: String,
+    operands: Vec<Operand<'a>>,
+    /// Number of instruction words reserved during layout.
+    words: usize,
+}
+
+/// Work left for the second pass, once all labels have an address.
+enum Fixup<'a> {
+    Instruction(Statement<'a>),
+    Data { width: usize, value: Expr<'a> },
+}
+
+/// Values of all symbols, once every section has been placed.
+struct Symbols<'a> {
+    labels: BTreeMap<String, u32>,
+    /// Section of every label.
+    label_sections: HashMap<String, usize>,
+    constants: HashMap<&'a str, i64>,
+    /// `.equ` definitions that refer to labels.
+    deferred: HashMap<&'a str, Expr<'a>>,
+    /// PC-relative value of every `%pcrel_hi`, by the address of its instruction.
+    pcrel_hi: HashMap<u32, u32>,
+    /// Addresses of the `%pcrel_hi`s that are left to the linker.
+    external_hi: HashSet<u32>,
+}
+
+impl<'a> Symbols<'a> {
+    /// Limit on nested `.equ` definitions, which also catches cyclic definitions.
+    const MAX_DEPTH: usize = 64;
+
+    fn lookup(&self, name: &str, depth: usize) -> Option<i64> {
+        if let Some(address) = self.labels.get(name) {
+            return Some(i64::from(*address));
+        }
+        if let Some(value) = self.constants.get(name) {
+            return Some(*value);
+        }
+
+        let expr = self
+            .deferred
+            .get(name)
+            .filter(|_| depth < Self::MAX_DEPTH)?;
+        expr.evaluate(&|name| self.lookup(name, depth + 1)).ok()
+    }
+
+    #[inline]
+    fn evaluate(&self, expr: &Expr) -> Result<i64, AssembleErrorKind> {
+        Ok(expr.evaluate(&|name| self.lookup(name, 0))?)
+    }
+
+    /// Whether `expr` refers to an address rather than being a plain number.
+    fn is_address(&self, expr: &Expr) -> bool {
+        expr.symbols()
+            .iter()
+            .any(|name| self.labels.contains_key(*name) || self.deferred.contains_key(name))
+    }
+
+    /// `expr` as a symbol plus a constant, for relocatable output. The symbol is `None` if the
+    /// value does not depend on where the linker places sections, like the distance between two
+    /// labels in the same section.
+    fn reference(&self, expr: &Expr<'a>) -> Result<(Option<&'a str>, i64), AssembleErrorKind> {
+        self.reference_at(expr, 0)
+    }
+
+    fn reference_at(
+        &self,
+        expr: &Expr<'a>,
+        depth: usize,
+    ) -> Result<(Option<&'a str>, i64), AssembleErrorKind> {
+        let not_relocatable = || AssembleErrorKind::NotRelocatable(expr.to_string());
+
+        Ok(match expr {
+            Expr::Number(value) => (None, *value),
+            Expr::Symbol(name) => match (self.constants.get(name), self.deferred.get(name)) {
+                (Some(value), _) => (None, *value),
+                (None, Some(expr)) if depth < Self::MAX_DEPTH => {
+                    self.reference_at(expr, depth + 1)?
+                }
+                (None, Some(_)) => return Err(not_relocatable()),
+                (None, None) => (Some(*name), 0),
+            },
+            Expr::Unary(UnaryOp::Plus, inner) => self.reference_at(inner, depth)?,
+            Expr::Binary(BinaryOp::Add, lhs, rhs) => {
+                match (
+                    self.reference_at(lhs, depth)?,
+                    self.reference_at(rhs, depth)?,
+                ) {
+                    ((Some(_), _), (Some(_), _)) => return Err(not_relocatable()),
+                    ((lhs, a), (rhs, b)) => (lhs.or(rhs), a.wrapping_add(b)),
+                }
+            }
+            Expr::Binary(BinaryOp::Sub, lhs, rhs) => {
+                match (
+                    self.reference_at(lhs, depth)?,
+                    self.reference_at(rhs, depth)?,
+                ) {
+                    ((lhs, a), (None, b)) => (lhs, a.wrapping_sub(b)),
+                    ((Some(lhs), a), (Some(rhs), b))
+                        if self.label_sections.contains_key(lhs)
+                            && self.label_sections.get(lhs) == self.label_sections.get(rhs) =>
+                    {
+                        let distance = i64::from(self.labels[lhs]) - i64::from(self.labels[rhs]);
+                        (None, distance + a - b)
+                    }
+                    _ => return Err(not_relocatable()),
+                }
+            }
+            _ if expr
+                .symbols()
+                .iter()
+                .all(|name| self.constants.contains_key(name)) =>
+            {
+                (None, self.evaluate(expr)?)
+            }
+            _ => return Err(not_relocatable()),
+        })
+    }
+
+    /// Whether a PC-relative reference to `symbol` from `section` has to be left to the linker.
+    #[inline]
+    fn is_external(&self, symbol: Option<&str>, section: usize) -> bool {
+        symbol.is_some_and(|symbol| self.label_sections.get(symbol) != Some(&section))
+    }
+
+    /// Define a new local label at `address`, for the `%pcrel_lo` half of an `auipc` pair.
+    fn pcrel_label(&mut self, section: usize, address: u32) -> String {
+        let name = (0..)
+            .map(|i| format!(".Lpcrel_hi{i}"))
+            .find(|name| !self.labels.contains_key(name))
+            .unwrap();
+
+        self.labels.insert(name.clone(), address);
+        self.label_sections.insert(name.clone(), section);
+        name
+    }
+}
+
+/// Evaluate `expr` if it only refers to constants that are already known.
+#[inline]
+fn fold<'a>(expr: Expr<'a>, constants: &HashMap<&str, i64>) -> Expr<'a> {
+    match expr.evaluate(&|name| constants.get(name).copied()) {
+        Ok(value) => Expr::Number(value),
+        Err(_) => expr,
+    }
+}
+
+/// The operand as far as it is known during layout. Operands that still refer to symbols are
+/// represented as a label.
+fn provisional<'a>(operand: &Operand<'a>) -> AsmArgument<'a> {
+    match operand {
+        Operand::Register(reg) => AsmArgument::Register(*reg),
+        Operand::FloatRegister(reg) => AsmArgument::FloatRegister(*reg),
+        Operand::Expr(Expr::Number(value)) => AsmArgument::Immediate(*value),
+        Operand::Expr(expr) => AsmArgument::Label(expr.symbols().first().copied().unwrap_or("")),
+        Operand::Offset(Expr::Number(value), reg) => AsmArgument::OffsetImmediate(*value, *reg),
+        Operand::Offset(_, reg) | Operand::OffsetRelocation(_, _, reg) => {
+            AsmArgument::OffsetImmediate(0, *reg)
+        }
+        Operand::Relocation(_, expr) => {
+            AsmArgument::Label(expr.symbols().first().copied().unwrap_or(""))
+        }
+    }
+}
+
+#[inline]
+fn check_width(value: i64, width: usize) -> Result<i64, ParseError> {
+    let bits = 8 * width as u32;
+    check_range(value, -(1 << (bits - 1)), (1 << bits) - 1)
+}
+
+struct Pending<'a> {
+    source: &'a SourceLine,
+    section: usize,
+    offset: u32,
+    fixup: Fixup<'a>,
+}
+
+struct SectionBuilder {
+    name: String,
+    nobits: bool,
+    alignment: u32,
+    /// Size in bytes, which sections without contents track without filling `data`.
+    size: u32,
+    data: Vec<u8>,
+}
+
+impl SectionBuilder {
+    fn new(name: &str, nobits: bool) -> Self {
+        Self {
+            name: name.to_string(),
+            nobits,
+            alignment: 1,
+            size: 0,
+            data: Vec::new(),
+        }
+    }
+
+    #[inline]
+    fn offset(&self) -> u32 {
+        self.size
+    }
+
+    #[inline]
+    fn is_code(&self) -> bool {
+        self.name == ".text" || self.name.starts_with(".text.")
+    }
+
+    /// Grow the size by `len` bytes, failing if the section would no longer fit in the 32-bit
+    /// address space.
+    fn grow(&mut self, len: u64) -> Result<(), AssembleErrorKind> {
+        self.size = u32::try_from(u64::from(self.size) + len)
+            .map_err(|_| AssembleErrorKind::SectionOverflow(self.name.clone()))?;
+        Ok(())
+    }
+
+    fn emit(&mut self, bytes: &[u8]) -> Result<(), AssembleErrorKind> {
+        if self.nobits && bytes.iter().any(|byte| *byte != 0) {
+            return Err(AssembleErrorKind::DataInNobits(self.name.clone()));
+        }
+
+        self.grow(bytes.len() as u64)?;
+        if !self.nobits {
+            self.data.extend_from_slice(bytes);
+        }
+        Ok(())
+    }
+
+    /// Append `len` bytes of `fill`.
+    fn space(&mut self, len: u64, fill: u8) -> Result<(), AssembleErrorKind> {
+        if self.nobits && fill != 0 && len > 0 {
+            return Err(AssembleErrorKind::DataInNobits(self.name.clone()));
+        }
+
+        self.grow(len)?;
+        if !self.nobits {
+            self.data.resize(self.size as usize, fill);
+        }
+        Ok(())
+    }
+
+    /// Pad to a multiple of `alignment`. Without an explicit fill value, code is padded with
+    /// `nop`s where possible.
+    fn align(&mut self, alignment: u32, fill: Option<u8>) -> Result<(), AssembleErrorKind> {
+        self.alignment = self.alignment.max(alignment);
+
+        let offset = u64::from(self.offset());
+        let padding = offset.next_multiple_of(alignment.into()) - offset;
+        match fill {
+            Some(fill) => self.space(padding, fill),
+            None if self.is_code() && !self.nobits && alignment >= 4 => {
+                self.grow(padding)?;
+                let word_aligned = self.data.len().next_multiple_of(4);
+                self.data.resize(word_aligned, 0);
+                while self.data.len() < self.size as usize {
+                    self.data.extend_from_slice(&NOP.to_le_bytes());
+                }
+                Ok(())
+            }
+            None => self.space(padding, 0),
+        }
+    }
+}
+
+const NOP: u32 = 0x0000_0013;
+
+/// Split off any number of leading `label:` definitions.
+fn split_labels(mut line: &str) -> (Vec<&str>, &str) {
+    let mut labels = Vec::new();
+
+    while let Some((label, rest)) = line.split_once(':') {
+        let label = label.trim();
+        if !is_identifier(label) {
+            break;
+        }
+
+        labels.push(label);
+        line = rest.trim();
+    }
+
+    (labels, line)
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Assembler {
+    base_address: u32,
+    section_addresses: HashMap<String, u32>,
+    include_paths: Vec<PathBuf>,
+    relocatable: bool,
+    xlen: Xlen,
+}
+
+impl Assembler {
+    /// Create an assembler placing the program at `base_address`.
+    ///
+    /// `.text` is placed at `base_address` and every other section follows the previous one in
+    /// order of first use, unless given a fixed address with [`Assembler::section_address`].
+    #[inline]
+    pub fn new(base_address: u32) -> Self {
+        Self {
+            base_address,
+            section_addresses: HashMap::new(),
+            include_paths: Vec::new(),
+            relocatable: false,
+            xlen: Xlen::Rv32,
+        }
+    }
+
+    /// Place the section `name` at `address`.
+    #[inline]
+    pub fn section_address(mut self, name: &str, address: u32) -> Self {
+        self.section_addresses.insert(name.to_string(), address);
+        self
+    }
+
+    /// Search `path` for files named by `.include`, after the directory of the including file.
+    /// Paths are searched in the order they are added.
+    #[inline]
+    pub fn include_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.include_paths.push(path.into());
+        self
+    }
+
+    /// Assemble into a relocatable program, to be written as an object file and linked.
+    ///
+    /// Every section is placed at address `0` and labels are relative to their section. References
+    /// to undefined symbols, absolute addresses and PC-relative references to other sections are
+    /// kept as [`Relocation`]s.
+    #[inline]
+    pub fn relocatable(mut self, relocatable: bool) -> Self {
+        self.relocatable = relocatable;
+        self
+    }
+
+    /// Assemble for a core with the register width `xlen`, RV32 by default. The RV64I
+    /// instructions and the 64-bit forms of `li` and the immediate shifts need [`Xlen::Rv64`].
+    /// RV64 programs cannot be written by [`write_object`](crate::elf::write_object), which only
+    /// writes 32-bit ELF files.
+    #[inline]
+    pub fn xlen(mut self, xlen: Xlen) -> Self {
+        self.xlen = xlen;
+        self
+    }
+
+    #[inline]
+    pub fn assemble(&self, source: &str) -> Result<Program, AssembleError> {
+        self.assemble_source(source, None)
+    }
+
+    /// Assemble the file at `path`. Files it includes are looked up relative to it first.
+    pub fn assemble_file(&self, path: impl AsRef<Path>) -> Result<Program, AssembleError> {
+        let path = path.as_ref();
+        let source = std::fs::read_to_string(path).map_err(|err| AssembleError {
+            file: Some(path.to_path_buf()),
+            line: 0,
+            kind: AssembleErrorKind::ReadFailed {
+                path: path.to_path_buf(),
+                message: err.to_string(),
+            },
+            expansion: Vec::new(),
+        })?;
+
+        self.assemble_source(&source, Some(path))
+    }
+
+    fn assemble_source(&self, source: &str, file: Option<&Path>) -> Result<Program, AssembleError> {
+        let mut sections = vec![SectionBuilder::new(".text", false)];
+        let mut current = 0;
+        let mut labels = HashMap::new();
+        let mut constants = HashMap::new();
+        let mut deferred = HashMap::new();
+        let mut globals = BTreeSet::new();
+        let mut pending = Vec::new();
+
+        let lines = Preprocessor::new(&self.include_paths).run(source, file)?;
+        for line in &lines {
+            let error = |kind| line.error(kind);
+
+            let (defined, rest) = split_labels(strip_comment(&line.text));
+            for label in defined {
+                let location = (current, sections[current].offset());
+                if labels.insert(label, location).is_some() {
+                    return Err(error(AssembleErrorKind::DuplicateLabel(label.to_string())));
+                }
+            }
+
+            if rest.is_empty() {
+                continue;
+            }
+
+            let (verb, operands) = split_statement(rest);
+            let verb = verb.to_ascii_lowercase();
+
+            if verb.starts_with('.') {
+                let directive =
+                    parse_directive(&verb, &operands, &|name| constants.get(name).copied())
+                        .map_err(|err| error(err.into()))?;
+                let section = &mut sections[current];
+
+                match directive {
+                    Directive::Section { name, nobits } => {
+                        current = match sections.iter().position(|s| s.name == name) {
+                            Some(index) => index,
+                            None => {
+                                sections.push(SectionBuilder::new(name, nobits));
+                                sections.len() - 1
+                            }
+                        };
+                    }
+                    Directive::Data { width, values } => {
+                        if section.nobits {
+                            return Err(error(AssembleErrorKind::DataInNobits(
+                                section.name.clone(),
+                            )));
+                        }
+
+                        for value in values {
+                            let value = match fold(value, &constants) {
+                                Expr::Number(value) => {
+                                    check_width(value, width).map_err(|err| error(err.into()))?
+                                }
+                                value => {
+                                    pending.push(Pending {
+                                        source: line,
+                                        section: current,
+                                        offset: section.offset(),
+                                        fixup: Fixup::Data { width, value },
+                                    });
+                                    0
+                                }
+                            };
+                            section.emit(&value.to_le_bytes()[..width]).map_err(error)?;
+                        }
+                    }
+                    Directive::Bytes(bytes) => section.emit(&bytes).map_err(error)?,
+                    Directive::Align { alignment, fill } => {
+                        section.align(alignment, fill).map_err(error)?
+                    }
+                    Directive::Space { size, fill } => {
+                        section.space(size.into(), fill).map_err(error)?
+                    }
+                    Directive::Global(names) => {
+                        globals.extend(names.into_iter().map(str::to_string))
+                    }
+                    Directive::Equ { name, value } => {
+                        if labels.contains_key(name) {
+                            return Err(error(AssembleErrorKind::DuplicateLabel(name.to_string())));
+                        }
+
+                        match fold(value, &constants) {
+                            Expr::Number(value) => {
+                                deferred.remove(name);
+                                constants.insert(name, value);
+                            }
+                            value => {
+                                constants.remove(name);
+                                deferred.insert(name, value);
+                            }
+                        }
+                    }
+                    Directive::Ignored => {}
+                }
+
+                continue;
+            }
+
+            let operands = operands
+                .into_iter()
+                .map(|operand| {
+                    Ok(match parse_operand(operand)? {
+                        Operand::Expr(expr) => Operand::Expr(fold(expr, &constants)),
+                        Operand::Offset(expr, reg) => Operand::Offset(fold(expr, &constants), reg),
+                        Operand::Relocation(relocation, expr) => {
+                            Operand::Relocation(relocation, fold(expr, &constants))
+                        }
+                        Operand::OffsetRelocation(relocation, expr, reg) => {
+                            Operand::OffsetRelocation(relocation, fold(expr, &constants), reg)
+                        }
+                        operand => operand,
+                    })
+                })
+                .collect::<Result<Vec<_>, ParseError>>()
+                .map_err(|err| error(err.into()))?;
+
+            let section = &mut sections[current];
+            if section.nobits {
+                return Err(error(AssembleErrorKind::DataInNobits(section.name.clone())));
+            }
+            section.alignment = section.alignment.max(4);
+
+            let args = operands.iter().map(provisional).collect::<Vec<_>>();
+            let words = pseudo::size(&verb, &args, self.xlen);
+            pending.push(Pending {
+                source: line,
+                section: current,
+                offset: section.offset(),
+                fixup: Fixup::Instruction(Statement {
+                    verb,
+                    operands,
+                    words,
+                }),
+            });
+            section.space(4 * words as u64, 0).map_err(error)?;
+        }
+
+        let addresses = self.layout(&sections);
+        let mut symbols = Symbols {
+            labels: labels
+                .iter()
+                .map(|(label, (section, offset))| (label.to_string(), addresses[*section] + offset))
+                .collect(),
+            label_sections: labels
+                .iter()
+                .map(|(label, (section, _))| (label.to_string(), *section))
+                .collect(),
+            constants,
+            deferred,
+            pcrel_hi: HashMap::new(),
+            external_hi: HashSet::new(),
+        };
+
+        // `%pcrel_lo` may refer to a `%pcrel_hi` further down, so these are evaluated up front.
+        for pending in &pending {
+            let Fixup::Instruction(statement) = &pending.fixup else {
+                continue;
+            };
+
+            let pc = addresses[pending.section] + pending.offset;
+            for operand in &statement.operands {
+                let Operand::Relocation(AsmRelocation::PcrelHi, expr) = operand else {
+                    continue;
+                };
+                let error = |kind| pending.source.error(kind);
+
+                if self.relocatable {
+                    let (symbol, _) = symbols.reference(expr).map_err(error)?;
+                    if symbols.is_external(symbol, pending.section) {
+                        symbols.external_hi.insert(pc);
+                        symbols.pcrel_hi.insert(pc, 0);
+                        continue;
+                    }
+                }
+
+                let value = symbols.evaluate(expr).map_err(error)?;
+                symbols.pcrel_hi.insert(pc, (value as u32).wrapping_sub(pc));
+            }
+        }
+
+        let mut words = Vec::new();
+        let mut relocations = Vec::new();
+        for Pending {
+            source,
+            section,
+            offset,
+            fixup,
+        } in pending
+        {
+            let pc = addresses[section] + offset;
+            let error = |kind| source.error(kind);
+            let start = offset as usize;
+
+            match fixup {
+                Fixup::Instruction(mut statement) => {
+                    if self.relocatable {
+                        relocations.extend(
+                            extract_relocations(&mut statement, section, offset, pc, &mut symbols)
+                                .map_err(error)?,
+                        );
+                    }
+
+                    let data = &mut sections[section].data;
+                    let encoded = resolve(&statement, pc, &symbols, self.xlen).map_err(error)?;
+                    for (i, word) in encoded.iter().enumerate() {
+                        data[start + 4 * i..][..4].copy_from_slice(&word.to_le_bytes());
+                    }
+                    words.extend(encoded);
+                }
+                Fixup::Data { width, value } => {
+                    let value = match self.relocatable {
+                        true => match symbols.reference(&value).map_err(error)? {
+                            (None, value) => value,
+                            (Some(symbol), addend) if width == 4 => {
+                                relocations.push(Relocation {
+                                    section,
+                                    offset,
+                                    kind: RelocationKind::Abs32,
+                                    symbol: symbol.to_string(),
+                                    addend,
+                                });
+                                0
+                            }
+                            (Some(_), _) => {
+                                return Err(error(AssembleErrorKind::NotRelocatable(
+                                    value.to_string(),
+                                )))
+                            }
+                        },
+                        false => symbols.evaluate(&value).map_err(error)?,
+                    };
+                    let value = check_width(value, width).map_err(|err| error(err.into()))?;
+                    let data = &mut sections[section].data;
+                    data[start..][..width].copy_from_slice(&value.to_le_bytes()[..width]);
+                }
+            }
+        }
+
+        // Sections of relocatable programs are only placed by the linker
+        let mut labels = symbols.labels;
+        let mut addresses = addresses;
+        if self.relocatable {
+            for (label, address) in &mut labels {
+                *address -= addresses[symbols.label_sections[label]];
+            }
+            addresses.fill(0);
+        }
+
+        Ok(Program {
+            sections: sections
+                .into_iter()
+                .zip(addresses)
+                .map(|(section, address)| Section {
+                    name: section.name,
+                    address,
+                    alignment: section.alignment,
+                    nobits: section.nobits,
+                    size: section.size,
+                    data: section.data,
+                })
+                .collect(),
+            words,
+            labels,
+            label_sections: symbols.label_sections.into_iter().collect(),
+            globals,
+            relocations,
+            xlen: self.xlen,
+        })
+    }
+
+    /// Assign an address to every section.
+    fn layout(&self, sections: &[SectionBuilder]) -> Vec<u32> {
+        let mut next = self.base_address;
+
+        sections
+            .iter()
+            .map(|section| {
+                let address = match self.section_addresses.get(&section.name) {
+                    Some(address) => *address,
+                    None if section.name == ".text" => self.base_address,
+                    None => next.next_multiple_of(section.alignment),
+                };
+                next = next.max(address.wrapping_add(section.offset()));
+                address
+            })
+            .collect()
+    }
+}
+
+/// Evaluate the operands of `statement` placed at `pc` and encode it.
+fn resolve(
+    statement: &Statement,
+    pc: u32,
+    symbols: &Symbols,
+    xlen: Xlen,
+) -> Result<Vec<u32>, AssembleErrorKind> {
+    let label_use = LabelUse::of(&statement.verb);
+
+    let args = statement
+        .operands
+        .iter()
+        .map(|operand| {
+            Ok(match operand {
+                Operand::Register(reg) => AsmArgument::Register(*reg),
+                Operand::FloatRegister(reg) => AsmArgument::FloatRegister(*reg),
+                Operand::Offset(expr, reg) => {
+                    AsmArgument::OffsetImmediate(symbols.evaluate(expr)?, *reg)
+                }
+                Operand::Relocation(relocation, expr) => {
+                    AsmArgument::Immediate(relocate(*relocation, expr, pc, symbols)?)
+                }
+                Operand::OffsetRelocation(relocation, expr, reg) => {
+                    AsmArgument::OffsetImmediate(relocate(*relocation, expr, pc, symbols)?, *reg)
+                }
+                Operand::Expr(Expr::Symbol(keyword)) if label_use == LabelUse::Keyword => {
+                    AsmArgument::Label(keyword)
+                }
+                Operand::Expr(expr) => {
+                    let value = symbols.evaluate(expr)?;
+
+                    // Plain numbers are offsets, like the formatter prints them
+                    if !symbols.is_address(expr) {
+                        return Ok(AsmArgument::Immediate(value));
+                    }
+
+                    let offset = i64::from((value as u32).wrapping_sub(pc) as i32);
+                    AsmArgument::Immediate(match label_use {
+                        LabelUse::Branch if !(-0x1000..0x1000).contains(&offset) => {
+                            return Err(AssembleErrorKind::BranchOutOfRange {
+                                label: expr.to_string(),
+                                offset,
+                            })
+                        }
+                        LabelUse::Jump if !(-0x10_0000..0x10_0000).contains(&offset) => {
+                            return Err(AssembleErrorKind::JumpOutOfRange {
+                                label: expr.to_string(),
+                                offset,
+                            })
+                        }
+                        LabelUse::Branch | LabelUse::Jump => offset,
+                        LabelUse::Absolute | LabelUse::Keyword => value,
+                    })
+                }
+            })
+        })
+        .collect::<Result<Vec<_>, AssembleErrorKind>>()?;
+
+    // The layout reserved the long form for `li` with a symbolic operand.
+    if statement.verb == "li" && statement.words == 2 {
+        return Ok(pseudo::li_long(
+            encode::reg(&args, 0)?,
+            encode::imm(&args, 1)?,
+        )?);
+    }
+
+    Ok(pseudo::expand_words(&statement.verb, &args, pc, xlen)?)
+}
+
+/// Replace the operands of `statement` that are left to the linker with placeholders that encode
+/// as zero, and return the relocations filling them in.
+///
+/// PC-relative references to labels in the same section are resolved as usual.
+fn extract_relocations<'a>(
+    statement: &mut Statement<'a>,
+    section: usize,
+    offset: u32,
+    pc: u32,
+    symbols: &mut Symbols<'a>,
+) -> Result<Vec<Relocation>, AssembleErrorKind> {
+    let label_use = LabelUse::of(&statement.verb);
+    let verb = statement.verb.as_str();
+    let is_store = matches!(verb, "sb" | "sh" | "sw" | "sd");
+    let is_load = matches!(verb, "lb" | "lh" | "lw" | "lbu" | "lhu" | "ld" | "lwu");
+
+    let mut relocations = Vec::new();
+    let mut relocate = |word: u32, kind, symbol: &str, addend| {
+        relocations.push(Relocation {
+            section,
+            offset: offset + 4 * word,
+            kind,
+            symbol: symbol.to_string(),
+            addend,
+        })
+    };
+
+    for operand in &mut statement.operands {
+        match operand {
+            Operand::Register(_) | Operand::FloatRegister(_) => {}
+            Operand::Expr(Expr::Symbol(_)) if label_use == LabelUse::Keyword => {}
+            Operand::Offset(expr, _) => {
+                if symbols.reference(expr)?.0.is_some() {
+                    return Err(AssembleErrorKind::NotRelocatable(expr.to_string()));
+                }
+            }
+            Operand::Relocation(relocation, expr)
+            | Operand::OffsetRelocation(relocation, expr, _) => {
+                let (Some(symbol), addend) = symbols.reference(expr)? else {
+                    continue;
+                };
+
+                let (kind, placeholder) = match relocation {
+                    AsmRelocation::Hi => (RelocationKind::Hi20, 0),
+                    AsmRelocation::Lo if is_store => (RelocationKind::Lo12S, 0),
+                    AsmRelocation::Lo => (RelocationKind::Lo12I, 0),
+                    AsmRelocation::PcrelHi if !symbols.is_external(Some(symbol), section) => {
+                        continue
+                    }
+                    AsmRelocation::PcrelHi => (RelocationKind::PcrelHi20, i64::from(pc)),
+                    // The value of a `%pcrel_hi` left to the linker is already recorded as zero
+                    AsmRelocation::PcrelLo => {
+                        let hi = symbols.evaluate(expr)? as u32;
+                        if !symbols.external_hi.contains(&hi) {
+                            continue;
+                        }
+                        let kind = match is_store {
+                            true => RelocationKind::PcrelLo12S,
+                            false => RelocationKind::PcrelLo12I,
+                        };
+                        relocate(0, kind, symbol, addend);
+                        continue;
+                    }
+                };
+                relocate(0, kind, symbol, addend);
+                *expr = Expr::Number(placeholder);
+            }
+            Operand::Expr(expr) => {
+                let (Some(symbol), addend) = symbols.reference(expr)? else {
+                    continue;
+                };
+                let external = symbols.is_external(Some(symbol), section);
+
+                // Branch and jump operands are offsets, other operands absolute addresses
+                let placeholder = match label_use {
+                    LabelUse::Branch | LabelUse::Jump if !external => continue,
+                    LabelUse::Branch => {
+                        relocate(0, RelocationKind::Branch, symbol, addend);
+                        0
+                    }
+                    LabelUse::Jump => {
+                        relocate(0, RelocationKind::Jal, symbol, addend);
+                        0
+                    }
+                    LabelUse::Absolute if verb == "li" => {
+                        relocate(0, RelocationKind::Hi20, symbol, addend);
+                        relocate(1, RelocationKind::Lo12I, symbol, addend);
+                        0
+                    }
+                    LabelUse::Absolute
+                        if matches!(verb, "call" | "tail" | "la" | "lla")
+                            || is_load
+                            || is_store =>
+                    {
+                        if !external {
+                            continue;
+                        }
+
+                        if matches!(verb, "call" | "tail") {
+                            relocate(0, RelocationKind::Call, symbol, addend);
+                        } else {
+                            let label = symbols.pcrel_label(section, pc);
+                            let lo = match is_store {
+                                true => RelocationKind::PcrelLo12S,
+                                false => RelocationKind::PcrelLo12I,
+                            };
+                            relocate(0, RelocationKind::PcrelHi20, symbol, addend);
+                            relocate(1, lo, &label, 0);
+                        }
+                        i64::from(pc)
+                    }
+                    _ => return Err(AssembleErrorKind::NotRelocatable(expr.to_string())),
+                };
+                *expr = Expr::Number(placeholder);
+            }
+        }
+    }
+
+    Ok(relocations)
+}
+
+/// Evaluate a relocation operator in an instruction placed at `pc`.
+fn relocate(
+    relocation: AsmRelocation,
+    expr: &Expr,
+    pc: u32,
+    symbols: &Symbols,
+) -> Result<i64, AssembleErrorKind> {
+    let value = symbols.evaluate(expr)? as u32;
+    let value = match relocation {
+        AsmRelocation::Hi | AsmRelocation::Lo => value,
+        AsmRelocation::PcrelHi => value.wrapping_sub(pc),
+        AsmRelocation::PcrelLo => *symbols
+            .pcrel_hi
+            .get(&value)
+            .ok_or_else(|| AssembleErrorKind::MissingPcrelHi(expr.to_string()))?,
+    };
+
+    Ok(relocation.apply(value))
+}
+
+/// Assemble `source` placed at `base_address`.
+#[inline]
+pub fn assemble(source: &str, base_address: u32) -> Result<Program, AssembleError> {
+    Assembler::new(base_address).assemble(source)
+}
+
+#[test]
+fn labels() {
+    let program = assemble(
+        "
+        start:
+            li a0, 10
+        loop:   addi a0, a0, -1
+            bnez a0, loop   # count down
+            call end
+            j start
+        end:
+            ret
+        ",
+        0x8000_0000,
+    )
+    .unwrap();
+
+    assert_eq!(program.labels()["loop"], 0x8000_0004);
+    assert_eq!(
+        program.words(),
+        [0x00a00513, 0xfff50513, 0xfe051ee3, 0x00000097, 0x00c080e7, 0xfedff06f, 0x00008067]
+    );
+
+    let far = format!("beqz a0, far{}\nfar: ret", "\nnop".repeat(1024));
+    assert!(matches!(
+        assemble(&far, 0),
+        Err(AssembleError {
+            line: 1,
+            kind: AssembleErrorKind::BranchOutOfRange { .. },
+            ..
+        })
+    ));
+}
+
+#[test]
+fn directives() {
+    let program = assemble(
+        r#"
+            .text
+            la a0, msg
+            .align 4
+        table:
+            .word msg, 0xdeadbeef
+            .data
+        msg: .asciz "hi, #1\n"
+            .balign 4
+            .half -1
+            .bss
+        buf: .zero 16
+        "#,
+        0x1000,
+    )
+    .unwrap();
+
+    let text = program.section(".text").unwrap();
+    assert_eq!(program.labels()["table"], 0x1010);
+    assert_eq!(
+        text.data()[8..24],
+        [0x13, 0, 0, 0, 0x13, 0, 0, 0, 0x18, 0x10, 0, 0, 0xef, 0xbe, 0xad, 0xde]
+    );
+
+    let data = program.section(".data").unwrap();
+    assert_eq!(data.address(), 0x1018);
+    assert_eq!(data.data(), b"hi, #1\n\0\xff\xff");
+
+    let bss = program.section(".bss").unwrap();
+    assert!(bss.is_nobits());
+    assert_eq!((bss.address(), bss.size()), (0x1022, 16));
+    assert!(bss.data().is_empty());
+
+    // Sections without contents are not allocated, others fail before allocating
+    let program = assemble(".bss\n.zero 0xffffffff", 0).unwrap();
+    assert_eq!(program.section(".bss").unwrap().size(), u32::MAX);
+    for source in [
+        ".bss\n.zero 0xffffffff\n.skip 1",
+        ".data\n.byte 1\n.space 0xffffffff",
+    ] {
+        assert!(matches!(
+            assemble(source, 0),
+            Err(AssembleError {
+                line: 3,
+                kind: AssembleErrorKind::SectionOverflow(_),
+                ..
+            })
+        ));
+    }
+
+    assert!(matches!(
+        assemble(".bss\n.word 1", 0),
+        Err(AssembleError {
+            line: 2,
+            kind: AssembleErrorKind::DataInNobits(_),
+            ..
+        })
+    ));
+}
+
+#[test]
+fn expressions() {
+    let program = assemble(
+        "
+            .equ UART_BASE, 0x10000000
+            .set COUNT, 3
+            .equ END, table + 4 * COUNT
+            li a0, UART_BASE + 4*3
+            addi a1, zero, (1 << 11) - 1
+            lw a2, COUNT * 4(a0)
+            j table + 4
+        table:
+            .word END - table, -1
+            .zero COUNT
+        ",
+        0,
+    )
+    .unwrap();
+
+    assert_eq!(
+        program.words(),
+        [0x10000537, 0x00c50513, 0x7ff00593, 0x00c52603, 0x0080006f]
+    );
+    assert_eq!(
+        program.section(".text").unwrap().data()[20..28],
+        [12, 0, 0, 0, 0xff, 0xff, 0xff, 0xff]
+    );
+
+    assert!(matches!(
+        assemble(".equ BIG, 0x1000\naddi a0, a0, BIG", 0),
+        Err(AssembleError {
+            line: 2,
+            kind: AssembleErrorKind::Parse(ParseError::ImmediateOutOfRange { value: 0x1000, .. }),
+            ..
+        })
+    ));
+    for (source, overflow) in [
+        (".equ X, 0x800\naddi a0, a0, X", 0x800),
+        (".equ X, 3000\naddi a0, a0, X", 3000),
+        (".equ X, 0x800\nlw a0, X(a1)", 0x800),
+        (".equ X, 3000\nsw a0, X(a1)", 3000),
+    ] {
+        assert!(
+            matches!(
+                assemble(source, 0),
+                Err(AssembleError {
+                    line: 2,
+                    kind: AssembleErrorKind::Parse(ParseError::ImmediateOutOfRange { value, .. }),
+                    ..
+                }) if value == overflow
+            ),
+            "{source}"
+        );
+    }
+}
+
+#[test]
+fn relocations() {
+    let program = Assembler::new(0x1000)
+        .section_address(".data", 0x1234_5800)
+        .assemble(
+            "
+                lui a0, %hi(value)
+                lw a1, %lo(value)(a0)
+            here:
+                auipc a2, %pcrel_hi(value)
+                addi a2, a2, %pcrel_lo(here)
+                .data
+            value:
+                .word 0
+            ",
+        )
+        .unwrap();
+
+    assert_eq!(
+        program.words(),
+        [0x12346537, 0x80052583, 0x12344617, 0x7f860613]
+    );
+
+    assert!(matches!(
+        assemble("x: addi a0, a0, %pcrel_lo(x)", 0),
+        Err(AssembleError {
+            line: 1,
+            kind: AssembleErrorKind::MissingPcrelHi(_),
+            ..
+        })
+    ));
+}
+
+#[test]
+fn relocatable() {
+    let program = Assembler::new(0x8000_0000)
+        .relocatable(true)
+        .assemble(
+            "
+            start:
+                lui a0, %hi(value)
+                sw zero, %lo(value + 4)(a0)
+                bnez a0, start
+                j end
+            after:
+                .data
+                .word 1, start + 8, after - start
+            ",
+        )
+        .unwrap();
+
+    let relocations = program
+        .relocations()
+        .iter()
+        .map(|relocation| {
+            let Relocation {
+                section,
+                offset,
+                kind,
+                symbol,
+                addend,
+            } = relocation;
+            (*section, *offset, *kind, symbol.as_str(), *addend)
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        relocations,
+        [
+            (0, 0, RelocationKind::Hi20, "value", 0),
+            (0, 4, RelocationKind::Lo12S, "value", 4),
+            (0, 12, RelocationKind::Jal, "end", 0),
+            (1, 4, RelocationKind::Abs32, "start", 8),
+        ]
+    );
+    // The branch to `start` stays within `.text` and is resolved
+    assert_eq!(
+        program.words(),
+        [0x00000537, 0x00052023, 0xfe051ce3, 0x0000006f]
+    );
+    assert_eq!(program.section(".data").unwrap().address(), 0);
+    assert_eq!(program.section(".data").unwrap().data()[8], 16);
+
+    assert!(matches!(
+        Assembler::new(0)
+            .relocatable(true)
+            .assemble(".word end - start"),
+        Err(AssembleError {
+            line: 1,
+            kind: AssembleErrorKind::NotRelocatable(_),
+            ..
+        })
+    ));
+}
+
+#[test]
+fn macros() {
+    let program = assemble(
+        r"
+            .equ COUNT, 2
+            .macro SAVE_REG n, base=sp
+                sw x\n, 4*\n(\base)
+            .endm
+            .macro LOOP
+            loop\@: j loop\@
+            .endm
+
+            .rept COUNT
+                SAVE_REG 1
+            .endr
+            SAVE_REG 5, base=a0
+            .irp reg, 6, 7
+                addi x\reg, x\reg, 1
+            .endr
+            .irpc c, 89
+                li x\c, \c
+            .endr
+            LOOP
+            LOOP
+        ",
+        0,
+    )
+    .unwrap();
+
+    assert_eq!(
+        program.words(),
+        [
+            0x00112223, 0x00112223, 0x00552a23, 0x00130313, 0x00138393, 0x00800413, 0x00900493,
+            0x0000006f, 0x0000006f
+        ]
+    );
+    assert_eq!(program.labels()["loop4"], 0x20);
+
+    let err = assemble(".macro BAD\naddi a0, a0, nope\n.endm\n\nBAD", 0).unwrap_err();
+    assert_eq!(err.line, 2);
+    assert_eq!(
+        err.kind,
+        AssembleErrorKind::UndefinedSymbol("nope".to_string())
+    );
+    assert_eq!(
+        err.expansion,
+        [Expansion {
+            name: "BAD".to_string(),
+            file: None,
+            line: 5
+        }]
+    );
+
+    for source in [
+        ".rept 50000000
+nop
+.endr",
+        ".rept 100000
+.rept 100000
+.endr
+.endr",
+        ".irpc c, 0123456789
+.rept 200000
+nop
+.endr
+.endr",
+    ] {
+        assert!(matches!(
+            assemble(source, 0),
+            Err(AssembleError {
+                kind: AssembleErrorKind::ExpansionSize(_),
+                ..
+            })
+        ));
+    }
+}
+
+#[test]
+fn conditionals_and_includes() {
+    let dir = std::env::temp_dir().join(format!("riscv-asm-lang-{}", std::process::id()));
+    std::fs::create_dir_all(dir.join("inc")).unwrap();
+    std::fs::write(dir.join("inc/regs.inc"), ".equ UART_BASE, 0x10000000\n").unwrap();
+    std::fs::write(dir.join("a.s"), ".include \"b.s\"\n").unwrap();
+    std::fs::write(dir.join("b.s"), "nop\n.include \"a.s\"\n").unwrap();
+
+    let program = Assembler::new(0)
+        .include_path(dir.join("inc"))
+        .assemble(
+            "
+                .include \"regs.inc\"
+                .equ BOARD_X, 1
+                .ifdef BOARD_X
+                    li a0, UART_BASE
+                .else
+                    li a0, 0
+                .endif
+                .ifndef BOARD_Y
+                    .if UART_BASE > 0x20000000
+                        nop
+                    .elseif UART_BASE & 0xfff
+                        nop
+                    .else
+                        addi a1, a0, 4
+                    .endif
+                .endif
+            ",
+        )
+        .unwrap();
+    assert_eq!(program.words(), [0x10000537, 0x00450593]);
+
+    let err = Assembler::new(0)
+        .assemble_file(dir.join("a.s"))
+        .unwrap_err();
+    assert!(matches!(err.kind, AssembleErrorKind::IncludeCycle(_)));
+    assert_eq!((err.file, err.line), (Some(dir.join("b.s")), 2));
+
+    assert!(matches!(
+        assemble("nop\n.if 1\nnop", 0),
+        Err(AssembleError {
+            line: 2,
+            kind: AssembleErrorKind::UnterminatedBlock(_),
+            ..
+        })
+    ));
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn diagnostics() {
+    let source = ".macro BAD\n    addi a0, a0, nope\n.endm\nBAD";
+    let diagnostic = assemble(source, 0).unwrap_err().to_diagnostic(source);
+
+    assert_eq!(diagnostic.columns, 17..21);
+    assert_eq!(
+        diagnostic.render(source),
+        "\
+error: symbol `nope` is not defined
+ --> line 2:18
+  |
+2 |     addi a0, a0, nope
+  |                  ^^^^
+  |
+  = note: in expansion of `BAD` at line 4
+"
+    );
+
+    let source = "addi a0, a0, 0x1000";
+    let diagnostic = assemble(source, 0).unwrap_err().to_diagnostic(source);
+    assert_eq!(diagnostic.columns, 13..19);
+}
@@ -0,0 +1,178 @@
+//! Assembler directives such as `.section`, `.word` or `.align`.
+
+use crate::expr::{parse_expr, Expr};
+use crate::parse::{is_identifier, parse_string, ParseError};
+
+pub(crate) enum Directive<'a> {
+    /// Switch to the section `name`, creating it if needed.
+    Section {
+        name: &'a str,
+        nobits: bool,
+    },
+    /// Little-endian values of `width` bytes each, which may refer to labels.
+    Data {
+        width: usize,
+        values: Vec<Expr<'a>>,
+    },
+    /// Raw bytes, e.g. from `.ascii`.
+    Bytes(Vec<u8>),
+    /// Pad to a multiple of `alignment` bytes.
+    Align {
+        alignment: u32,
+        fill: Option<u8>,
+    },
+    /// `size` bytes of `fill`.
+    Space {
+        size: u32,
+        fill: u8,
+    },
+    Global(Vec<&'a str>),
+    /// `.equ`/`.set`, (re)defining the symbol `name`.
+    Equ {
+        name: &'a str,
+        value: Expr<'a>,
+    },
+    /// Directives that carry no meaning for the output, e.g. `.file` or `.type`.
+    Ignored,
+}
+
+#[inline]
+fn expect_count(verb: &str, operands: &[&str], min: usize, max: usize) -> Result<(), ParseError> {
+    if operands.len() < min || operands.len() > max {
+        return Err(ParseError::OperandCount {
+            verb: verb.to_string(),
+            expected: if operands.len() < min { min } else { max },
+            found: operands.len(),
+        });
+    }
+
+    Ok(())
+}
+
+/// Symbol lookup for expressions that have to be constant while parsing a directive.
+pub(crate) type Constants<'c> = &'c dyn Fn(&str) -> Option<i64>;
+
+#[inline]
+fn unsigned(operand: &str, max: i64, constants: Constants) -> Result<u32, ParseError> {
+    let value = parse_expr(operand)?.evaluate(constants)?;
+    crate::encode::check_range(value, 0, max).map(|value| value as u32)
+}
+
+#[inline]
+fn fill(operand: Option<&&str>, constants: Constants) -> Result<Option<u8>, ParseError> {
+    operand
+        .map(|fill| {
+            let value = parse_expr(fill)?.evaluate(constants)?;
+            crate::encode::check_range(value, -0x80, 0xFF).map(|value| value as u8)
+        })
+        .transpose()
+}
+
+#[inline]
+fn is_nobits(name: &str) -> bool {
+    [".bss", ".sbss", ".tbss"]
+        .iter()
+        .any(|prefix| name == *prefix || name.starts_with(&format!("{prefix}.")))
+}
+
+/// Parse the directive `verb` (including the leading `.`) with its `operands`.
+///
+/// Sizes and fill values have to be constant; `constants` provides the symbols known so far.
+pub(crate) fn parse_directive<'a>(
+    verb: &str,
+    operands: &[&'a str],
+    constants: Constants,
+) -> Result<Directive<'a>, ParseError> {
+    let directive = match verb {
+        ".text" | ".data" | ".bss" => {
+            expect_count(verb, operands, 0, 0)?;
+            let name = match verb {
+                ".text" => ".text",
+                ".data" => ".data",
+                _ => ".bss",
+            };
+            Directive::Section {
+                name,
+                nobits: name == ".bss",
+            }
+        }
+        ".section" => {
+            expect_count(verb, operands, 1, 4)?;
+            let name = operands[0];
+            Directive::Section {
+                name,
+                nobits: is_nobits(name) || operands[1..].contains(&"@nobits"),
+            }
+        }
+        ".byte" | ".half" | ".2byte" | ".short" | ".word" | ".4byte" | ".long" => {
+            let width = match verb {
+                ".byte" => 1,
+                ".half" | ".2byte" | ".short" => 2,
+                _ => 4,
+            };
+            let values = operands
+                .iter()
+                .map(|operand| parse_expr(operand))
+                .collect::<Result<Vec<_>, _>>()?;
+            Directive::Data { width, values }
+        }
+        ".ascii" | ".asciz" | ".string" => {
+            let mut bytes = Vec::new();
+            for operand in operands {
+                bytes.extend(parse_string(operand)?);
+                if verb != ".ascii" {
+                    bytes.push(0);
+                }
+            }
+            Directive::Bytes(bytes)
+        }
+        ".align" | ".p2align" | ".balign" => {
+            expect_count(verb, operands, 1, 3)?;
+            let alignment = if verb == ".balign" {
+                let alignment = unsigned(operands[0], 1 << 16, constants)?;
+                if !alignment.is_power_of_two() {
+                    return Err(ParseError::InvalidOperand(operands[0].to_string()));
+                }
+                alignment
+            } else {
+                1 << unsigned(operands[0], 16, constants)?
+            };
+            Directive::Align {
+                alignment,
+                fill: fill(operands.get(1), constants)?,
+            }
+        }
+        ".zero" => {
+            expect_count(verb, operands, 1, 1)?;
+            Directive::Space {
+                size: unsigned(operands[0], i64::from(u32::MAX), constants)?,
+                fill: 0,
+            }
+        }
+        ".space" | ".skip" => {
+            expect_count(verb, operands, 1, 2)?;
+            Directive::Space {
+                size: unsigned(operands[0], i64::from(u32::MAX), constants)?,
+                fill: fill(operands.get(1), constants)?.unwrap_or(0),
+            }
+        }
+        ".equ" | ".set" => {
+            expect_count(verb, operands, 2, 2)?;
+            if !is_identifier(operands[0]) {
+                return Err(ParseError::InvalidOperand(operands[0].to_string()));
+            }
+            Directive::Equ {
+                name: operands[0],
+                value: parse_expr(operands[1])?,
+            }
+        }
+        ".globl" | ".global" => Directive::Global(operands.to_vec()),
+        ".local" | ".file" | ".ident" | ".type" | ".size" | ".option" | ".attribute" => {
+            Directive::Ignored
+        }
+        _ if verb.starts_with(".cfi_") => Directive::Ignored,
+        _ => return Err(ParseError::UnknownDirective(verb.to_string())),
+    };
+
+    Ok(directive)
+}
@@ -0,0 +1,710 @@
+//! The preprocessing stage of the assembler, expanding macros, repeated blocks, conditional
+//! blocks and included files into plain source lines.
+//!
+//! Block counts and conditions have to be constant. They may refer to `.equ`/`.set` symbols
+//! defined earlier in the source, which are tracked while preprocessing.
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+use super::directive::{parse_directive, Directive};
+use super::{split_labels, AssembleError, AssembleErrorKind};
+use crate::encode::check_range;
+use crate::expr::parse_expr;
+use crate::parse::{is_identifier, parse_string, split_statement, strip_comment, ParseError};
+
+/// A macro invocation, `.rept`/`.irp`/`.irpc` block or `.include` that a line was expanded from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Expansion {
+    /// Name of the macro, or the directive of a block, e.g. `.rept`.
+    pub name: String,
+    /// The file of the invocation, if the source was read from a file.
+    pub file: Option<PathBuf>,
+    /// 1-based line number of the invocation.
+    pub line: usize,
+}
+
+/// A line of source, before preprocessing.
+#[derive(Debug, Clone)]
+struct RawLine {
+    file: Option<Rc<Path>>,
+    /// 1-based line number in `file`.
+    line: usize,
+    text: String,
+}
+
+impl RawLine {
+    #[inline]
+    fn with_text(&self, text: String) -> Self {
+        Self {
+            file: self.file.clone(),
+            line: self.line,
+            text,
+        }
+    }
+
+    #[inline]
+    fn expansion(&self, name: &str) -> Expansion {
+        Expansion {
+            name: name.to_string(),
+            file: self.file.as_deref().map(Path::to_path_buf),
+            line: self.line,
+        }
+    }
+
+    #[inline]
+    fn error(&self, kind: AssembleErrorKind, expansion: &[Expansion]) -> AssembleError {
+        AssembleError {
+            file: self.file.as_deref().map(Path::to_path_buf),
+            line: self.line,
+            kind,
+            expansion: expansion.to_vec(),
+        }
+    }
+}
+
+/// A line of source after preprocessing.
+pub(crate) struct SourceLine {
+    pub text: String,
+    file: Option<Rc<Path>>,
+    /// 1-based line number in the source. For expanded lines this is the line in the body of the
+    /// macro or block.
+    line: usize,
+    /// The expansions this line results from, innermost first.
+    expansion: Vec<Expansion>,
+}
+
+impl SourceLine {
+    #[inline]
+    pub fn error(&self, kind: AssembleErrorKind) -> AssembleError {
+        AssembleError {
+            file: self.file.as_deref().map(Path::to_path_buf),
+            line: self.line,
+            kind,
+            expansion: self.expansion.clone(),
+        }
+    }
+}
+
+type Lines = [RawLine];
+
+/// The state of an `.if` block.
+struct Conditional {
+    /// Index of the `.if` line.
+    start: usize,
+    /// Whether the block itself is assembled.
+    parent: bool,
+    /// Whether the current branch is assembled.
+    active: bool,
+    /// Whether any branch so far was assembled.
+    taken: bool,
+}
+
+struct Parameter {
+    name: String,
+    default: Option<String>,
+    required: bool,
+    /// Takes all remaining arguments.
+    vararg: bool,
+}
+
+struct Macro {
+    parameters: Vec<Parameter>,
+    body: Vec<RawLine>,
+}
+
+/// Whether to continue with the lines after a block, or leave the macro it is part of.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Flow {
+    Continue,
+    Exit,
+}
+
+/// Limit on nested expansions, which also catches recursive macros.
+const MAX_DEPTH: usize = 64;
+
+/// Limit on the total number of lines produced by all expansions, so that large or nested
+/// repetitions fail rather than exhausting memory or running for ages.
+const MAX_EXPANDED_LINES: usize = 1_000_000;
+
+/// The directive of a line, lowercased, ignoring any labels in front of it.
+fn directive_of(RawLine { text, .. }: &RawLine) -> String {
+    let (_, rest) = split_labels(strip_comment(text));
+    let (verb, _) = split_statement(rest);
+    match verb.starts_with('.') {
+        true => verb.to_ascii_lowercase(),
+        false => String::new(),
+    }
+}
+
+/// Index of the line closing the block opened at `lines[start]`.
+fn block_end(lines: &Lines, start: usize, openers: &[&str], closer: &str) -> Option<usize> {
+    let mut depth = 0;
+    for (i, line) in lines.iter().enumerate().skip(start) {
+        let directive = directive_of(line);
+        if openers.contains(&directive.as_str()) {
+            depth += 1;
+        } else if directive == closer {
+            depth -= 1;
+            if depth == 0 {
+                return Some(i);
+            }
+        }
+    }
+
+    None
+}
+
+/// Replace `\name` by the value of the parameter `name`, and `\@` by `counter`. `\()` separates
+/// a parameter from text following it and is removed.
+fn substitute<'v>(
+    text: &str,
+    lookup: impl Fn(&str) -> Option<&'v str>,
+    counter: Option<usize>,
+) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(i) = rest.find('\\') {
+        result.push_str(&rest[..i]);
+        rest = &rest[i + 1..];
+
+        if let Some(tail) = rest.strip_prefix("()") {
+            rest = tail;
+            continue;
+        }
+        if let (Some(tail), Some(counter)) = (rest.strip_prefix('@'), counter) {
+            result.push_str(&counter.to_string());
+            rest = tail;
+            continue;
+        }
+
+        let length = rest
+            .find(|c: char| !c.is_ascii_alphanumeric() && c != '_')
+            .unwrap_or(rest.len());
+        match lookup(&rest[..length]) {
+            Some(value) if length > 0 => {
+                result.push_str(value);
+                rest = &rest[length..];
+            }
+            _ => result.push('\\'),
+        }
+    }
+
+    result.push_str(rest);
+    result
+}
+
+/// Parse the name and parameters following `.macro`.
+fn parse_header(header: &str) -> Result<(String, Vec<Parameter>), ParseError> {
+    let header = header.trim();
+    let (name, rest) = header
+        .split_once(|c: char| c.is_whitespace() || c == ',')
+        .unwrap_or((header, ""));
+    if !is_identifier(name) {
+        return Err(ParseError::InvalidOperand(header.to_string()));
+    }
+
+    let mut parameters = Vec::new();
+    for item in rest.split(',') {
+        // Parameters may also be separated by spaces, as long as they have no default
+        let words = match item.contains('=') {
+            true => vec![item.trim()],
+            false => item.split_whitespace().collect(),
+        };
+
+        for word in words.into_iter().filter(|word| !word.is_empty()) {
+            let (spec, default) = match word.split_once('=') {
+                Some((spec, default)) => (spec.trim(), Some(default.trim().to_string())),
+                None => (word, None),
+            };
+            let (name, qualifier) = spec.split_once(':').unwrap_or((spec, ""));
+
+            let name = name.trim();
+            if !is_identifier(name) || !matches!(qualifier.trim(), "" | "req" | "vararg") {
+                return Err(ParseError::InvalidOperand(word.to_string()));
+            }
+
+            parameters.push(Parameter {
+                name: name.to_string(),
+                default,
+                required: qualifier.trim() == "req",
+                vararg: qualifier.trim() == "vararg",
+            });
+        }
+    }
+
+    Ok((name.to_string(), parameters))
+}
+
+/// Split `source` read from `file` into lines.
+fn lines(source: &str, file: Option<&Path>) -> Vec<RawLine> {
+    let file: Option<Rc<Path>> = file.map(Rc::from);
+    source
+        .lines()
+        .enumerate()
+        .map(|(i, text)| RawLine {
+            file: file.clone(),
+            line: i + 1,
+            text: text.to_string(),
+        })
+        .collect()
+}
+
+pub(crate) struct Preprocessor<'p> {
+    include_paths: &'p [PathBuf],
+    macros: HashMap<String, Macro>,
+    /// Number of macros expanded so far, the value of `\@`.
+    counter: usize,
+    /// Number of lines expanded so far, see [`MAX_EXPANDED_LINES`].
+    expanded: usize,
+    /// `.equ`/`.set` symbols with a constant value.
+    constants: HashMap<String, i64>,
+    /// All labels and symbols defined so far, for `.ifdef`.
+    defined: HashSet<String>,
+    /// Canonical paths of the files currently being included, to detect cycles.
+    including: Vec<PathBuf>,
+    output: Vec<SourceLine>,
+}
+
+impl<'p> Preprocessor<'p> {
+    /// Create a preprocessor looking for included files in the directory of the including file
+    /// and then in `include_paths`.
+    #[inline]
+    pub fn new(include_paths: &'p [PathBuf]) -> Self {
+        Self {
+            include_paths,
+            macros: HashMap::new(),
+            counter: 0,
+            expanded: 0,
+            constants: HashMap::new(),
+            defined: HashSet::new(),
+            including: Vec::new(),
+            output: Vec::new(),
+        }
+    }
+
+    /// Expand all macros, blocks and includes in `source`, which was read from `file`.
+    pub fn run(
+        mut self,
+        source: &str,
+        file: Option<&Path>,
+    ) -> Result<Vec<SourceLine>, AssembleError> {
+        if let Some(path) = file.and_then(|file| file.canonicalize().ok()) {
+            self.including.push(path);
+        }
+
+        self.process(&lines(source, file), &[])?;
+        Ok(self.output)
+    }
+
+    fn process(&mut self, lines: &Lines, expansion: &[Expansion]) -> Result<Flow, AssembleError> {
+        let mut conditionals: Vec<Conditional> = Vec::new();
+
+        let mut i = 0;
+        while i < lines.len() {
+            let line = &lines[i];
+            let error = |kind| line.error(kind, expansion);
+            let parse_error = |err: ParseError| error(err.into());
+
+            let (labels, rest) = split_labels(strip_comment(&line.text));
+            let (verb, operands) = split_statement(rest);
+            let directive = verb.to_ascii_lowercase();
+            let argument = rest[verb.len()..].trim();
+
+            let active = conditionals.last().is_none_or(|top| top.active);
+            match directive.as_str() {
+                ".if" | ".ifdef" | ".ifndef" => {
+                    let active = active && self.condition(&directive, argument).map_err(error)?;
+                    conditionals.push(Conditional {
+                        start: i,
+                        parent: conditionals.last().is_none_or(|top| top.active),
+                        active,
+                        taken: active,
+                    });
+                }
+                ".elseif" | ".else" | ".endif" => {
+                    let Some(top) = conditionals.last_mut() else {
+                        return Err(error(AssembleErrorKind::UnmatchedDirective(directive)));
+                    };
+
+                    match directive.as_str() {
+                        ".elseif" => {
+                            top.active = top.parent
+                                && !top.taken
+                                && self.condition(".if", argument).map_err(error)?;
+                            top.taken |= top.active;
+                        }
+                        ".else" => {
+                            top.active = top.parent && !top.taken;
+                            top.taken = true;
+                        }
+                        _ => {
+                            conditionals.pop();
+                        }
+                    }
+                }
+                _ if !active => {}
+                ".include" => {
+                    self.emit_labels(&labels, line, expansion);
+                    self.include(line, &operands, expansion)?;
+                }
+                ".macro" => {
+                    let end = block_end(lines, i, &[".macro"], ".endm").ok_or_else(|| {
+                        error(AssembleErrorKind::UnterminatedBlock(directive.clone()))
+                    })?;
+                    let (name, parameters) = parse_header(argument).map_err(parse_error)?;
+
+                    if self.macros.contains_key(&name) {
+                        return Err(error(AssembleErrorKind::DuplicateMacro(name)));
+                    }
+                    let body = lines[i + 1..end].to_vec();
+                    self.macros.insert(name, Macro { parameters, body });
+
+                    i = end + 1;
+                    continue;
+                }
+                ".rept" | ".irp" | ".irpc" => {
+                    let end = block_end(lines, i, &[".rept", ".irp", ".irpc"], ".endr")
+                        .ok_or_else(|| {
+                            error(AssembleErrorKind::UnterminatedBlock(directive.clone()))
+                        })?;
+                    self.emit_labels(&labels, line, expansion);
+
+                    let body = &lines[i + 1..end];
+                    let expansion = [&[line.expansion(&directive)], expansion].concat();
+                    if expansion.len() > MAX_DEPTH {
+                        return Err(error(AssembleErrorKind::ExpansionDepth(directive)));
+                    }
+
+                    let flow = match directive.as_str() {
+                        ".rept" => self.rept(&operands, body, &expansion, &error)?,
+                        _ => self.irp(&directive, &operands, body, &expansion, &error)?,
+                    };
+                    if flow == Flow::Exit {
+                        return Ok(Flow::Exit);
+                    }
+
+                    i = end + 1;
+                    continue;
+                }
+                ".endm" | ".endr" => {
+                    return Err(error(AssembleErrorKind::UnmatchedDirective(directive)));
+                }
+                // Only macros can be left early, not blocks or included files
+                ".exitm" if expansion.iter().all(|outer| outer.name.starts_with('.')) => {
+                    return Err(error(AssembleErrorKind::UnmatchedDirective(directive)));
+                }
+                ".exitm" => return Ok(Flow::Exit),
+                ".purgem" => {
+                    for name in operands {
+                        if self.macros.remove(name).is_none() {
+                            return Err(parse_error(ParseError::UnknownInstruction(
+                                name.to_string(),
+                            )));
+                        }
+                    }
+                }
+                ".equ" | ".set" => {
+                    let constants = |name: &str| self.constants.get(name).copied();
+                    if let Ok(Directive::Equ { name, value }) =
+                        parse_directive(&directive, &operands, &constants)
+                    {
+                        match value.evaluate(&constants) {
+                            Ok(value) => self.constants.insert(name.to_string(), value),
+                            Err(_) => self.constants.remove(name),
+                        };
+                        self.defined.insert(name.to_string());
+                    }
+                    self.emit(line, expansion);
+                }
+                _ if self.macros.contains_key(verb) => {
+                    self.emit_labels(&labels, line, expansion);
+                    let body = self.invoke(verb, &operands).map_err(error)?;
+                    self.expand(body.len(), verb).map_err(error)?;
+
+                    let expansion = [&[line.expansion(verb)], expansion].concat();
+                    if expansion.len() > MAX_DEPTH {
+                        return Err(error(AssembleErrorKind::ExpansionDepth(verb.to_string())));
+                    }
+
+                    // `.exitm` only leaves this macro
+                    self.process(&body, &expansion)?;
+                }
+                _ => self.emit(line, expansion),
+            }
+
+            i += 1;
+        }
+
+        match conditionals.first() {
+            Some(conditional) => Err(lines[conditional.start].error(
+                AssembleErrorKind::UnterminatedBlock(directive_of(&lines[conditional.start])),
+                expansion,
+            )),
+            None => Ok(Flow::Continue),
+        }
+    }
+
+    /// Evaluate the condition of `.if expr`, `.ifdef symbol` or `.ifndef symbol`.
+    fn condition(&self, directive: &str, argument: &str) -> Result<bool, AssembleErrorKind> {
+        match directive {
+            ".if" => {
+                let value =
+                    parse_expr(argument)?.evaluate(&|name| self.constants.get(name).copied())?;
+                Ok(value != 0)
+            }
+            _ if !is_identifier(argument) => {
+                Err(ParseError::InvalidOperand(argument.to_string()).into())
+            }
+            ".ifdef" => Ok(self.defined.contains(argument)),
+            _ => Ok(!self.defined.contains(argument)),
+        }
+    }
+
+    /// `.include "file"`
+    fn include(
+        &mut self,
+        line: &RawLine,
+        operands: &[&str],
+        expansion: &[Expansion],
+    ) -> Result<(), AssembleError> {
+        let error = |kind| line.error(kind, expansion);
+
+        let [name] = operands else {
+            return Err(error(AssembleErrorKind::Parse(ParseError::OperandCount {
+                verb: ".include".to_string(),
+                expected: 1,
+                found: operands.len(),
+            })));
+        };
+        let name = name
+            .strip_prefix('"')
+            .and_then(|_| String::from_utf8(parse_string(name).ok()?).ok())
+            .ok_or_else(|| error(ParseError::InvalidString(name.to_string()).into()))?;
+
+        // Relative to the including file first, then the search path
+        let directory = line
+            .file
+            .as_deref()
+            .and_then(Path::parent)
+            .unwrap_or(Path::new(""));
+        let path = std::iter::once(directory)
+            .chain(self.include_paths.iter().map(PathBuf::as_path))
+            .map(|directory| directory.join(&name))
+            .find(|path| path.is_file())
+            .ok_or_else(|| error(AssembleErrorKind::IncludeNotFound(name.clone())))?;
+
+        let canonical = path.canonicalize().unwrap_or_else(|_| path.clone());
+        if self.including.contains(&canonical) {
+            return Err(error(AssembleErrorKind::IncludeCycle(path)));
+        }
+        let source = std::fs::read_to_string(&path).map_err(|err| {
+            error(AssembleErrorKind::ReadFailed {
+                path: path.clone(),
+                message: err.to_string(),
+            })
+        })?;
+
+        let expansion = [&[line.expansion(".include")], expansion].concat();
+        if expansion.len() > MAX_DEPTH {
+            return Err(error(AssembleErrorKind::ExpansionDepth(name)));
+        }
+
+        self.including.push(canonical);
+        self.process(&lines(&source, Some(&path)), &expansion)?;
+        self.including.pop();
+
+        Ok(())
+    }
+
+    #[inline]
+    fn emit(&mut self, line: &RawLine, expansion: &[Expansion]) {
+        let (labels, _) = split_labels(strip_comment(&line.text));
+        self.defined.extend(labels.into_iter().map(str::to_string));
+
+        self.output.push(SourceLine {
+            text: line.text.clone(),
+            file: line.file.clone(),
+            line: line.line,
+            expansion: expansion.to_vec(),
+        });
+    }
+
+    /// Keep the labels in front of a line that is replaced by an expansion.
+    fn emit_labels(&mut self, labels: &[&str], line: &RawLine, expansion: &[Expansion]) {
+        if !labels.is_empty() {
+            self.emit(&line.with_text(format!("{}:", labels.join(":"))), expansion);
+        }
+    }
+
+    /// The body of the macro `name` with the `arguments` substituted.
+    fn invoke(
+        &mut self,
+        name: &str,
+        arguments: &[&str],
+    ) -> Result<Vec<RawLine>, AssembleErrorKind> {
+        let definition = &self.macros[name];
+        let parameters = &definition.parameters;
+        let mut values: Vec<Option<String>> = vec![None; parameters.len()];
+
+        let mut position = 0;
+        for (i, argument) in arguments.iter().enumerate() {
+            // `name=value`, but not a comparison such as `a == b`
+            let keyword = argument
+                .split_once('=')
+                .filter(|(key, value)| is_identifier(key.trim()) && !value.starts_with('='));
+
+            let index = match keyword {
+                Some((key, value)) => {
+                    let index = parameters
+                        .iter()
+                        .position(|parameter| parameter.name == key.trim())
+                        .ok_or_else(|| AssembleErrorKind::UnknownMacroParameter {
+                            name: name.to_string(),
+                            parameter: key.trim().to_string(),
+                        })?;
+                    values[index] = Some(value.trim().to_string());
+                    continue;
+                }
+                None => position,
+            };
+
+            let Some(parameter) = parameters.get(index) else {
+                return Err(AssembleErrorKind::TooManyMacroArguments(name.to_string()));
+            };
+            if parameter.vararg {
+                values[index] = Some(arguments[i..].join(", "));
+                break;
+            }
+
+            values[index] = Some(argument.to_string());
+            position += 1;
+        }
+
+        for (parameter, value) in parameters.iter().zip(&mut values) {
+            if value.as_deref().is_none_or(str::is_empty) {
+                if parameter.required {
+                    return Err(AssembleErrorKind::MissingMacroArgument {
+                        name: name.to_string(),
+                        parameter: parameter.name.clone(),
+                    });
+                }
+                if value.is_none() {
+                    *value = parameter.default.clone();
+                }
+            }
+        }
+
+        let lookup = |key: &str| {
+            parameters
+                .iter()
+                .position(|parameter| parameter.name == key)
+                .map(|index| values[index].as_deref().unwrap_or(""))
+        };
+        let counter = self.counter;
+        let body = definition
+            .body
+            .iter()
+            .map(|line| line.with_text(substitute(&line.text, lookup, Some(counter))))
+            .collect();
+
+        self.counter += 1;
+        Ok(body)
+    }
+
+    /// Count `lines` more expanded lines of `name`, at least one so that empty bodies count too.
+    fn expand(&mut self, lines: usize, name: &str) -> Result<(), AssembleErrorKind> {
+        self.expanded += lines.max(1);
+        if self.expanded > MAX_EXPANDED_LINES {
+            return Err(AssembleErrorKind::ExpansionSize(name.to_string()));
+        }
+        Ok(())
+    }
+
+    /// `.rept count`
+    fn rept(
+        &mut self,
+        operands: &[&str],
+        body: &Lines,
+        expansion: &[Expansion],
+        error: &dyn Fn(AssembleErrorKind) -> AssembleError,
+    ) -> Result<Flow, AssembleError> {
+        let [count] = operands else {
+            return Err(error(
+                ParseError::OperandCount {
+                    verb: ".rept".to_string(),
+                    expected: 1,
+                    found: operands.len(),
+                }
+                .into(),
+            ));
+        };
+        let count = parse_expr(count)
+            .and_then(|count| count.evaluate(&|name| self.constants.get(name).copied()))
+            .and_then(|count| check_range(count, 0, u32::MAX.into()))
+            .map_err(|err| error(err.into()))?;
+
+        for _ in 0..count {
+            self.expand(body.len(), ".rept").map_err(error)?;
+            if self.process(body, expansion)? == Flow::Exit {
+                return Ok(Flow::Exit);
+            }
+        }
+
+        Ok(Flow::Continue)
+    }
+
+    /// `.irp name, values...` and `.irpc name, characters`
+    fn irp(
+        &mut self,
+        directive: &str,
+        operands: &[&str],
+        body: &Lines,
+        expansion: &[Expansion],
+        error: &dyn Fn(AssembleErrorKind) -> AssembleError,
+    ) -> Result<Flow, AssembleError> {
+        let Some((name, values)) = operands.split_first() else {
+            return Err(error(
+                ParseError::OperandCount {
+                    verb: directive.to_string(),
+                    expected: 1,
+                    found: 0,
+                }
+                .into(),
+            ));
+        };
+        if !is_identifier(name) {
+            return Err(error(ParseError::InvalidOperand(name.to_string()).into()));
+        }
+
+        let mut values = match directive {
+            ".irpc" => values
+                .concat()
+                .chars()
+                .map(String::from)
+                .collect::<Vec<_>>(),
+            _ => values.iter().map(|value| value.to_string()).collect(),
+        };
+        // Without values, the body is assembled once with an empty value
+        if values.is_empty() {
+            values.push(String::new());
+        }
+
+        for value in values {
+            self.expand(body.len(), directive).map_err(error)?;
+            let lookup = |key: &str| (key == *name).then_some(value.as_str());
+            let lines = body
+                .iter()
+                .map(|line| line.with_text(substitute(&line.text, lookup, None)))
+                .collect::<Vec<_>>();
+
+            if self.process(&lines, expansion)? == Flow::Exit {
+                return Ok(Flow::Exit);
+            }
+        }
+
+        Ok(Flow::Continue)
+    }
+}
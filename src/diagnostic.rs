@@ -0,0 +1,205 @@
+//! Diagnostics pointing at a span of source, rendered as annotated snippets.
+//!
+//! ```text
+//! error: symbol `nope` is not defined
+//!  --> boot.s:2:18
+//!   |
+//! 2 |     addi a0, a0, nope
+//!   |                  ^^^^
+//!   |
+//!   = note: in expansion of `BAD` at line 5
+//! ```
+
+use std::fmt::{Display, Write};
+use std::ops::Range;
+use std::path::PathBuf;
+
+/// Tabs are rendered as this many spaces, so carets line up regardless of the terminal.
+const TAB_WIDTH: usize = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Error,
+    Warning,
+    Note,
+    Help,
+}
+
+impl Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Error => "error",
+            Self::Warning => "warning",
+            Self::Note => "note",
+            Self::Help => "help",
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub message: String,
+    pub file: Option<PathBuf>,
+    /// 1-based line number, or `0` if the diagnostic does not point at a line.
+    pub line: usize,
+    /// Byte range within the line that the diagnostic points at.
+    pub columns: Range<usize>,
+    /// Short text shown next to the highlighted span.
+    pub label: Option<String>,
+    pub notes: Vec<String>,
+    pub help: Option<String>,
+}
+
+impl Diagnostic {
+    #[inline]
+    pub fn new(severity: Severity, message: impl Into<String>) -> Self {
+        Self {
+            severity,
+            message: message.into(),
+            file: None,
+            line: 0,
+            columns: 0..0,
+            label: None,
+            notes: Vec::new(),
+            help: None,
+        }
+    }
+
+    #[inline]
+    pub fn error(message: impl Into<String>) -> Self {
+        Self::new(Severity::Error, message)
+    }
+
+    #[inline]
+    pub fn warning(message: impl Into<String>) -> Self {
+        Self::new(Severity::Warning, message)
+    }
+
+    /// Point at `columns` of the 1-based `line`.
+    #[inline]
+    pub fn at(mut self, file: Option<PathBuf>, line: usize, columns: Range<usize>) -> Self {
+        self.file = file;
+        self.line = line;
+        self.columns = columns;
+        self
+    }
+
+    #[inline]
+    pub fn with_label(mut self, label: impl Into<String>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+
+    #[inline]
+    pub fn with_note(mut self, note: impl Into<String>) -> Self {
+        self.notes.push(note.into());
+        self
+    }
+
+    #[inline]
+    pub fn with_help(mut self, help: impl Into<String>) -> Self {
+        self.help = Some(help.into());
+        self
+    }
+
+    /// Render the diagnostic with a snippet of `source`, the contents of the file it points at.
+    ///
+    /// Without a matching line in `source`, only the message, location and notes are rendered.
+    pub fn render(&self, source: &str) -> String {
+        let mut out = String::new();
+        self.write(&mut out, source).unwrap();
+        out
+    }
+
+    fn write(&self, out: &mut String, source: &str) -> std::fmt::Result {
+        writeln!(out, "{}: {}", self.severity, self.message)?;
+
+        let text = self
+            .line
+            .checked_sub(1)
+            .and_then(|index| source.lines().nth(index));
+        let gutter = " ".repeat(self.line.to_string().len());
+
+        let column = text.map_or(self.columns.start, |text| {
+            display_width(text, self.columns.start)
+        });
+        match (&self.file, self.line) {
+            (_, 0) => {}
+            (Some(file), line) => {
+                writeln!(out, "{gutter}--> {}:{line}:{}", file.display(), column + 1)?
+            }
+            (None, line) => writeln!(out, "{gutter}--> line {line}:{}", column + 1)?,
+        }
+
+        if let Some(text) = text {
+            let end = display_width(text, self.columns.end.max(self.columns.start));
+            let carets = "^".repeat((end - column).max(1));
+
+            writeln!(out, "{gutter} |")?;
+            writeln!(out, "{} | {}", self.line, expand_tabs(text).trim_end())?;
+            write!(out, "{gutter} | {}{carets}", " ".repeat(column))?;
+            match &self.label {
+                Some(label) => writeln!(out, " {label}")?,
+                None => writeln!(out)?,
+            }
+        }
+
+        if !self.notes.is_empty() || self.help.is_some() {
+            writeln!(out, "{gutter} |")?;
+        }
+        for note in &self.notes {
+            writeln!(out, "{gutter} = note: {note}")?;
+        }
+        if let Some(help) = &self.help {
+            writeln!(out, "{gutter} = help: {help}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Display for Diagnostic {
+    /// The message and location, without a snippet.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut out = String::new();
+        self.write(&mut out, "")?;
+        f.write_str(out.trim_end())
+    }
+}
+
+impl std::error::Error for Diagnostic {}
+
+/// Width of `text[..index]` as rendered, with tabs expanded.
+fn display_width(text: &str, index: usize) -> usize {
+    text.char_indices()
+        .take_while(|(i, _)| *i < index)
+        .map(|(_, c)| if c == '\t' { TAB_WIDTH } else { 1 })
+        .sum()
+}
+
+#[inline]
+fn expand_tabs(text: &str) -> String {
+    text.replace('\t', &" ".repeat(TAB_WIDTH))
+}
+
+#[test]
+fn render() {
+    let diagnostic = Diagnostic::error("symbol `nope` is not defined")
+        .at(Some("boot.s".into()), 2, 14..18)
+        .with_label("not defined")
+        .with_note("in expansion of `BAD` at line 5");
+
+    assert_eq!(
+        diagnostic.render("BAD:\n\taddi a0, a0, nope # comment\n"),
+        "\
+error: symbol `nope` is not defined
+ --> boot.s:2:18
+  |
+2 |     addi a0, a0, nope # comment
+  |                  ^^^^ not defined
+  |
+  = note: in expansion of `BAD` at line 5
+"
+    );
+}
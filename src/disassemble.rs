@@ -0,0 +1,307 @@
+//! Linear-sweep disassembly of little-endian byte buffers.
+//!
+//! Instructions are decoded one after another from the beginning of the buffer, taking 2 bytes for
+//! compressed instructions and 4 otherwise as told by the lowest bits of each. Bytes that do not
+//! decode are kept as data, so a listing covers every byte of the input.
+
+use std::fmt::{Display, Write};
+
+use crate::encode::{OP_AUIPC, OP_IMM, OP_JALR, OP_LOAD, OP_LUI, OP_STORE};
+use crate::instruction::Instruction;
+use crate::symbols::SymbolTable;
+use crate::{AsmFormatOptions, AsmInstruction, Xlen};
+
+/// A single instruction of a disassembled buffer.
+#[derive(Debug, Clone)]
+pub struct Entry {
+    pub address: u32,
+    /// The raw little-endian instruction, zero-extended for compressed instructions. A trailing
+    /// partial instruction is padded with zeroes.
+    pub word: u32,
+    /// Number of bytes of the buffer this entry covers: `2` for compressed instructions, `4` for
+    /// others, and less for a trailing partial instruction.
+    pub len: usize,
+    /// The decoded instruction, or `None` for undecodable and partial instructions.
+    pub instruction: Option<Instruction>,
+}
+
+impl Entry {
+    /// Format the instruction, or the entry as data if it has none. Branch and jump targets are
+    /// shown as absolute addresses.
+    pub fn display<'a>(&'a self, options: &'a AsmFormatOptions) -> impl Display + 'a {
+        EntryDisplay {
+            entry: self,
+            options,
+            symbols: None,
+        }
+    }
+
+    /// The 32-bit form of the instruction, expanding compressed instructions.
+    fn expanded(&self) -> Option<u32> {
+        let instruction = self.instruction.as_ref()?;
+        Some(instruction.expanded().unwrap_or(self.word))
+    }
+
+    /// The register and value written by a `lui` or `auipc`.
+    fn upper(&self) -> Option<(u32, u32)> {
+        let word = self.expanded()?;
+        let rd = (word >> 7) & 0x1f;
+        let value = word & 0xffff_f000;
+
+        match word & 0x7f {
+            _ if rd == 0 => None,
+            OP_LUI => Some((rd, value)),
+            OP_AUIPC => Some((rd, self.address.wrapping_add(value))),
+            _ => None,
+        }
+    }
+
+    /// The address formed by adding the 12-bit immediate of an `addi`, `jalr`, load or store to
+    /// `value` in the register `reg`.
+    fn lower(&self, (reg, value): (u32, u32)) -> Option<u32> {
+        let word = self.expanded()?;
+        let rs1 = (word >> 15) & 0x1f;
+        let i_imm = (word as i32) >> 20;
+        let s_imm = ((word as i32) >> 25 << 5) | ((word >> 7) & 0x1f) as i32;
+
+        let imm = match word & 0x7f {
+            _ if rs1 != reg => return None,
+            OP_IMM if (word >> 12) & 0b111 == 0 => i_imm,
+            OP_LOAD | OP_JALR => i_imm,
+            OP_STORE => s_imm,
+            _ => return None,
+        };
+        Some(value.wrapping_add(imm as u32))
+    }
+}
+
+struct EntryDisplay<'a> {
+    entry: &'a Entry,
+    options: &'a AsmFormatOptions,
+    symbols: Option<&'a SymbolTable>,
+}
+
+impl Display for EntryDisplay<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let Entry {
+            word,
+            len,
+            instruction,
+            ..
+        } = self.entry;
+
+        match instruction {
+            // Instructions without arguments still end in the verb spacing
+            Some(instruction) => {
+                let display = instruction
+                    .display(self.options.clone())
+                    .at(self.entry.address);
+                let text = match self.symbols {
+                    Some(symbols) => display.with_symbols(symbols).to_string(),
+                    None => display.to_string(),
+                };
+                f.write_str(text.trim_end())
+            }
+            None if *len == 4 => write!(f, ".word{}0x{word:08x}", self.options.verb_arg_spacing),
+            None if *len == 2 && word & 0b11 != 0b11 => {
+                write!(f, ".half{}0x{word:04x}", self.options.verb_arg_spacing)
+            }
+            None => {
+                f.write_str(".byte")?;
+                f.write_str(self.options.verb_arg_spacing)?;
+                for (i, byte) in word.to_le_bytes()[..*len].iter().enumerate() {
+                    if i != 0 {
+                        f.write_str(",")?;
+                        f.write_str(self.options.arg_spacing)?;
+                    }
+                    write!(f, "0x{byte:02x}")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Iterator over the instructions of a buffer, created by [`disassemble`].
+#[derive(Debug, Clone)]
+pub struct Disassemble<'b> {
+    bytes: &'b [u8],
+    address: u32,
+    xlen: Xlen,
+}
+
+impl Disassemble<'_> {
+    /// Decode the instructions as those of a core with the register width `xlen`, RV32 by
+    /// default.
+    #[inline]
+    pub fn xlen(mut self, xlen: Xlen) -> Self {
+        self.xlen = xlen;
+        self
+    }
+}
+
+impl Iterator for Disassemble<'_> {
+    type Item = Entry;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.bytes.is_empty() {
+            return None;
+        }
+
+        let len = Instruction::len(self.bytes[0]).min(self.bytes.len());
+        let mut raw = [0; 4];
+        raw[..len].copy_from_slice(&self.bytes[..len]);
+        let word = u32::from_le_bytes(raw);
+
+        let entry = Entry {
+            address: self.address,
+            word,
+            len,
+            instruction: match len {
+                4 => Instruction::decode(word, self.xlen),
+                2 if word & 0b11 != 0b11 => Instruction::decode_compressed(word as u16, self.xlen),
+                _ => None,
+            },
+        };
+
+        self.bytes = &self.bytes[len..];
+        self.address = self.address.wrapping_add(len as u32);
+        Some(entry)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (
+            self.bytes.len().div_ceil(4),
+            Some(self.bytes.len().div_ceil(2)),
+        )
+    }
+}
+
+/// Disassemble `bytes`, the first of which is placed at `base_address`.
+#[inline]
+pub fn disassemble(bytes: &[u8], base_address: u32) -> Disassemble<'_> {
+    Disassemble {
+        bytes,
+        address: base_address,
+        xlen: Xlen::Rv32,
+    }
+}
+
+/// Formats disassembled buffers as listings of address, raw word and instruction.
+///
+/// ```text
+/// 80000010:  00a58593  addi a1, a1, 10
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Listing<'s> {
+    options: AsmFormatOptions,
+    symbols: Option<&'s SymbolTable>,
+}
+
+impl<'s> Listing<'s> {
+    #[inline]
+    pub fn new(options: AsmFormatOptions) -> Self {
+        Self {
+            options,
+            symbols: None,
+        }
+    }
+
+    /// Name addresses after `symbols`: a `<symbol>:` header is written where a symbol starts,
+    /// branch and jump targets are named, and the address formed by a `lui` or `auipc` with the
+    /// instruction after it is added as a comment.
+    #[inline]
+    pub fn symbols(mut self, symbols: &'s SymbolTable) -> Self {
+        self.symbols = Some(symbols);
+        self
+    }
+
+    /// Write one line for every instruction of `bytes`, placed at `base_address`.
+    pub fn write<W: Write>(&self, f: &mut W, bytes: &[u8], base_address: u32) -> std::fmt::Result {
+        let mut upper = None;
+
+        for entry in disassemble(bytes, base_address).xlen(self.options.xlen) {
+            if let Some(symbol) = self.symbols.and_then(|symbols| symbols.at(entry.address)) {
+                writeln!(f, "\n{:08x} <{}>:", entry.address, symbol.name)?;
+            }
+
+            let raw = format!("{:0width$x}", entry.word, width = 2 * entry.len);
+            let display = EntryDisplay {
+                entry: &entry,
+                options: &self.options,
+                symbols: self.symbols,
+            };
+            write!(f, "{:08x}:  {raw:<8}  {display}", entry.address)?;
+
+            let address = upper.and_then(|upper| entry.lower(upper));
+            if let (Some(symbols), Some(address)) = (self.symbols, address) {
+                write!(f, "  # {}", symbols.describe(address))?;
+            }
+            writeln!(f)?;
+
+            upper = entry.upper();
+        }
+
+        Ok(())
+    }
+
+    #[inline]
+    pub fn render(&self, bytes: &[u8], base_address: u32) -> String {
+        let mut out = String::new();
+        self.write(&mut out, bytes, base_address).unwrap();
+        out
+    }
+}
+
+#[test]
+fn listing() {
+    let options = AsmFormatOptions::default()
+        .verb_arg_spacing(" ")
+        .arg_spacing(" ")
+        .immediate_format(crate::AsmImmediateFormat::SignedDecimal);
+    let bytes = [
+        0x93, 0x85, 0xa5, 0x00, 0xe3, 0x1e, 0x05, 0xfe, 0xff, 0xff, 0xff, 0xff, 0x13, 0x00,
+    ];
+
+    assert_eq!(
+        Listing::new(options.clone()).render(&bytes, 0x8000_0010),
+        "\
+80000010:  00a58593  addi a1, a1, 10
+80000014:  fe051ee3  bnez a0, 80000010
+80000018:  ffffffff  .word 0xffffffff
+8000001c:  0013      .byte 0x13, 0x00
+"
+    );
+
+    // c.li a0, 1; addi a1, a1, 10; c.bnez a0, -6; the illegal instruction
+    let bytes = [0x05, 0x45, 0x93, 0x85, 0xa5, 0x00, 0x6d, 0xfd, 0x00, 0x00];
+    assert_eq!(
+        Listing::new(options.clone()).render(&bytes, 0x8000_0010),
+        "\
+80000010:  4505      c.li a0, 1
+80000012:  00a58593  addi a1, a1, 10
+80000016:  fd6d      c.bnez a0, 80000010
+80000018:  0000      .half 0x0000
+"
+    );
+
+    let symbols = SymbolTable::from_nm("80000000 T _start\n80000010 T loop\n80000800 D message");
+    // auipc a0, 0x1; addi a0, a0, -0x800; j loop; j _start+0x4
+    let bytes = [
+        0x17, 0x15, 0x00, 0x00, 0x13, 0x05, 0x05, 0x80, 0x6f, 0x00, 0x80, 0x00, 0x6f, 0xf0, 0x9f,
+        0xff,
+    ];
+    assert_eq!(
+        Listing::new(options)
+            .symbols(&symbols)
+            .render(&bytes, 0x8000_0000),
+        "
+80000000 <_start>:
+80000000:  00001517  auipc a0, 1
+80000004:  80050513  addi a0, a0, -2048  # 80000800 <message>
+80000008:  0080006f  j loop
+8000000c:  ff9ff06f  j 80000004 <_start+0x4>
+"
+    );
+}
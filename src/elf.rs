@@ -0,0 +1,715 @@
+//! Reading and writing 32-bit little-endian RISC-V ELF files.
+//!
+//! Only the section headers and the symbol table are read; program headers are ignored, so
+//! both executables and relocatable objects can be read. Assembled programs are written as
+//! relocatable objects, to be linked with other objects by a regular linker.
+
+use std::collections::{BTreeSet, HashMap};
+use std::fmt::Display;
+
+use crate::assembler::{Program, RelocationKind};
+use crate::disassemble::Listing;
+use crate::symbols::{Symbol, SymbolKind, SymbolTable};
+use crate::{AsmFormatOptions, Xlen};
+
+pub(crate) const EM_RISCV: u16 = 243;
+pub(crate) const ELFCLASS32: u8 = 1;
+pub(crate) const ELFCLASS64: u8 = 2;
+pub(crate) const ELFDATA2LSB: u8 = 1;
+pub(crate) const EV_CURRENT: u8 = 1;
+pub(crate) const ET_REL: u16 = 1;
+
+pub(crate) const SHT_PROGBITS: u32 = 1;
+pub(crate) const SHT_SYMTAB: u32 = 2;
+pub(crate) const SHT_STRTAB: u32 = 3;
+pub(crate) const SHT_RELA: u32 = 4;
+pub(crate) const SHT_NOBITS: u32 = 8;
+
+pub(crate) const SHF_WRITE: u32 = 0x1;
+pub(crate) const SHF_ALLOC: u32 = 0x2;
+pub(crate) const SHF_EXECINSTR: u32 = 0x4;
+pub(crate) const SHF_INFO_LINK: u32 = 0x40;
+
+pub(crate) const STT_OBJECT: u8 = 1;
+pub(crate) const STT_FUNC: u8 = 2;
+pub(crate) const STT_SECTION: u8 = 3;
+pub(crate) const STT_FILE: u8 = 4;
+pub(crate) const STB_LOCAL: u8 = 0;
+pub(crate) const STB_GLOBAL: u8 = 1;
+
+pub(crate) const R_RISCV_32: u32 = 1;
+pub(crate) const R_RISCV_BRANCH: u32 = 16;
+pub(crate) const R_RISCV_JAL: u32 = 17;
+pub(crate) const R_RISCV_CALL: u32 = 18;
+pub(crate) const R_RISCV_PCREL_HI20: u32 = 23;
+pub(crate) const R_RISCV_PCREL_LO12_I: u32 = 24;
+pub(crate) const R_RISCV_PCREL_LO12_S: u32 = 25;
+pub(crate) const R_RISCV_HI20: u32 = 26;
+pub(crate) const R_RISCV_LO12_I: u32 = 27;
+pub(crate) const R_RISCV_LO12_S: u32 = 28;
+
+const HEADER_SIZE: usize = 52;
+const SECTION_HEADER_SIZE: usize = 40;
+const SYMBOL_SIZE: usize = 16;
+const RELA_SIZE: usize = 12;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ElfError {
+    NotElf,
+    /// The file is not a 32-bit ELF file, e.g. an RV64 one, or an RV64 program was to be written.
+    UnsupportedClass(u8),
+    /// The file is big-endian.
+    UnsupportedEncoding(u8),
+    /// The file is not for RISC-V.
+    WrongMachine(u16),
+    /// A header, table or section extends beyond the end of the file.
+    Truncated,
+    InvalidName(u32),
+}
+
+impl Display for ElfError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NotElf => write!(f, "not an ELF file"),
+            Self::UnsupportedClass(class) => {
+                write!(f, "unsupported ELF class {class}, expected 32-bit")
+            }
+            Self::UnsupportedEncoding(encoding) => {
+                write!(
+                    f,
+                    "unsupported data encoding {encoding}, expected little-endian"
+                )
+            }
+            Self::WrongMachine(machine) => {
+                write!(f, "machine {machine} is not RISC-V ({EM_RISCV})")
+            }
+            Self::Truncated => write!(f, "file is truncated"),
+            Self::InvalidName(offset) => write!(f, "invalid name at string table offset {offset}"),
+        }
+    }
+}
+
+impl std::error::Error for ElfError {}
+
+/// Little-endian fields at fixed offsets of a record.
+#[derive(Clone, Copy)]
+struct Record<'a>(&'a [u8]);
+
+impl<'a> Record<'a> {
+    #[inline]
+    fn u8(self, offset: usize) -> u8 {
+        self.0[offset]
+    }
+
+    #[inline]
+    fn u16(self, offset: usize) -> u16 {
+        u16::from_le_bytes([self.0[offset], self.0[offset + 1]])
+    }
+
+    #[inline]
+    fn u32(self, offset: usize) -> u32 {
+        u32::from_le_bytes(self.0[offset..offset + 4].try_into().unwrap())
+    }
+}
+
+/// `len` bytes of `bytes` at `offset`.
+#[inline]
+fn slice(bytes: &[u8], offset: u32, len: u32) -> Result<&[u8], ElfError> {
+    let start = offset as usize;
+    start
+        .checked_add(len as usize)
+        .and_then(|end| bytes.get(start..end))
+        .ok_or(ElfError::Truncated)
+}
+
+/// The NUL-terminated string at `offset` of a string table.
+fn string(table: &[u8], offset: u32) -> Result<&str, ElfError> {
+    let bytes = table
+        .get(offset as usize..)
+        .and_then(|rest| rest.split(|byte| *byte == 0).next())
+        .ok_or(ElfError::InvalidName(offset))?;
+    std::str::from_utf8(bytes).map_err(|_| ElfError::InvalidName(offset))
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ElfSection<'a> {
+    pub name: &'a str,
+    /// `sh_type`
+    pub kind: u32,
+    /// `sh_flags`
+    pub flags: u32,
+    pub address: u32,
+    /// The contents of the section, empty for sections without contents such as `.bss`.
+    pub data: &'a [u8],
+    /// `sh_link`
+    link: u32,
+}
+
+impl ElfSection<'_> {
+    /// Whether the section holds instructions that are loaded into memory.
+    #[inline]
+    pub fn is_executable(&self) -> bool {
+        self.flags & (SHF_ALLOC | SHF_EXECINSTR) == SHF_ALLOC | SHF_EXECINSTR
+    }
+}
+
+/// A parsed ELF file, borrowing its contents.
+#[derive(Debug, Clone)]
+pub struct ElfFile<'a> {
+    /// `e_type`, e.g. `1` for relocatable objects or `2` for executables.
+    pub kind: u16,
+    pub entry: u32,
+    sections: Vec<ElfSection<'a>>,
+    /// Symbols with the index of the section they are defined in.
+    symbols: Vec<(usize, Symbol)>,
+}
+
+impl<'a> ElfFile<'a> {
+    pub fn parse(bytes: &'a [u8]) -> Result<Self, ElfError> {
+        if !bytes.starts_with(b"\x7fELF") {
+            return Err(ElfError::NotElf);
+        }
+        let header = Record(bytes.get(..HEADER_SIZE).ok_or(ElfError::Truncated)?);
+
+        match (header.u8(4), header.u8(5), header.u16(18)) {
+            (ELFCLASS32, ELFDATA2LSB, EM_RISCV) => {}
+            (ELFCLASS32, ELFDATA2LSB, machine) => return Err(ElfError::WrongMachine(machine)),
+            (ELFCLASS32, encoding, _) => return Err(ElfError::UnsupportedEncoding(encoding)),
+            (class, _, _) => return Err(ElfError::UnsupportedClass(class)),
+        }
+
+        let (section_offset, section_count) = (header.u32(32), header.u16(48));
+        let names_index = header.u16(50) as usize;
+
+        let headers = slice(
+            bytes,
+            section_offset,
+            u32::from(section_count) * SECTION_HEADER_SIZE as u32,
+        )?;
+        let headers = headers
+            .chunks_exact(SECTION_HEADER_SIZE)
+            .map(Record)
+            .collect::<Vec<_>>();
+
+        let contents = |header: Record| match header.u32(4) {
+            SHT_NOBITS => Ok(&[][..]),
+            _ => slice(bytes, header.u32(16), header.u32(20)),
+        };
+        let names = match headers.get(names_index) {
+            Some(header) => contents(*header)?,
+            None => &[],
+        };
+
+        let sections = headers
+            .iter()
+            .map(|header| {
+                Ok(ElfSection {
+                    name: string(names, header.u32(0))?,
+                    kind: header.u32(4),
+                    flags: header.u32(8),
+                    address: header.u32(12),
+                    data: contents(*header)?,
+                    link: header.u32(24),
+                })
+            })
+            .collect::<Result<Vec<_>, ElfError>>()?;
+
+        let mut symbols = Vec::new();
+        for table in sections.iter().filter(|section| section.kind == SHT_SYMTAB) {
+            let strings = sections
+                .get(table.link as usize)
+                .ok_or(ElfError::Truncated)?
+                .data;
+
+            // The first entry is the undefined symbol
+            for entry in table.data.chunks_exact(SYMBOL_SIZE).skip(1).map(Record) {
+                let (info, section) = (entry.u8(12), entry.u16(14) as usize);
+                let name = string(strings, entry.u32(0))?;
+
+                // Undefined, absolute and common symbols do not name an address in a section
+                if name.is_empty() || section == 0 || section >= sections.len() {
+                    continue;
+                }
+
+                symbols.push((
+                    section,
+                    Symbol {
+                        name: name.to_string(),
+                        address: entry.u32(4),
+                        size: entry.u32(8),
+                        kind: match info & 0xf {
+                            STT_FUNC => SymbolKind::Function,
+                            STT_OBJECT => SymbolKind::Object,
+                            STT_SECTION | STT_FILE => continue,
+                            _ => SymbolKind::Other,
+                        },
+                        global: info >> 4 != STB_LOCAL,
+                    },
+                ));
+            }
+        }
+
+        Ok(Self {
+            kind: header.u16(16),
+            entry: header.u32(24),
+            sections,
+            symbols,
+        })
+    }
+
+    #[inline]
+    pub fn sections(&self) -> &[ElfSection<'a>] {
+        &self.sections
+    }
+
+    #[inline]
+    pub fn section(&self, name: &str) -> Option<&ElfSection<'a>> {
+        self.sections.iter().find(|section| section.name == name)
+    }
+
+    #[inline]
+    pub fn executable_sections(&self) -> impl Iterator<Item = &ElfSection<'a>> {
+        self.sections
+            .iter()
+            .filter(|section| section.is_executable())
+    }
+
+    /// All symbols naming an address in a section.
+    #[inline]
+    pub fn symbols(&self) -> SymbolTable {
+        self.symbols
+            .iter()
+            .map(|(_, symbol)| symbol.clone())
+            .collect()
+    }
+
+    /// The symbols defined in `section`. In relocatable objects every section starts at address
+    /// `0`, so symbols of different sections can only be told apart this way.
+    pub fn section_symbols(&self, section: &ElfSection) -> SymbolTable {
+        let index = self
+            .sections
+            .iter()
+            .position(|other| std::ptr::eq(other, section));
+
+        self.symbols
+            .iter()
+            .filter(|(defined_in, _)| Some(*defined_in) == index)
+            .map(|(_, symbol)| symbol.clone())
+            .collect()
+    }
+
+    /// Disassemble every executable section, like `objdump -d`.
+    pub fn listing(&self, options: AsmFormatOptions) -> String {
+        let mut out = String::new();
+
+        for section in self.executable_sections() {
+            let symbols = self.section_symbols(section);
+            out.push_str(&format!("\nDisassembly of section {}:\n", section.name));
+            Listing::new(options.clone())
+                .symbols(&symbols)
+                .write(&mut out, section.data, section.address)
+                .unwrap();
+        }
+
+        out
+    }
+}
+
+impl RelocationKind {
+    /// The `R_RISCV_*` relocation type.
+    #[inline]
+    pub(crate) fn elf_type(self) -> u32 {
+        match self {
+            Self::Abs32 => R_RISCV_32,
+            Self::Branch => R_RISCV_BRANCH,
+            Self::Jal => R_RISCV_JAL,
+            Self::Call => R_RISCV_CALL,
+            Self::Hi20 => R_RISCV_HI20,
+            Self::Lo12I => R_RISCV_LO12_I,
+            Self::Lo12S => R_RISCV_LO12_S,
+            Self::PcrelHi20 => R_RISCV_PCREL_HI20,
+            Self::PcrelLo12I => R_RISCV_PCREL_LO12_I,
+            Self::PcrelLo12S => R_RISCV_PCREL_LO12_S,
+        }
+    }
+}
+
+/// A string table being built, starting with the empty string.
+struct StringTable(Vec<u8>);
+
+impl StringTable {
+    #[inline]
+    fn new() -> Self {
+        Self(vec![0])
+    }
+
+    fn add(&mut self, string: &str) -> u32 {
+        let offset = self.0.len() as u32;
+        self.0.extend_from_slice(string.as_bytes());
+        self.0.push(0);
+        offset
+    }
+}
+
+/// Append `data` to `out` at a multiple of `alignment`, returning its offset.
+fn place(out: &mut Vec<u8>, data: &[u8], alignment: u32) -> u32 {
+    out.resize(out.len().next_multiple_of(alignment.max(1) as usize), 0);
+    let offset = out.len() as u32;
+    out.extend_from_slice(data);
+    offset
+}
+
+/// The fields of a section header, in order.
+struct SectionHeader {
+    name: u32,
+    kind: u32,
+    flags: u32,
+    address: u32,
+    offset: u32,
+    size: u32,
+    link: u32,
+    info: u32,
+    alignment: u32,
+    entry_size: u32,
+}
+
+/// Write `program` as a relocatable object (`ET_REL`).
+///
+/// Every section of the program is written with a `.symtab`, `.strtab` and, for sections with
+/// relocations, a `.rela` section. Labels become local symbols unless declared with `.globl`,
+/// and symbols that are referenced but not defined become undefined global symbols.
+///
+/// The program is usually assembled with [`Assembler::relocatable`]; otherwise its sections keep
+/// their addresses and have no relocations.
+///
+/// Only RV32 programs can be written, as 32-bit ELF files; an RV64 program is an
+/// [`ElfError::UnsupportedClass`] error.
+///
+/// [`Assembler::relocatable`]: crate::assembler::Assembler::relocatable
+pub fn write_object(program: &Program) -> Result<Vec<u8>, ElfError> {
+    if program.xlen() == Xlen::Rv64 {
+        return Err(ElfError::UnsupportedClass(ELFCLASS64));
+    }
+
+    let mut out = vec![0; HEADER_SIZE];
+    let mut headers = vec![SectionHeader {
+        name: 0,
+        kind: 0,
+        flags: 0,
+        address: 0,
+        offset: 0,
+        size: 0,
+        link: 0,
+        info: 0,
+        alignment: 0,
+        entry_size: 0,
+    }];
+    let mut names = StringTable::new();
+
+    for section in program.sections() {
+        let flags = match section.name() {
+            name if name == ".text" || name.starts_with(".text.") => SHF_ALLOC | SHF_EXECINSTR,
+            name if name == ".rodata" || name.starts_with(".rodata.") => SHF_ALLOC,
+            _ => SHF_ALLOC | SHF_WRITE,
+        };
+        let (kind, offset) = match section.is_nobits() {
+            true => (SHT_NOBITS, out.len() as u32),
+            false => (
+                SHT_PROGBITS,
+                place(&mut out, section.data(), section.alignment()),
+            ),
+        };
+
+        headers.push(SectionHeader {
+            name: names.add(section.name()),
+            kind,
+            flags,
+            address: section.address(),
+            offset,
+            size: section.size(),
+            link: 0,
+            info: 0,
+            alignment: section.alignment(),
+            entry_size: 0,
+        });
+    }
+
+    // Local symbols have to come before global ones
+    let (globals, locals): (Vec<_>, Vec<_>) = program
+        .labels()
+        .iter()
+        .partition(|(label, _)| program.globals().contains(*label));
+    let undefined = program
+        .relocations()
+        .iter()
+        .map(|relocation| &relocation.symbol)
+        .chain(program.globals())
+        .filter(|symbol| !program.labels().contains_key(*symbol))
+        .collect::<BTreeSet<_>>();
+
+    let mut strings = StringTable::new();
+    let mut symtab = vec![0; SYMBOL_SIZE];
+    let mut indices = HashMap::new();
+    let defined = locals.iter().chain(&globals).map(|(label, address)| {
+        let section = program.label_section(label).unwrap();
+        let value = *address - program.sections()[section].address();
+        (*label, value, section as u16 + 1)
+    });
+    let undefined = undefined.into_iter().map(|symbol| (symbol, 0, 0));
+
+    for (i, (name, value, section)) in defined.chain(undefined).enumerate() {
+        let binding = match program.globals().contains(name) || section == 0 {
+            true => STB_GLOBAL,
+            false => STB_LOCAL,
+        };
+
+        indices.insert(name.as_str(), i as u32 + 1);
+        symtab.extend_from_slice(&strings.add(name).to_le_bytes());
+        symtab.extend_from_slice(&value.to_le_bytes());
+        symtab.extend_from_slice(&0u32.to_le_bytes());
+        symtab.extend_from_slice(&[binding << 4, 0]);
+        symtab.extend_from_slice(&section.to_le_bytes());
+    }
+
+    let symtab_index = headers.len() as u32;
+    let symtab_offset = place(&mut out, &symtab, 4);
+    headers.push(SectionHeader {
+        name: names.add(".symtab"),
+        kind: SHT_SYMTAB,
+        flags: 0,
+        address: 0,
+        offset: symtab_offset,
+        size: symtab.len() as u32,
+        link: symtab_index + 1,
+        info: locals.len() as u32 + 1,
+        alignment: 4,
+        entry_size: SYMBOL_SIZE as u32,
+    });
+    let strtab_offset = place(&mut out, &strings.0, 1);
+    headers.push(SectionHeader {
+        name: names.add(".strtab"),
+        kind: SHT_STRTAB,
+        flags: 0,
+        address: 0,
+        offset: strtab_offset,
+        size: strings.0.len() as u32,
+        link: 0,
+        info: 0,
+        alignment: 1,
+        entry_size: 0,
+    });
+
+    for (index, section) in program.sections().iter().enumerate() {
+        let mut rela = Vec::new();
+        for relocation in program.relocations() {
+            if relocation.section != index {
+                continue;
+            }
+
+            let symbol = indices[relocation.symbol.as_str()];
+            let info = (symbol << 8) | relocation.kind.elf_type();
+            rela.extend_from_slice(&relocation.offset.to_le_bytes());
+            rela.extend_from_slice(&info.to_le_bytes());
+            rela.extend_from_slice(&(relocation.addend as i32).to_le_bytes());
+        }
+
+        if rela.is_empty() {
+            continue;
+        }
+
+        let offset = place(&mut out, &rela, 4);
+        headers.push(SectionHeader {
+            name: names.add(&format!(".rela{}", section.name())),
+            kind: SHT_RELA,
+            flags: SHF_INFO_LINK,
+            address: 0,
+            offset,
+            size: rela.len() as u32,
+            link: symtab_index,
+            info: index as u32 + 1,
+            alignment: 4,
+            entry_size: RELA_SIZE as u32,
+        });
+    }
+
+    let names_index = headers.len() as u16;
+    let name = names.add(".shstrtab");
+    let names_offset = place(&mut out, &names.0, 1);
+    headers.push(SectionHeader {
+        name,
+        kind: SHT_STRTAB,
+        flags: 0,
+        address: 0,
+        offset: names_offset,
+        size: names.0.len() as u32,
+        link: 0,
+        info: 0,
+        alignment: 1,
+        entry_size: 0,
+    });
+
+    let section_offset = place(&mut out, &[], 4);
+    for header in &headers {
+        for field in [
+            header.name,
+            header.kind,
+            header.flags,
+            header.address,
+            header.offset,
+            header.size,
+            header.link,
+            header.info,
+            header.alignment,
+            header.entry_size,
+        ] {
+            out.extend_from_slice(&field.to_le_bytes());
+        }
+    }
+
+    let header = &mut out[..HEADER_SIZE];
+    header[..8].copy_from_slice(&[
+        0x7f,
+        b'E',
+        b'L',
+        b'F',
+        ELFCLASS32,
+        ELFDATA2LSB,
+        EV_CURRENT,
+        0,
+    ]);
+    header[16..18].copy_from_slice(&ET_REL.to_le_bytes());
+    header[18..20].copy_from_slice(&EM_RISCV.to_le_bytes());
+    header[20..24].copy_from_slice(&u32::from(EV_CURRENT).to_le_bytes());
+    header[32..36].copy_from_slice(&section_offset.to_le_bytes());
+    header[40..42].copy_from_slice(&(HEADER_SIZE as u16).to_le_bytes());
+    header[46..48].copy_from_slice(&(SECTION_HEADER_SIZE as u16).to_le_bytes());
+    header[48..50].copy_from_slice(&(headers.len() as u16).to_le_bytes());
+    header[50..52].copy_from_slice(&names_index.to_le_bytes());
+
+    Ok(out)
+}
+
+#[test]
+fn read() {
+    // An executable with `_start: addi a0, zero, 1; ret` in `.text`
+    let mut bytes = vec![0; HEADER_SIZE];
+    bytes[..8].copy_from_slice(b"\x7fELF\x01\x01\x01\x00");
+    bytes[16..20].copy_from_slice(&[2, 0, 243, 0]);
+    bytes[24..28].copy_from_slice(&0x8000_0000u32.to_le_bytes());
+
+    let text = bytes.len() as u32;
+    bytes.extend_from_slice(&[0x13, 0x05, 0x10, 0x00, 0x67, 0x80, 0x00, 0x00]);
+    let strtab = bytes.len() as u32;
+    bytes.extend_from_slice(b"\0_start\0\0.text\0.symtab\0.strtab\0");
+    let symtab = bytes.len() as u32;
+    bytes.extend_from_slice(&[0; SYMBOL_SIZE]);
+    for field in [1, 0x8000_0000, 8, 0x0001_0012] {
+        bytes.extend_from_slice(&u32::to_le_bytes(field));
+    }
+
+    let section_offset = bytes.len() as u32;
+    let headers: [[u32; 10]; 4] = [
+        [0; 10],
+        [
+            9,
+            1,
+            SHF_ALLOC | SHF_EXECINSTR,
+            0x8000_0000,
+            text,
+            8,
+            0,
+            0,
+            4,
+            0,
+        ],
+        [15, SHT_SYMTAB, 0, 0, symtab, 32, 3, 1, 4, 16],
+        [23, 3, 0, 0, strtab, 31, 0, 0, 1, 0],
+    ];
+    for field in headers.iter().flatten() {
+        bytes.extend_from_slice(&field.to_le_bytes());
+    }
+    bytes[32..36].copy_from_slice(&section_offset.to_le_bytes());
+    bytes[46..52].copy_from_slice(&[40, 0, 4, 0, 3, 0]);
+
+    let elf = ElfFile::parse(&bytes).unwrap();
+    assert_eq!(elf.entry, 0x8000_0000);
+    assert_eq!(elf.executable_sections().count(), 1);
+    assert_eq!(elf.symbols().get("_start").unwrap().size, 8);
+    assert_eq!(
+        elf.listing(AsmFormatOptions::default().arg_spacing(" ")),
+        "
+Disassembly of section .text:
+
+80000000 <_start>:
+80000000:  00100513  li\ta0, 0x1
+80000004:  00008067  ret
+"
+    );
+
+    bytes[18] = 62;
+    assert_eq!(
+        ElfFile::parse(&bytes).unwrap_err(),
+        ElfError::WrongMachine(62)
+    );
+    bytes[4] = 2;
+    assert_eq!(
+        ElfFile::parse(&bytes).unwrap_err(),
+        ElfError::UnsupportedClass(2)
+    );
+}
+
+#[test]
+fn write() {
+    let program = crate::assembler::Assembler::new(0)
+        .relocatable(true)
+        .assemble(
+            "
+                .globl _start
+            _start:
+                call puts
+                la a0, message
+                j _start
+                .data
+            message:
+                .asciz \"hi\"
+            ",
+        )
+        .unwrap();
+    let bytes = write_object(&program).unwrap();
+
+    let elf = ElfFile::parse(&bytes).unwrap();
+    assert_eq!(elf.kind, ET_REL);
+    assert_eq!(elf.section(".data").unwrap().data, b"hi\0");
+    assert_eq!(elf.section(".rela.text").unwrap().data.len(), 3 * RELA_SIZE);
+
+    let symbols = elf.symbols();
+    assert!(symbols.get("_start").unwrap().global);
+    assert!(!symbols.get("message").unwrap().global);
+    // `puts` is undefined, so it names no address
+    assert_eq!(symbols.get("puts"), None);
+
+    assert_eq!(
+        elf.listing(AsmFormatOptions::default().arg_spacing(" ")),
+        "
+Disassembly of section .text:
+
+00000000 <_start>:
+00000000:  00000097  auipc\tra, 0x0
+00000004:  000080e7  jalr\tra  # 0 <_start>
+
+00000008 <.Lpcrel_hi0>:
+00000008:  00000517  auipc\ta0, 0x0
+0000000c:  00050513  mv\ta0, a0  # 8 <.Lpcrel_hi0>
+00000010:  ff1ff06f  j\t_start
+"
+    );
+
+    let program = crate::assembler::Assembler::new(0)
+        .xlen(Xlen::Rv64)
+        .assemble("ld a0, 0(a1)")
+        .unwrap();
+    assert_eq!(
+        write_object(&program),
+        Err(ElfError::UnsupportedClass(ELFCLASS64))
+    );
+}
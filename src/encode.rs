@@ -0,0 +1,1076 @@
+use crate::parse::ParseError;
+use crate::{AsmArgument, AsmRoundingMode, Xlen};
+
+pub(crate) const OP_LUI: u32 = 0b0110111;
+pub(crate) const OP_AUIPC: u32 = 0b0010111;
+pub(crate) const OP_JAL: u32 = 0b1101111;
+pub(crate) const OP_JALR: u32 = 0b1100111;
+pub(crate) const OP_BRANCH: u32 = 0b1100011;
+pub(crate) const OP_LOAD: u32 = 0b0000011;
+pub(crate) const OP_STORE: u32 = 0b0100011;
+pub(crate) const OP_IMM: u32 = 0b0010011;
+pub(crate) const OP: u32 = 0b0110011;
+pub(crate) const OP_IMM_32: u32 = 0b0011011;
+pub(crate) const OP_32: u32 = 0b0111011;
+pub(crate) const OP_MISC_MEM: u32 = 0b0001111;
+pub(crate) const OP_SYSTEM: u32 = 0b1110011;
+pub(crate) const OP_AMO: u32 = 0b0101111;
+pub(crate) const OP_LOAD_FP: u32 = 0b0000111;
+pub(crate) const OP_STORE_FP: u32 = 0b0100111;
+pub(crate) const OP_MADD: u32 = 0b1000011;
+pub(crate) const OP_MSUB: u32 = 0b1000111;
+pub(crate) const OP_NMSUB: u32 = 0b1001011;
+pub(crate) const OP_NMADD: u32 = 0b1001111;
+pub(crate) const OP_FP: u32 = 0b1010011;
+pub(crate) const OP_V: u32 = 0b1010111;
+
+/// `funct7` of the M extension instructions.
+pub(crate) const MULDIV: u32 = 0b0000001;
+
+#[inline]
+pub(crate) fn r_type(opcode: u32, funct3: u32, funct7: u32, rd: u8, rs1: u8, rs2: u8) -> u32 {
+    (funct7 << 25)
+        | (u32::from(rs2) << 20)
+        | (u32::from(rs1) << 15)
+        | (funct3 << 12)
+        | (u32::from(rd) << 7)
+        | opcode
+}
+
+#[inline]
+pub(crate) fn i_type(opcode: u32, funct3: u32, rd: u8, rs1: u8, imm: i64) -> u32 {
+    ((imm as u32 & 0xFFF) << 20)
+        | (u32::from(rs1) << 15)
+        | (funct3 << 12)
+        | (u32::from(rd) << 7)
+        | opcode
+}
+
+#[inline]
+pub(crate) fn s_type(opcode: u32, funct3: u32, rs1: u8, rs2: u8, imm: i64) -> u32 {
+    let imm = imm as u32;
+    (((imm >> 5) & 0x7F) << 25)
+        | (u32::from(rs2) << 20)
+        | (u32::from(rs1) << 15)
+        | (funct3 << 12)
+        | ((imm & 0x1F) << 7)
+        | opcode
+}
+
+#[inline]
+pub(crate) fn b_type(opcode: u32, funct3: u32, rs1: u8, rs2: u8, imm: i64) -> u32 {
+    let imm = imm as u32;
+    (((imm >> 12) & 0x1) << 31)
+        | (((imm >> 5) & 0x3F) << 25)
+        | (u32::from(rs2) << 20)
+        | (u32::from(rs1) << 15)
+        | (funct3 << 12)
+        | (((imm >> 1) & 0xF) << 8)
+        | (((imm >> 11) & 0x1) << 7)
+        | opcode
+}
+
+/// `imm` holds the upper 20 bits only, i.e. the value as printed by `lui`/`auipc`.
+#[inline]
+pub(crate) fn u_type(opcode: u32, rd: u8, imm: i64) -> u32 {
+    ((imm as u32 & 0xF_FFFF) << 12) | (u32::from(rd) << 7) | opcode
+}
+
+#[inline]
+pub(crate) fn j_type(opcode: u32, rd: u8, imm: i64) -> u32 {
+    let imm = imm as u32;
+    (((imm >> 20) & 0x1) << 31)
+        | (((imm >> 1) & 0x3FF) << 21)
+        | (((imm >> 11) & 0x1) << 20)
+        | (((imm >> 12) & 0xFF) << 12)
+        | (u32::from(rd) << 7)
+        | opcode
+}
+
+#[inline]
+pub(crate) fn check_range(value: i64, min: i64, max: i64) -> Result<i64, ParseError> {
+    if value < min || value > max {
+        return Err(ParseError::ImmediateOutOfRange { value, min, max });
+    }
+
+    Ok(value)
+}
+
+/// Range of a 12-bit I/S-type immediate.
+#[inline]
+pub(crate) fn imm12(value: i64) -> Result<i64, ParseError> {
+    check_range(value, -0x800, 0x7FF)
+}
+
+#[inline]
+pub(crate) fn imm20(value: i64) -> Result<i64, ParseError> {
+    check_range(value, -0x8_0000, 0xF_FFFF)
+}
+
+#[inline]
+pub(crate) fn branch_offset(value: i64) -> Result<i64, ParseError> {
+    check_range(value, -0x1000, 0xFFE)?;
+    if value & 1 != 0 {
+        return Err(ParseError::MisalignedOffset(value));
+    }
+    Ok(value)
+}
+
+#[inline]
+pub(crate) fn jump_offset(value: i64) -> Result<i64, ParseError> {
+    check_range(value, -0x10_0000, 0xF_FFFE)?;
+    if value & 1 != 0 {
+        return Err(ParseError::MisalignedOffset(value));
+    }
+    Ok(value)
+}
+
+/// Range of a shift amount, which is 5 bits on RV32 and for the 32-bit `*w` shifts, and 6 bits
+/// otherwise on RV64.
+#[inline]
+pub(crate) fn shamt(value: i64, xlen: Xlen) -> Result<i64, ParseError> {
+    match xlen {
+        Xlen::Rv32 => check_range(value, 0, 31),
+        Xlen::Rv64 => check_range(value, 0, 63),
+    }
+}
+
+pub(crate) fn reg(args: &[AsmArgument], index: usize) -> Result<u8, ParseError> {
+    match args.get(index) {
+        Some(AsmArgument::Register(reg)) => Ok(u8::from(*reg)),
+        _ => Err(ParseError::ExpectedRegister(index)),
+    }
+}
+
+pub(crate) fn freg(args: &[AsmArgument], index: usize) -> Result<u8, ParseError> {
+    match args.get(index) {
+        Some(AsmArgument::FloatRegister(reg)) => Ok(u8::from(*reg)),
+        _ => Err(ParseError::ExpectedFloatRegister(index)),
+    }
+}
+
+pub(crate) fn imm(args: &[AsmArgument], index: usize) -> Result<i64, ParseError> {
+    match args.get(index) {
+        Some(AsmArgument::Immediate(imm)) => Ok(*imm),
+        Some(AsmArgument::Label(label)) => Err(ParseError::UnresolvedLabel(label.to_string())),
+        Some(AsmArgument::Relocation(_, expr)) => {
+            Err(ParseError::UnresolvedLabel(expr.to_string()))
+        }
+        _ => Err(ParseError::ExpectedImmediate(index)),
+    }
+}
+
+pub(crate) fn offset(args: &[AsmArgument], index: usize) -> Result<(i64, u8), ParseError> {
+    match args.get(index) {
+        Some(AsmArgument::OffsetImmediate(imm, reg)) => Ok((*imm, u8::from(*reg))),
+        // `lw a0, a1` is accepted by GNU as as `lw a0, 0(a1)`
+        Some(AsmArgument::Register(reg) | AsmArgument::Address(reg)) => Ok((0, u8::from(*reg))),
+        Some(AsmArgument::OffsetRelocation(_, expr, _)) => {
+            Err(ParseError::UnresolvedLabel(expr.to_string()))
+        }
+        _ => Err(ParseError::ExpectedOffset(index)),
+    }
+}
+
+/// The `(reg)` address operand of the atomics, also accepted as `0(reg)`.
+pub(crate) fn address(args: &[AsmArgument], index: usize) -> Result<u8, ParseError> {
+    match args.get(index) {
+        Some(AsmArgument::Address(reg) | AsmArgument::OffsetImmediate(0, reg)) => {
+            Ok(u8::from(*reg))
+        }
+        _ => Err(ParseError::ExpectedAddress(index)),
+    }
+}
+
+#[inline]
+pub(crate) fn expect_count(
+    verb: &str,
+    args: &[AsmArgument],
+    expected: usize,
+) -> Result<(), ParseError> {
+    if args.len() != expected {
+        return Err(ParseError::OperandCount {
+            verb: verb.to_string(),
+            expected,
+            found: args.len(),
+        });
+    }
+
+    Ok(())
+}
+
+/// The rounding mode operand following `count` other operands, or `default` if it is left out.
+fn rounding_mode(
+    verb: &str,
+    args: &[AsmArgument],
+    count: usize,
+    default: AsmRoundingMode,
+) -> Result<u32, ParseError> {
+    expect_count(verb, args, args.len().clamp(count, count + 1))?;
+
+    let mode = match args.get(count) {
+        None => default,
+        Some(AsmArgument::Label(name)) => name
+            .parse()
+            .map_err(|_| ParseError::InvalidRoundingMode(count))?,
+        Some(_) => return Err(ParseError::InvalidRoundingMode(count)),
+    };
+
+    Ok(u32::from(u8::from(mode)))
+}
+
+fn fence_set(arg: Option<&AsmArgument>, index: usize) -> Result<u32, ParseError> {
+    let Some(AsmArgument::Label(set)) = arg else {
+        return Err(ParseError::InvalidFenceSet(index));
+    };
+
+    let mut bits = 0;
+    for c in set.chars() {
+        bits |= match c {
+            'i' => 0b1000,
+            'o' => 0b0100,
+            'r' => 0b0010,
+            'w' => 0b0001,
+            _ => return Err(ParseError::InvalidFenceSet(index)),
+        };
+    }
+
+    Ok(bits)
+}
+
+pub(crate) const ZERO: u8 = 0;
+
+/// A CSR operand, given by its name or number.
+fn csr(args: &[AsmArgument], index: usize, xlen: Xlen) -> Result<u32, ParseError> {
+    let number = match args.get(index) {
+        Some(AsmArgument::Label(name)) => crate::csr::number(name, xlen),
+        Some(AsmArgument::Csr(number)) => Some(*number),
+        Some(AsmArgument::Immediate(number)) => u16::try_from(*number).ok(),
+        _ => None,
+    };
+
+    match number {
+        Some(number @ 0..=0xfff) => Ok(u32::from(number)),
+        _ => Err(ParseError::InvalidCsr(index)),
+    }
+}
+
+/// The 5-bit immediate of the CSR instructions, in the place of `rs1`.
+#[inline]
+fn uimm5(args: &[AsmArgument], index: usize) -> Result<u8, ParseError> {
+    Ok(check_range(imm(args, index)?, 0, 31)? as u8)
+}
+
+/// Encode a Zicsr instruction or one of its aliases, like `csrr` or `fsrm`.
+fn encode_csr(verb: &str, args: &[AsmArgument], xlen: Xlen) -> Result<Option<u32>, ParseError> {
+    let csr_type =
+        |funct3: u32, rd: u8, csr: u32, rs1: u8| i_type(OP_SYSTEM, funct3, rd, rs1, i64::from(csr));
+    // The CSR of the floating-point aliases, such as `fsrm`
+    let float_csr = |verb: &str| match verb.trim_end_matches('i') {
+        "frcsr" | "fscsr" => 0x003,
+        "frrm" | "fsrm" => 0x002,
+        _ => 0x001,
+    };
+
+    let word = match verb {
+        "csrrw" | "csrrs" | "csrrc" => {
+            expect_count(verb, args, 3)?;
+            let funct3 = match verb {
+                "csrrw" => 0b001,
+                "csrrs" => 0b010,
+                _ => 0b011,
+            };
+            csr_type(funct3, reg(args, 0)?, csr(args, 1, xlen)?, reg(args, 2)?)
+        }
+        "csrrwi" | "csrrsi" | "csrrci" => {
+            expect_count(verb, args, 3)?;
+            let funct3 = match verb {
+                "csrrwi" => 0b101,
+                "csrrsi" => 0b110,
+                _ => 0b111,
+            };
+            csr_type(funct3, reg(args, 0)?, csr(args, 1, xlen)?, uimm5(args, 2)?)
+        }
+        "csrr" => {
+            expect_count(verb, args, 2)?;
+            csr_type(0b010, reg(args, 0)?, csr(args, 1, xlen)?, ZERO)
+        }
+        "csrw" | "csrs" | "csrc" => {
+            expect_count(verb, args, 2)?;
+            let funct3 = match verb {
+                "csrw" => 0b001,
+                "csrs" => 0b010,
+                _ => 0b011,
+            };
+            csr_type(funct3, ZERO, csr(args, 0, xlen)?, reg(args, 1)?)
+        }
+        "csrwi" | "csrsi" | "csrci" => {
+            expect_count(verb, args, 2)?;
+            let funct3 = match verb {
+                "csrwi" => 0b101,
+                "csrsi" => 0b110,
+                _ => 0b111,
+            };
+            csr_type(funct3, ZERO, csr(args, 0, xlen)?, uimm5(args, 1)?)
+        }
+        "rdcycle" | "rdtime" | "rdinstret" | "rdcycleh" | "rdtimeh" | "rdinstreth" => {
+            expect_count(verb, args, 1)?;
+            let number = match verb {
+                "rdcycle" => 0xc00,
+                "rdtime" => 0xc01,
+                "rdinstret" => 0xc02,
+                "rdcycleh" => 0xc80,
+                "rdtimeh" => 0xc81,
+                _ => 0xc82,
+            };
+            csr_type(0b010, reg(args, 0)?, number, ZERO)
+        }
+        "frcsr" | "frrm" | "frflags" => {
+            expect_count(verb, args, 1)?;
+            csr_type(0b010, reg(args, 0)?, float_csr(verb), ZERO)
+        }
+        // The old value is only written to a register when one is given
+        "fscsr" | "fsrm" | "fsflags" => {
+            expect_count(verb, args, args.len().clamp(1, 2))?;
+            let rd = if args.len() == 2 { reg(args, 0)? } else { ZERO };
+            let rs1 = reg(args, args.len() - 1)?;
+            csr_type(0b001, rd, float_csr(verb), rs1)
+        }
+        "fsrmi" | "fsflagsi" => {
+            expect_count(verb, args, args.len().clamp(1, 2))?;
+            let rd = if args.len() == 2 { reg(args, 0)? } else { ZERO };
+            let uimm = uimm5(args, args.len() - 1)?;
+            csr_type(0b101, rd, float_csr(verb), uimm)
+        }
+        _ => return Ok(None),
+    };
+
+    Ok(Some(word))
+}
+
+/// `funct3`, `funct5` and the ordering bits of an atomic such as `amoadd.w.aqrl`.
+fn atomic(verb: &str, xlen: Xlen) -> Option<(u32, u32, u32)> {
+    let (verb, ordering) = match verb.rsplit_once('.') {
+        Some((verb, "aq")) => (verb, 0b10),
+        Some((verb, "rl")) => (verb, 0b01),
+        Some((verb, "aqrl")) => (verb, 0b11),
+        _ => (verb, 0b00),
+    };
+
+    let (name, width) = verb.rsplit_once('.')?;
+    let funct3 = match width {
+        "w" => 0b010,
+        "d" if xlen == Xlen::Rv64 => 0b011,
+        _ => return None,
+    };
+    let funct5 = match name {
+        "lr" => 0b00010,
+        "sc" => 0b00011,
+        "amoswap" => 0b00001,
+        "amoadd" => 0b00000,
+        "amoxor" => 0b00100,
+        "amoand" => 0b01100,
+        "amoor" => 0b01000,
+        "amomin" => 0b10000,
+        "amomax" => 0b10100,
+        "amominu" => 0b11000,
+        "amomaxu" => 0b11100,
+        _ => return None,
+    };
+
+    Some((funct3, funct5, ordering))
+}
+
+/// `funct7` and `rs2` of a hypervisor virtual-machine load such as `hlvx.hu`.
+fn hypervisor_load(verb: &str, xlen: Xlen) -> Option<(u32, u8)> {
+    Some(match verb {
+        "hlv.b" => (0b0110000, 0b00000),
+        "hlv.bu" => (0b0110000, 0b00001),
+        "hlv.h" => (0b0110010, 0b00000),
+        "hlv.hu" => (0b0110010, 0b00001),
+        "hlvx.hu" => (0b0110010, 0b00011),
+        "hlv.w" => (0b0110100, 0b00000),
+        "hlvx.wu" => (0b0110100, 0b00011),
+        "hlv.wu" if xlen == Xlen::Rv64 => (0b0110100, 0b00001),
+        "hlv.d" if xlen == Xlen::Rv64 => (0b0110110, 0b00000),
+        _ => return None,
+    })
+}
+
+/// `funct7` of a hypervisor virtual-machine store such as `hsv.w`.
+fn hypervisor_store(verb: &str, xlen: Xlen) -> Option<u32> {
+    Some(match verb {
+        "hsv.b" => 0b0110001,
+        "hsv.h" => 0b0110011,
+        "hsv.w" => 0b0110101,
+        "hsv.d" if xlen == Xlen::Rv64 => 0b0110111,
+        _ => return None,
+    })
+}
+
+/// Encode an instruction of the bit-manipulation extensions Zba, Zbb, Zbc and Zbs.
+fn encode_bitmanip(
+    verb: &str,
+    args: &[AsmArgument],
+    xlen: Xlen,
+) -> Result<Option<u32>, ParseError> {
+    let rv64 = xlen == Xlen::Rv64;
+
+    let register = match verb {
+        "sh1add" => Some((OP, 0b010, 0b0010000)),
+        "sh2add" => Some((OP, 0b100, 0b0010000)),
+        "sh3add" => Some((OP, 0b110, 0b0010000)),
+        "andn" => Some((OP, 0b111, 0b0100000)),
+        "orn" => Some((OP, 0b110, 0b0100000)),
+        "xnor" => Some((OP, 0b100, 0b0100000)),
+        "max" => Some((OP, 0b110, 0b0000101)),
+        "maxu" => Some((OP, 0b111, 0b0000101)),
+        "min" => Some((OP, 0b100, 0b0000101)),
+        "minu" => Some((OP, 0b101, 0b0000101)),
+        "rol" => Some((OP, 0b001, 0b0110000)),
+        "ror" => Some((OP, 0b101, 0b0110000)),
+        "clmul" => Some((OP, 0b001, 0b0000101)),
+        "clmulr" => Some((OP, 0b010, 0b0000101)),
+        "clmulh" => Some((OP, 0b011, 0b0000101)),
+        "bclr" => Some((OP, 0b001, 0b0100100)),
+        "bext" => Some((OP, 0b101, 0b0100100)),
+        "binv" => Some((OP, 0b001, 0b0110100)),
+        "bset" => Some((OP, 0b001, 0b0010100)),
+        "add.uw" if rv64 => Some((OP_32, 0b000, 0b0000100)),
+        "sh1add.uw" if rv64 => Some((OP_32, 0b010, 0b0010000)),
+        "sh2add.uw" if rv64 => Some((OP_32, 0b100, 0b0010000)),
+        "sh3add.uw" if rv64 => Some((OP_32, 0b110, 0b0010000)),
+        "rolw" if rv64 => Some((OP_32, 0b001, 0b0110000)),
+        "rorw" if rv64 => Some((OP_32, 0b101, 0b0110000)),
+        _ => None,
+    };
+    if let Some((opcode, funct3, funct7)) = register {
+        expect_count(verb, args, 3)?;
+        let (rd, rs1, rs2) = (reg(args, 0)?, reg(args, 1)?, reg(args, 2)?);
+        return Ok(Some(r_type(opcode, funct3, funct7, rd, rs1, rs2)));
+    }
+
+    // Instructions of a single source, with a fixed `rs2`
+    let unary = match verb {
+        "clz" => Some((OP_IMM, 0b001, 0b0110000, 0b00000)),
+        "ctz" => Some((OP_IMM, 0b001, 0b0110000, 0b00001)),
+        "cpop" => Some((OP_IMM, 0b001, 0b0110000, 0b00010)),
+        "sext.b" => Some((OP_IMM, 0b001, 0b0110000, 0b00100)),
+        "sext.h" => Some((OP_IMM, 0b001, 0b0110000, 0b00101)),
+        "orc.b" => Some((OP_IMM, 0b101, 0b0010100, 0b00111)),
+        "rev8" if rv64 => Some((OP_IMM, 0b101, 0b0110101, 0b11000)),
+        "rev8" => Some((OP_IMM, 0b101, 0b0110100, 0b11000)),
+        "zext.h" if rv64 => Some((OP_32, 0b100, 0b0000100, 0b00000)),
+        "zext.h" => Some((OP, 0b100, 0b0000100, 0b00000)),
+        "zext.w" if rv64 => Some((OP_32, 0b000, 0b0000100, 0b00000)),
+        "clzw" if rv64 => Some((OP_IMM_32, 0b001, 0b0110000, 0b00000)),
+        "ctzw" if rv64 => Some((OP_IMM_32, 0b001, 0b0110000, 0b00001)),
+        "cpopw" if rv64 => Some((OP_IMM_32, 0b001, 0b0110000, 0b00010)),
+        _ => None,
+    };
+    if let Some((opcode, funct3, funct7, rs2)) = unary {
+        expect_count(verb, args, 2)?;
+        let (rd, rs1) = (reg(args, 0)?, reg(args, 1)?);
+        return Ok(Some(r_type(opcode, funct3, funct7, rd, rs1, rs2)));
+    }
+
+    // Immediate shifts and bit operations, with the amount below funct7
+    let (opcode, funct3, funct7, width) = match verb {
+        "rori" => (OP_IMM, 0b101, 0b0110000, xlen),
+        "bclri" => (OP_IMM, 0b001, 0b0100100, xlen),
+        "bexti" => (OP_IMM, 0b101, 0b0100100, xlen),
+        "binvi" => (OP_IMM, 0b001, 0b0110100, xlen),
+        "bseti" => (OP_IMM, 0b001, 0b0010100, xlen),
+        "slli.uw" if rv64 => (OP_IMM_32, 0b001, 0b0000100, xlen),
+        "roriw" if rv64 => (OP_IMM_32, 0b101, 0b0110000, Xlen::Rv32),
+        _ => return Ok(None),
+    };
+    expect_count(verb, args, 3)?;
+    let shamt = shamt(imm(args, 2)?, width)?;
+    Ok(Some(i_type(
+        opcode,
+        funct3,
+        reg(args, 0)?,
+        reg(args, 1)?,
+        (funct7 << 5) | shamt,
+    )))
+}
+
+/// `fmt` of a floating-point verb such as `fadd.d`, along with the verb without it.
+#[inline]
+fn float_format(verb: &str) -> Option<(&str, u32)> {
+    match verb.rsplit_once('.')? {
+        (base, "s") => Some((base, 0b00)),
+        (base, "d") => Some((base, 0b01)),
+        _ => None,
+    }
+}
+
+/// `rs2` of a conversion from or to the integer type `ty`, as in `fcvt.wu.s`.
+#[inline]
+fn int_type(ty: &str, xlen: Xlen) -> Option<u8> {
+    match ty {
+        "w" => Some(0),
+        "wu" => Some(1),
+        "l" if xlen == Xlen::Rv64 => Some(2),
+        "lu" if xlen == Xlen::Rv64 => Some(3),
+        _ => None,
+    }
+}
+
+/// Encode an `fcvt` between the types `to` and `from`.
+fn encode_conversion(
+    verb: &str,
+    args: &[AsmArgument],
+    to: &str,
+    from: &str,
+    xlen: Xlen,
+) -> Result<Option<u32>, ParseError> {
+    let fmt = |ty| match ty {
+        "s" => Some(0b00),
+        "d" => Some(0b01),
+        _ => None,
+    };
+
+    let word = match (fmt(to), fmt(from)) {
+        (Some(0b00), Some(0b01)) => {
+            let rm = rounding_mode(verb, args, 2, AsmRoundingMode::Dyn)?;
+            r_type(OP_FP, rm, 0b0100000, freg(args, 0)?, freg(args, 1)?, 1)
+        }
+        // Converting to a wider type is exact, so the rounding mode defaults to `rne`
+        (Some(0b01), Some(0b00)) => {
+            let rm = rounding_mode(verb, args, 2, AsmRoundingMode::Rne)?;
+            r_type(OP_FP, rm, 0b0100001, freg(args, 0)?, freg(args, 1)?, 0)
+        }
+        (None, Some(fmt)) => {
+            let Some(ty) = int_type(to, xlen) else {
+                return Ok(None);
+            };
+            let rm = rounding_mode(verb, args, 2, AsmRoundingMode::Dyn)?;
+            r_type(
+                OP_FP,
+                rm,
+                0b1100000 | fmt,
+                reg(args, 0)?,
+                freg(args, 1)?,
+                ty,
+            )
+        }
+        (Some(fmt), None) => {
+            let Some(ty) = int_type(from, xlen) else {
+                return Ok(None);
+            };
+            let default = match (fmt, ty) {
+                (0b01, 0 | 1) => AsmRoundingMode::Rne,
+                _ => AsmRoundingMode::Dyn,
+            };
+            let rm = rounding_mode(verb, args, 2, default)?;
+            r_type(
+                OP_FP,
+                rm,
+                0b1101000 | fmt,
+                freg(args, 0)?,
+                reg(args, 1)?,
+                ty,
+            )
+        }
+        _ => return Ok(None),
+    };
+
+    Ok(Some(word))
+}
+
+/// Encode an F or D extension instruction, or return `None` if `verb` is not one.
+fn encode_float(verb: &str, args: &[AsmArgument], xlen: Xlen) -> Result<Option<u32>, ParseError> {
+    let word = match verb {
+        "flw" | "fld" => {
+            expect_count(verb, args, 2)?;
+            let funct3 = if verb == "flw" { 0b010 } else { 0b011 };
+            let (imm, rs1) = offset(args, 1)?;
+            i_type(OP_LOAD_FP, funct3, freg(args, 0)?, rs1, imm12(imm)?)
+        }
+        "fsw" | "fsd" => {
+            expect_count(verb, args, 2)?;
+            let funct3 = if verb == "fsw" { 0b010 } else { 0b011 };
+            let (imm, rs1) = offset(args, 1)?;
+            s_type(OP_STORE_FP, funct3, rs1, freg(args, 0)?, imm12(imm)?)
+        }
+        "fmv.x.w" => {
+            expect_count(verb, args, 2)?;
+            r_type(OP_FP, 0b000, 0b1110000, reg(args, 0)?, freg(args, 1)?, 0)
+        }
+        "fmv.x.d" if xlen == Xlen::Rv64 => {
+            expect_count(verb, args, 2)?;
+            r_type(OP_FP, 0b000, 0b1110001, reg(args, 0)?, freg(args, 1)?, 0)
+        }
+        "fmv.w.x" => {
+            expect_count(verb, args, 2)?;
+            r_type(OP_FP, 0b000, 0b1111000, freg(args, 0)?, reg(args, 1)?, 0)
+        }
+        "fmv.d.x" if xlen == Xlen::Rv64 => {
+            expect_count(verb, args, 2)?;
+            r_type(OP_FP, 0b000, 0b1111001, freg(args, 0)?, reg(args, 1)?, 0)
+        }
+        _ => {
+            if let Some(("fcvt", types)) = verb.split_once('.') {
+                let Some((to, from)) = types.split_once('.') else {
+                    return Ok(None);
+                };
+                return encode_conversion(verb, args, to, from, xlen);
+            }
+
+            let Some((base, fmt)) = float_format(verb) else {
+                return Ok(None);
+            };
+            match base {
+                "fmadd" | "fmsub" | "fnmsub" | "fnmadd" => {
+                    let opcode = match base {
+                        "fmadd" => OP_MADD,
+                        "fmsub" => OP_MSUB,
+                        "fnmsub" => OP_NMSUB,
+                        _ => OP_NMADD,
+                    };
+                    let rm = rounding_mode(verb, args, 4, AsmRoundingMode::Dyn)?;
+                    let rs3 = u32::from(freg(args, 3)?);
+                    r_type(
+                        opcode,
+                        rm,
+                        (rs3 << 2) | fmt,
+                        freg(args, 0)?,
+                        freg(args, 1)?,
+                        freg(args, 2)?,
+                    )
+                }
+                "fadd" | "fsub" | "fmul" | "fdiv" => {
+                    let funct5 = match base {
+                        "fadd" => 0b00000,
+                        "fsub" => 0b00001,
+                        "fmul" => 0b00010,
+                        _ => 0b00011,
+                    };
+                    let rm = rounding_mode(verb, args, 3, AsmRoundingMode::Dyn)?;
+                    r_type(
+                        OP_FP,
+                        rm,
+                        (funct5 << 2) | fmt,
+                        freg(args, 0)?,
+                        freg(args, 1)?,
+                        freg(args, 2)?,
+                    )
+                }
+                "fsqrt" => {
+                    let rm = rounding_mode(verb, args, 2, AsmRoundingMode::Dyn)?;
+                    r_type(
+                        OP_FP,
+                        rm,
+                        (0b01011 << 2) | fmt,
+                        freg(args, 0)?,
+                        freg(args, 1)?,
+                        0,
+                    )
+                }
+                "fsgnj" | "fsgnjn" | "fsgnjx" | "fmin" | "fmax" => {
+                    expect_count(verb, args, 3)?;
+                    let (funct3, funct5) = match base {
+                        "fsgnj" => (0b000, 0b00100),
+                        "fsgnjn" => (0b001, 0b00100),
+                        "fsgnjx" => (0b010, 0b00100),
+                        "fmin" => (0b000, 0b00101),
+                        _ => (0b001, 0b00101),
+                    };
+                    r_type(
+                        OP_FP,
+                        funct3,
+                        (funct5 << 2) | fmt,
+                        freg(args, 0)?,
+                        freg(args, 1)?,
+                        freg(args, 2)?,
+                    )
+                }
+                // Sign injection of a register with itself
+                "fmv" | "fneg" | "fabs" => {
+                    expect_count(verb, args, 2)?;
+                    let funct3 = match base {
+                        "fmv" => 0b000,
+                        "fneg" => 0b001,
+                        _ => 0b010,
+                    };
+                    let rs = freg(args, 1)?;
+                    r_type(OP_FP, funct3, (0b00100 << 2) | fmt, freg(args, 0)?, rs, rs)
+                }
+                "feq" | "flt" | "fle" => {
+                    expect_count(verb, args, 3)?;
+                    let funct3 = match base {
+                        "feq" => 0b010,
+                        "flt" => 0b001,
+                        _ => 0b000,
+                    };
+                    r_type(
+                        OP_FP,
+                        funct3,
+                        (0b10100 << 2) | fmt,
+                        reg(args, 0)?,
+                        freg(args, 1)?,
+                        freg(args, 2)?,
+                    )
+                }
+                "fclass" => {
+                    expect_count(verb, args, 2)?;
+                    r_type(
+                        OP_FP,
+                        0b001,
+                        (0b11100 << 2) | fmt,
+                        reg(args, 0)?,
+                        freg(args, 1)?,
+                        0,
+                    )
+                }
+                _ => return Ok(None),
+            }
+        }
+    };
+
+    Ok(Some(word))
+}
+
+/// Encode a single base instruction into its 32-bit instruction word.
+///
+/// Pseudo-instructions are handled by [`crate::pseudo`]. The RV64I instructions are only known
+/// for [`Xlen::Rv64`].
+pub(crate) fn encode(verb: &str, args: &[AsmArgument], xlen: Xlen) -> Result<u32, ParseError> {
+    if let Some((funct3, funct5, ordering)) = atomic(verb, xlen) {
+        let funct7 = (funct5 << 2) | ordering;
+        return Ok(match funct5 {
+            // lr
+            0b00010 => {
+                expect_count(verb, args, 2)?;
+                r_type(
+                    OP_AMO,
+                    funct3,
+                    funct7,
+                    reg(args, 0)?,
+                    address(args, 1)?,
+                    ZERO,
+                )
+            }
+            _ => {
+                expect_count(verb, args, 3)?;
+                r_type(
+                    OP_AMO,
+                    funct3,
+                    funct7,
+                    reg(args, 0)?,
+                    address(args, 2)?,
+                    reg(args, 1)?,
+                )
+            }
+        });
+    }
+    if let Some((funct7, rs2)) = hypervisor_load(verb, xlen) {
+        expect_count(verb, args, 2)?;
+        return Ok(r_type(
+            OP_SYSTEM,
+            0b100,
+            funct7,
+            reg(args, 0)?,
+            address(args, 1)?,
+            rs2,
+        ));
+    }
+    if let Some(funct7) = hypervisor_store(verb, xlen) {
+        expect_count(verb, args, 2)?;
+        return Ok(r_type(
+            OP_SYSTEM,
+            0b100,
+            funct7,
+            ZERO,
+            address(args, 1)?,
+            reg(args, 0)?,
+        ));
+    }
+    if let Some(word) = encode_bitmanip(verb, args, xlen)? {
+        return Ok(word);
+    }
+    if let Some(word) = encode_csr(verb, args, xlen)? {
+        return Ok(word);
+    }
+    if let Some(word) = encode_float(verb, args, xlen)? {
+        return Ok(word);
+    }
+
+    let word = match verb {
+        "lui" | "auipc" => {
+            expect_count(verb, args, 2)?;
+            let opcode = if verb == "lui" { OP_LUI } else { OP_AUIPC };
+            u_type(opcode, reg(args, 0)?, imm20(imm(args, 1)?)?)
+        }
+        "jal" => {
+            expect_count(verb, args, 2)?;
+            j_type(OP_JAL, reg(args, 0)?, jump_offset(imm(args, 1)?)?)
+        }
+        "jalr" => match args.len() {
+            2 => {
+                let (imm, rs1) = offset(args, 1)?;
+                i_type(OP_JALR, 0, reg(args, 0)?, rs1, imm12(imm)?)
+            }
+            _ => {
+                expect_count(verb, args, 3)?;
+                i_type(
+                    OP_JALR,
+                    0,
+                    reg(args, 0)?,
+                    reg(args, 1)?,
+                    imm12(imm(args, 2)?)?,
+                )
+            }
+        },
+        "beq" | "bne" | "blt" | "bge" | "bltu" | "bgeu" => {
+            expect_count(verb, args, 3)?;
+            let funct3 = match verb {
+                "beq" => 0b000,
+                "bne" => 0b001,
+                "blt" => 0b100,
+                "bge" => 0b101,
+                "bltu" => 0b110,
+                _ => 0b111,
+            };
+            b_type(
+                OP_BRANCH,
+                funct3,
+                reg(args, 0)?,
+                reg(args, 1)?,
+                branch_offset(imm(args, 2)?)?,
+            )
+        }
+        "lb" | "lh" | "lw" | "lbu" | "lhu" => {
+            expect_count(verb, args, 2)?;
+            let funct3 = match verb {
+                "lb" => 0b000,
+                "lh" => 0b001,
+                "lw" => 0b010,
+                "lbu" => 0b100,
+                _ => 0b101,
+            };
+            let (imm, rs1) = offset(args, 1)?;
+            i_type(OP_LOAD, funct3, reg(args, 0)?, rs1, imm12(imm)?)
+        }
+        "sb" | "sh" | "sw" => {
+            expect_count(verb, args, 2)?;
+            let funct3 = match verb {
+                "sb" => 0b000,
+                "sh" => 0b001,
+                _ => 0b010,
+            };
+            let (imm, rs1) = offset(args, 1)?;
+            s_type(OP_STORE, funct3, rs1, reg(args, 0)?, imm12(imm)?)
+        }
+        "addi" | "slti" | "sltiu" | "xori" | "ori" | "andi" => {
+            expect_count(verb, args, 3)?;
+            let funct3 = match verb {
+                "addi" => 0b000,
+                "slti" => 0b010,
+                "sltiu" => 0b011,
+                "xori" => 0b100,
+                "ori" => 0b110,
+                _ => 0b111,
+            };
+            i_type(
+                OP_IMM,
+                funct3,
+                reg(args, 0)?,
+                reg(args, 1)?,
+                imm12(imm(args, 2)?)?,
+            )
+        }
+        "slli" | "srli" | "srai" => {
+            expect_count(verb, args, 3)?;
+            let (funct3, funct7) = match verb {
+                "slli" => (0b001, 0b0000000),
+                "srli" => (0b101, 0b0000000),
+                _ => (0b101, 0b0100000),
+            };
+            let shamt = shamt(imm(args, 2)?, xlen)?;
+            i_type(
+                OP_IMM,
+                funct3,
+                reg(args, 0)?,
+                reg(args, 1)?,
+                (funct7 << 5) | shamt,
+            )
+        }
+        "add" | "sub" | "sll" | "slt" | "sltu" | "xor" | "srl" | "sra" | "or" | "and" => {
+            expect_count(verb, args, 3)?;
+            let (funct3, funct7) = match verb {
+                "add" => (0b000, 0b0000000),
+                "sub" => (0b000, 0b0100000),
+                "sll" => (0b001, 0b0000000),
+                "slt" => (0b010, 0b0000000),
+                "sltu" => (0b011, 0b0000000),
+                "xor" => (0b100, 0b0000000),
+                "srl" => (0b101, 0b0000000),
+                "sra" => (0b101, 0b0100000),
+                "or" => (0b110, 0b0000000),
+                _ => (0b111, 0b0000000),
+            };
+            r_type(
+                OP,
+                funct3,
+                funct7,
+                reg(args, 0)?,
+                reg(args, 1)?,
+                reg(args, 2)?,
+            )
+        }
+        "ld" | "lwu" if xlen == Xlen::Rv64 => {
+            expect_count(verb, args, 2)?;
+            let funct3 = match verb {
+                "ld" => 0b011,
+                _ => 0b110,
+            };
+            let (imm, rs1) = offset(args, 1)?;
+            i_type(OP_LOAD, funct3, reg(args, 0)?, rs1, imm12(imm)?)
+        }
+        "sd" if xlen == Xlen::Rv64 => {
+            expect_count(verb, args, 2)?;
+            let (imm, rs1) = offset(args, 1)?;
+            s_type(OP_STORE, 0b011, rs1, reg(args, 0)?, imm12(imm)?)
+        }
+        "addiw" if xlen == Xlen::Rv64 => {
+            expect_count(verb, args, 3)?;
+            i_type(
+                OP_IMM_32,
+                0b000,
+                reg(args, 0)?,
+                reg(args, 1)?,
+                imm12(imm(args, 2)?)?,
+            )
+        }
+        "slliw" | "srliw" | "sraiw" if xlen == Xlen::Rv64 => {
+            expect_count(verb, args, 3)?;
+            let (funct3, funct7) = match verb {
+                "slliw" => (0b001, 0b0000000),
+                "srliw" => (0b101, 0b0000000),
+                _ => (0b101, 0b0100000),
+            };
+            let shamt = shamt(imm(args, 2)?, Xlen::Rv32)?;
+            i_type(
+                OP_IMM_32,
+                funct3,
+                reg(args, 0)?,
+                reg(args, 1)?,
+                (funct7 << 5) | shamt,
+            )
+        }
+        "addw" | "subw" | "sllw" | "srlw" | "sraw" if xlen == Xlen::Rv64 => {
+            expect_count(verb, args, 3)?;
+            let (funct3, funct7) = match verb {
+                "addw" => (0b000, 0b0000000),
+                "subw" => (0b000, 0b0100000),
+                "sllw" => (0b001, 0b0000000),
+                "srlw" => (0b101, 0b0000000),
+                _ => (0b101, 0b0100000),
+            };
+            r_type(
+                OP_32,
+                funct3,
+                funct7,
+                reg(args, 0)?,
+                reg(args, 1)?,
+                reg(args, 2)?,
+            )
+        }
+        "mul" | "mulh" | "mulhsu" | "mulhu" | "div" | "divu" | "rem" | "remu" => {
+            expect_count(verb, args, 3)?;
+            let funct3 = match verb {
+                "mul" => 0b000,
+                "mulh" => 0b001,
+                "mulhsu" => 0b010,
+                "mulhu" => 0b011,
+                "div" => 0b100,
+                "divu" => 0b101,
+                "rem" => 0b110,
+                _ => 0b111,
+            };
+            r_type(
+                OP,
+                funct3,
+                MULDIV,
+                reg(args, 0)?,
+                reg(args, 1)?,
+                reg(args, 2)?,
+            )
+        }
+        "mulw" | "divw" | "divuw" | "remw" | "remuw" if xlen == Xlen::Rv64 => {
+            expect_count(verb, args, 3)?;
+            let funct3 = match verb {
+                "mulw" => 0b000,
+                "divw" => 0b100,
+                "divuw" => 0b101,
+                "remw" => 0b110,
+                _ => 0b111,
+            };
+            r_type(
+                OP_32,
+                funct3,
+                MULDIV,
+                reg(args, 0)?,
+                reg(args, 1)?,
+                reg(args, 2)?,
+            )
+        }
+        "fence" => match args.len() {
+            0 => i_type(OP_MISC_MEM, 0b000, ZERO, ZERO, 0b1111_1111),
+            _ => {
+                expect_count(verb, args, 2)?;
+                let pred = fence_set(args.first(), 0)?;
+                let succ = fence_set(args.get(1), 1)?;
+                i_type(
+                    OP_MISC_MEM,
+                    0b000,
+                    ZERO,
+                    ZERO,
+                    i64::from((pred << 4) | succ),
+                )
+            }
+        },
+        "fence.tso" => {
+            expect_count(verb, args, 0)?;
+            0x8330_000F
+        }
+        "pause" => {
+            expect_count(verb, args, 0)?;
+            0x0100_000F
+        }
+        "ecall" => {
+            expect_count(verb, args, 0)?;
+            i_type(OP_SYSTEM, 0b000, ZERO, ZERO, 0)
+        }
+        "ebreak" => {
+            expect_count(verb, args, 0)?;
+            i_type(OP_SYSTEM, 0b000, ZERO, ZERO, 1)
+        }
+        "mret" | "sret" | "wfi" => {
+            expect_count(verb, args, 0)?;
+            match verb {
+                "mret" => 0x3020_0073,
+                "sret" => 0x1020_0073,
+                _ => 0x1050_0073,
+            }
+        }
+        // Both operands default to `zero`, which applies the fence to all addresses and spaces
+        "sfence.vma" | "hfence.vvma" | "hfence.gvma" => {
+            expect_count(verb, args, args.len().min(2))?;
+            let funct7 = match verb {
+                "sfence.vma" => 0b0001001,
+                "hfence.vvma" => 0b0010001,
+                _ => 0b0110001,
+            };
+            let rs1 = if args.is_empty() { ZERO } else { reg(args, 0)? };
+            let rs2 = if args.len() < 2 { ZERO } else { reg(args, 1)? };
+            r_type(OP_SYSTEM, 0b000, funct7, ZERO, rs1, rs2)
+        }
+        _ => return Err(ParseError::UnknownInstruction(verb.to_string())),
+    };
+
+    Ok(word)
+}
@@ -0,0 +1,425 @@
+//! Constant expressions in operands, e.g. `UART_BASE + 4*3` or `(1 << 12) - 1`.
+//!
+//! The operators and their precedence follow C. Arithmetic is done on 64-bit signed integers and
+//! overflowing it is an error; whether the result fits the immediate field it ends up in is
+//! checked when the instruction is encoded.
+
+use std::fmt::Display;
+
+use crate::parse::{is_identifier, parse_immediate, parse_string, ParseError};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnaryOp {
+    Neg,
+    Plus,
+    Not,
+    LogicalNot,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinaryOp {
+    Mul,
+    Div,
+    Rem,
+    Add,
+    Sub,
+    Shl,
+    Shr,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Eq,
+    Ne,
+    And,
+    Xor,
+    Or,
+    LogicalAnd,
+    LogicalOr,
+}
+
+impl BinaryOp {
+    /// Binding strength, higher binds tighter.
+    #[inline]
+    fn precedence(self) -> u8 {
+        match self {
+            Self::Mul | Self::Div | Self::Rem => 10,
+            Self::Add | Self::Sub => 9,
+            Self::Shl | Self::Shr => 8,
+            Self::Lt | Self::Le | Self::Gt | Self::Ge => 7,
+            Self::Eq | Self::Ne => 6,
+            Self::And => 5,
+            Self::Xor => 4,
+            Self::Or => 3,
+            Self::LogicalAnd => 2,
+            Self::LogicalOr => 1,
+        }
+    }
+
+    #[inline]
+    fn symbol(self) -> &'static str {
+        match self {
+            Self::Mul => "*",
+            Self::Div => "/",
+            Self::Rem => "%",
+            Self::Add => "+",
+            Self::Sub => "-",
+            Self::Shl => "<<",
+            Self::Shr => ">>",
+            Self::Lt => "<",
+            Self::Le => "<=",
+            Self::Gt => ">",
+            Self::Ge => ">=",
+            Self::Eq => "==",
+            Self::Ne => "!=",
+            Self::And => "&",
+            Self::Xor => "^",
+            Self::Or => "|",
+            Self::LogicalAnd => "&&",
+            Self::LogicalOr => "||",
+        }
+    }
+
+    fn apply(self, lhs: i64, rhs: i64) -> Result<i64, ParseError> {
+        let value = match self {
+            Self::Mul => lhs.checked_mul(rhs),
+            Self::Div | Self::Rem if rhs == 0 => return Err(ParseError::DivisionByZero),
+            Self::Div => lhs.checked_div(rhs),
+            Self::Rem => lhs.checked_rem(rhs),
+            Self::Add => lhs.checked_add(rhs),
+            Self::Sub => lhs.checked_sub(rhs),
+            Self::Shl => u32::try_from(rhs).ok().and_then(|rhs| lhs.checked_shl(rhs)),
+            Self::Shr => u32::try_from(rhs).ok().and_then(|rhs| lhs.checked_shr(rhs)),
+            Self::Lt => Some(i64::from(lhs < rhs)),
+            Self::Le => Some(i64::from(lhs <= rhs)),
+            Self::Gt => Some(i64::from(lhs > rhs)),
+            Self::Ge => Some(i64::from(lhs >= rhs)),
+            Self::Eq => Some(i64::from(lhs == rhs)),
+            Self::Ne => Some(i64::from(lhs != rhs)),
+            Self::And => Some(lhs & rhs),
+            Self::Xor => Some(lhs ^ rhs),
+            Self::Or => Some(lhs | rhs),
+            Self::LogicalAnd => Some(i64::from(lhs != 0 && rhs != 0)),
+            Self::LogicalOr => Some(i64::from(lhs != 0 || rhs != 0)),
+        };
+
+        value.ok_or(ParseError::Overflow)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Expr<'a> {
+    Number(i64),
+    Symbol(&'a str),
+    Unary(UnaryOp, Box<Expr<'a>>),
+    Binary(BinaryOp, Box<Expr<'a>>, Box<Expr<'a>>),
+}
+
+impl<'a> Expr<'a> {
+    /// Evaluate the expression, looking up the value of symbols with `lookup`.
+    pub fn evaluate(&self, lookup: &dyn Fn(&str) -> Option<i64>) -> Result<i64, ParseError> {
+        match self {
+            Self::Number(value) => Ok(*value),
+            Self::Symbol(name) => {
+                lookup(name).ok_or_else(|| ParseError::UnresolvedLabel(name.to_string()))
+            }
+            Self::Unary(op, expr) => {
+                let value = expr.evaluate(lookup)?;
+                match op {
+                    UnaryOp::Neg => value.checked_neg().ok_or(ParseError::Overflow),
+                    UnaryOp::Plus => Ok(value),
+                    UnaryOp::Not => Ok(!value),
+                    UnaryOp::LogicalNot => Ok(i64::from(value == 0)),
+                }
+            }
+            Self::Binary(op, lhs, rhs) => op.apply(lhs.evaluate(lookup)?, rhs.evaluate(lookup)?),
+        }
+    }
+
+    /// Evaluate an expression that may not refer to any symbols.
+    #[inline]
+    pub fn constant(&self) -> Result<i64, ParseError> {
+        self.evaluate(&|_| None)
+    }
+
+    /// All symbols the expression refers to.
+    pub fn symbols(&self) -> Vec<&'a str> {
+        let mut symbols = Vec::new();
+        self.collect_symbols(&mut symbols);
+        symbols
+    }
+
+    fn collect_symbols(&self, symbols: &mut Vec<&'a str>) {
+        match self {
+            Self::Number(_) => {}
+            Self::Symbol(name) => symbols.push(name),
+            Self::Unary(_, expr) => expr.collect_symbols(symbols),
+            Self::Binary(_, lhs, rhs) => {
+                lhs.collect_symbols(symbols);
+                rhs.collect_symbols(symbols);
+            }
+        }
+    }
+}
+
+impl<'a> Display for Expr<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Number(value) => write!(f, "{value}"),
+            Self::Symbol(name) => f.write_str(name),
+            Self::Unary(op, expr) => {
+                let op = match op {
+                    UnaryOp::Neg => "-",
+                    UnaryOp::Plus => "+",
+                    UnaryOp::Not => "~",
+                    UnaryOp::LogicalNot => "!",
+                };
+                match **expr {
+                    Self::Binary(..) => write!(f, "{op}({expr})"),
+                    _ => write!(f, "{op}{expr}"),
+                }
+            }
+            Self::Binary(op, lhs, rhs) => {
+                let operand =
+                    |f: &mut std::fmt::Formatter<'_>, expr: &Expr, strict: bool| match expr {
+                        Expr::Binary(inner, ..)
+                            if inner.precedence() < op.precedence()
+                                || (strict && inner.precedence() == op.precedence()) =>
+                        {
+                            write!(f, "({expr})")
+                        }
+                        _ => write!(f, "{expr}"),
+                    };
+                operand(f, lhs, false)?;
+                write!(f, " {} ", op.symbol())?;
+                operand(f, rhs, true)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Token<'a> {
+    Number(i64),
+    Symbol(&'a str),
+    Op(&'a str),
+    Open,
+    Close,
+}
+
+fn tokenize(s: &str) -> Result<Vec<Token<'_>>, ParseError> {
+    let invalid = || ParseError::InvalidExpression(s.to_string());
+    let mut tokens = Vec::new();
+    let mut rest = s.trim_start();
+
+    while let Some(c) = rest.chars().next() {
+        let len = if c.is_ascii_digit() {
+            let len = rest
+                .find(|c: char| !c.is_ascii_alphanumeric())
+                .unwrap_or(rest.len());
+            tokens.push(Token::Number(parse_immediate(&rest[..len])?));
+            len
+        } else if c == '\'' {
+            // Character literal, e.g. 'a' or '\n'
+            let end = rest[1..].find('\'').ok_or_else(invalid)? + 2;
+            let bytes = parse_string(&format!("\"{}\"", &rest[1..end - 1]))?;
+            let [byte] = bytes[..] else {
+                return Err(invalid());
+            };
+            tokens.push(Token::Number(i64::from(byte)));
+            end
+        } else if c.is_ascii_alphabetic() || c == '_' || c == '.' || c == '$' {
+            let len = rest
+                .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_' || c == '.' || c == '$'))
+                .unwrap_or(rest.len());
+            debug_assert!(is_identifier(&rest[..len]));
+            tokens.push(Token::Symbol(&rest[..len]));
+            len
+        } else if c == '(' {
+            tokens.push(Token::Open);
+            1
+        } else if c == ')' {
+            tokens.push(Token::Close);
+            1
+        } else {
+            let len = ["<<", ">>", "<=", ">=", "==", "!=", "&&", "||"]
+                .iter()
+                .find(|op| rest.starts_with(*op))
+                .map_or(1, |op| op.len());
+            if len == 1 && !"+-*/%<>&^|~!".contains(c) {
+                return Err(invalid());
+            }
+            tokens.push(Token::Op(&rest[..len]));
+            len
+        };
+
+        rest = rest[len..].trim_start();
+    }
+
+    Ok(tokens)
+}
+
+/// How deeply expressions may nest, keeping the recursion in parsing and evaluating them bounded.
+const MAX_DEPTH: usize = 256;
+
+struct Parser<'s, 'a> {
+    source: &'s str,
+    tokens: Vec<Token<'a>>,
+    position: usize,
+    depth: usize,
+}
+
+impl<'s, 'a> Parser<'s, 'a> {
+    #[inline]
+    fn peek(&self) -> Option<Token<'a>> {
+        self.tokens.get(self.position).copied()
+    }
+
+    #[inline]
+    fn next(&mut self) -> Option<Token<'a>> {
+        let token = self.peek();
+        self.position += 1;
+        token
+    }
+
+    #[inline]
+    fn invalid(&self) -> ParseError {
+        ParseError::InvalidExpression(self.source.to_string())
+    }
+
+    /// Go one level deeper into the expression.
+    fn enter(&mut self) -> Result<(), ParseError> {
+        self.depth += 1;
+        match self.depth > MAX_DEPTH {
+            true => Err(ParseError::NestingDepth),
+            false => Ok(()),
+        }
+    }
+
+    fn primary(&mut self) -> Result<Expr<'a>, ParseError> {
+        match self.next() {
+            Some(Token::Number(value)) => Ok(Expr::Number(value)),
+            Some(Token::Symbol(name)) => Ok(Expr::Symbol(name)),
+            Some(Token::Open) => {
+                self.enter()?;
+                let expr = self.binary(0)?;
+                self.depth -= 1;
+                match self.next() {
+                    Some(Token::Close) => Ok(expr),
+                    _ => Err(self.invalid()),
+                }
+            }
+            Some(Token::Op(op)) => {
+                let op = match op {
+                    "-" => UnaryOp::Neg,
+                    "+" => UnaryOp::Plus,
+                    "~" => UnaryOp::Not,
+                    "!" => UnaryOp::LogicalNot,
+                    _ => return Err(self.invalid()),
+                };
+                self.enter()?;
+                let expr = Expr::Unary(op, Box::new(self.primary()?));
+                self.depth -= 1;
+                Ok(expr)
+            }
+            _ => Err(self.invalid()),
+        }
+    }
+
+    /// Precedence climbing over all binary operators binding tighter than `min`.
+    fn binary(&mut self, min: u8) -> Result<Expr<'a>, ParseError> {
+        let depth = self.depth;
+        let mut lhs = self.primary()?;
+
+        while let Some(Token::Op(op)) = self.peek() {
+            let op = match op {
+                "*" => BinaryOp::Mul,
+                "/" => BinaryOp::Div,
+                "%" => BinaryOp::Rem,
+                "+" => BinaryOp::Add,
+                "-" => BinaryOp::Sub,
+                "<<" => BinaryOp::Shl,
+                ">>" => BinaryOp::Shr,
+                "<" => BinaryOp::Lt,
+                "<=" => BinaryOp::Le,
+                ">" => BinaryOp::Gt,
+                ">=" => BinaryOp::Ge,
+                "==" => BinaryOp::Eq,
+                "!=" => BinaryOp::Ne,
+                "&" => BinaryOp::And,
+                "^" => BinaryOp::Xor,
+                "|" => BinaryOp::Or,
+                "&&" => BinaryOp::LogicalAnd,
+                "||" => BinaryOp::LogicalOr,
+                _ => return Err(self.invalid()),
+            };
+            if op.precedence() <= min {
+                break;
+            }
+
+            self.position += 1;
+            let rhs = self.binary(op.precedence())?;
+            lhs = Expr::Binary(op, Box::new(lhs), Box::new(rhs));
+            // Chains like `1 + 1 + 1` nest to the left
+            self.enter()?;
+        }
+
+        self.depth = depth;
+        Ok(lhs)
+    }
+}
+
+/// Parse an expression.
+pub fn parse_expr(s: &str) -> Result<Expr<'_>, ParseError> {
+    let mut parser = Parser {
+        source: s,
+        tokens: tokenize(s)?,
+        position: 0,
+        depth: 0,
+    };
+
+    let expr = parser.binary(0)?;
+    if parser.position != parser.tokens.len() {
+        return Err(parser.invalid());
+    }
+
+    Ok(expr)
+}
+
+#[test]
+fn precedence() {
+    let lookup = |name: &str| (name == "UART_BASE").then_some(0x1000_0000);
+    let eval = |s| parse_expr(s).and_then(|expr| expr.evaluate(&lookup));
+
+    assert_eq!(eval("UART_BASE + 4*3"), Ok(0x1000_000c));
+    assert_eq!(eval("(1 << 12) - 1"), Ok(0xfff));
+    assert_eq!(eval("1 + 2 << 3 | 1"), Ok(25));
+    assert_eq!(eval("10 - 4 - 3"), Ok(3));
+    assert_eq!(eval("-~0x0f & 0xff"), Ok(0x10));
+    assert_eq!(eval("3 > 2 && !0"), Ok(1));
+    assert_eq!(eval("'a' + 1"), Ok(0x62));
+    assert_eq!(eval("1 / 0"), Err(ParseError::DivisionByZero));
+    assert_eq!(eval("1 << 64"), Err(ParseError::Overflow));
+    assert_eq!(
+        eval("UNKNOWN"),
+        Err(ParseError::UnresolvedLabel("UNKNOWN".to_string()))
+    );
+    assert_eq!(
+        parse_expr("a - (b - c) * 2").unwrap().to_string(),
+        "a - (b - c) * 2"
+    );
+}
+
+#[test]
+fn nesting() {
+    let nested = format!("{}1{}", "(".repeat(200), ")".repeat(200));
+    assert_eq!(parse_expr(&nested).and_then(|expr| expr.constant()), Ok(1));
+
+    for expr in [
+        format!("{}1{}", "(".repeat(50_000), ")".repeat(50_000)),
+        format!("{}1", "-".repeat(50_000)),
+        format!("{}1", "1 + ".repeat(50_000)),
+    ] {
+        assert_eq!(parse_expr(&expr), Err(ParseError::NestingDepth));
+    }
+}
@@ -0,0 +1,205 @@
+//! Instruction word types for the extensions that are defined in this crate rather than in
+//! `riscv_encoding`.
+
+#[inline]
+pub(crate) const fn sext(value: u32, bits: u32) -> i32 {
+    ((value << (32 - bits)) as i32) >> (32 - bits)
+}
+
+/// Accessor for one field of an instruction word.
+macro_rules! field {
+    (rd) => {
+        #[inline]
+        pub fn rd(&self) -> u8 {
+            ((self.0 >> 7) & 0x1f) as u8
+        }
+    };
+    (rs1) => {
+        #[inline]
+        pub fn rs1(&self) -> u8 {
+            ((self.0 >> 15) & 0x1f) as u8
+        }
+    };
+    (rs2) => {
+        #[inline]
+        pub fn rs2(&self) -> u8 {
+            ((self.0 >> 20) & 0x1f) as u8
+        }
+    };
+    // Third source register of the fused multiply-add instructions
+    (rs3) => {
+        #[inline]
+        pub fn rs3(&self) -> u8 {
+            (self.0 >> 27) as u8
+        }
+    };
+    // Rounding mode of the floating-point instructions
+    (rm) => {
+        #[inline]
+        pub fn rm(&self) -> u8 {
+            ((self.0 >> 12) & 0b111) as u8
+        }
+    };
+    // I-type immediate
+    (imm) => {
+        #[inline]
+        pub fn imm(&self) -> i32 {
+            $crate::fields::sext(self.0 >> 20, 12)
+        }
+    };
+    // S-type immediate
+    (s_imm) => {
+        #[inline]
+        pub fn imm(&self) -> i32 {
+            $crate::fields::sext(((self.0 >> 25) << 5) | ((self.0 >> 7) & 0x1f), 12)
+        }
+    };
+    // B-type immediate
+    (b_imm) => {
+        #[inline]
+        pub fn imm(&self) -> i32 {
+            let word = self.0;
+            let imm = ((word >> 31) << 12)
+                | (((word >> 7) & 1) << 11)
+                | (((word >> 25) & 0x3f) << 5)
+                | (((word >> 8) & 0xf) << 1);
+            $crate::fields::sext(imm, 13)
+        }
+    };
+    // J-type immediate
+    (j_imm) => {
+        #[inline]
+        pub fn imm(&self) -> i32 {
+            let word = self.0;
+            let imm = ((word >> 31) << 20)
+                | (((word >> 12) & 0xff) << 12)
+                | (((word >> 20) & 1) << 11)
+                | (((word >> 21) & 0x3ff) << 1);
+            $crate::fields::sext(imm, 21)
+        }
+    };
+    // U-type immediate, the upper 20 bits as printed by `lui`
+    (u_imm) => {
+        #[inline]
+        pub fn imm(&self) -> i32 {
+            (self.0 >> 12) as i32
+        }
+    };
+    // CSR number of the Zicsr instructions
+    (csr) => {
+        #[inline]
+        pub fn csr(&self) -> u16 {
+            (self.0 >> 20) as u16
+        }
+    };
+    // 5-bit immediate in the rs1 field of the Zicsr immediate forms
+    (uimm) => {
+        #[inline]
+        pub fn uimm(&self) -> u8 {
+            ((self.0 >> 15) & 0x1f) as u8
+        }
+    };
+    // 6-bit shift amount of the RV64I immediate shifts
+    (shamt) => {
+        #[inline]
+        pub fn shamt(&self) -> u8 {
+            ((self.0 >> 20) & 0x3f) as u8
+        }
+    };
+    // Acquire and release ordering bits of the atomics
+    (aq) => {
+        #[inline]
+        pub fn aq(&self) -> bool {
+            (self.0 >> 26) & 1 != 0
+        }
+    };
+    (rl) => {
+        #[inline]
+        pub fn rl(&self) -> bool {
+            (self.0 >> 25) & 1 != 0
+        }
+    };
+    // 5-bit shift amount of the 32-bit `*w` shifts
+    (shamtw) => {
+        #[inline]
+        pub fn shamt(&self) -> u8 {
+            ((self.0 >> 20) & 0x1f) as u8
+        }
+    };
+    // Vector registers of the V extension, in the same positions as `rd`, `rs1` and `rs2`. `vd`
+    // is also the data register `vs3` of the stores.
+    (vd) => {
+        #[inline]
+        pub fn vd(&self) -> u8 {
+            ((self.0 >> 7) & 0x1f) as u8
+        }
+    };
+    (vs1) => {
+        #[inline]
+        pub fn vs1(&self) -> u8 {
+            ((self.0 >> 15) & 0x1f) as u8
+        }
+    };
+    (vs2) => {
+        #[inline]
+        pub fn vs2(&self) -> u8 {
+            ((self.0 >> 20) & 0x1f) as u8
+        }
+    };
+    // Set for vector instructions that are not masked by `v0.t`
+    (vm) => {
+        #[inline]
+        pub fn vm(&self) -> bool {
+            (self.0 >> 25) & 1 != 0
+        }
+    };
+}
+
+/// A newtype over an instruction word with accessors for the listed fields.
+///
+/// Values are only created by the decoder of the enum in the module invoking this.
+macro_rules! instruction {
+    ($(#[$attr:meta])* $name:ident { $($field:ident),* $(,)? }) => {
+        $(#[$attr])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        pub struct $name(u32);
+
+        impl $name {
+            /// The raw instruction word.
+            #[inline]
+            pub fn word(&self) -> u32 {
+                self.0
+            }
+
+            $($crate::fields::field!($field);)*
+        }
+    };
+}
+
+/// A compressed instruction with accessors for the listed fields of the 32-bit instruction it
+/// expands to.
+macro_rules! compressed {
+    ($(#[$attr:meta])* $name:ident { $($field:ident),* $(,)? }) => {
+        $(#[$attr])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        pub struct $name(u32, u16);
+
+        impl $name {
+            /// The raw 16-bit instruction.
+            #[inline]
+            pub fn half(&self) -> u16 {
+                self.1
+            }
+
+            /// The 32-bit instruction this is the compressed form of.
+            #[inline]
+            pub fn word(&self) -> u32 {
+                self.0
+            }
+
+            $($crate::fields::field!($field);)*
+        }
+    };
+}
+
+pub(crate) use {compressed, field, instruction};
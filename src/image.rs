@@ -0,0 +1,775 @@
+//! Memory images as flat binaries, Intel HEX and Motorola S-record files.
+//!
+//! An [`Image`] is a set of segments of bytes at absolute addresses. Images can be built from
+//! assembled programs and written in any of the formats, or read from them, e.g. to disassemble
+//! a HEX file at the addresses it is loaded to.
+//!
+//! Images can also be exported as memory initialization files for HDL simulation and synthesis:
+//! Verilog `$readmemh`/`$readmemb` text and Xilinx `.coe` files.
+
+use std::fmt::{Display, Write};
+
+use crate::assembler::Program;
+use crate::disassemble::{disassemble, Listing};
+use crate::AsmFormatOptions;
+
+/// Data bytes per Intel HEX or S-record data record.
+const RECORD_SIZE: usize = 16;
+
+const IHEX_DATA: u8 = 0x00;
+const IHEX_END_OF_FILE: u8 = 0x01;
+const IHEX_EXTENDED_SEGMENT_ADDRESS: u8 = 0x02;
+const IHEX_START_SEGMENT_ADDRESS: u8 = 0x03;
+const IHEX_EXTENDED_LINEAR_ADDRESS: u8 = 0x04;
+const IHEX_START_LINEAR_ADDRESS: u8 = 0x05;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ImageError {
+    /// A line that is not a well-formed record.
+    InvalidRecord {
+        line: usize,
+    },
+    ChecksumMismatch {
+        line: usize,
+    },
+    UnknownRecordType {
+        line: usize,
+        kind: u8,
+    },
+    /// Data extending beyond the 32-bit address space.
+    AddressOverflow {
+        line: usize,
+    },
+}
+
+impl Display for ImageError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidRecord { line } => write!(f, "line {line}: invalid record"),
+            Self::ChecksumMismatch { line } => write!(f, "line {line}: checksum mismatch"),
+            Self::UnknownRecordType { line, kind } => {
+                write!(f, "line {line}: unknown record type {kind}")
+            }
+            Self::AddressOverflow { line } => {
+                write!(
+                    f,
+                    "line {line}: data extends beyond the 32-bit address space"
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for ImageError {}
+
+/// Contiguous bytes at an absolute address.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Segment {
+    pub address: u32,
+    pub data: Vec<u8>,
+}
+
+impl Segment {
+    #[inline]
+    fn end(&self) -> u64 {
+        u64::from(self.address) + self.data.len() as u64
+    }
+}
+
+/// Segments of memory, ordered by address, with an optional entry point.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Image {
+    pub segments: Vec<Segment>,
+    pub entry: Option<u32>,
+}
+
+impl Image {
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add `data` at `address`, overwriting any bytes already there, and join it with the segments
+    /// it overlaps or is adjacent to.
+    pub fn insert(&mut self, address: u32, data: &[u8]) {
+        if data.is_empty() {
+            return;
+        }
+
+        // The segments from `first` to `last` overlap or touch the new data
+        let start = u64::from(address);
+        let end = start + data.len() as u64;
+        let first = self
+            .segments
+            .partition_point(|segment| segment.end() < start);
+        let mut last = self
+            .segments
+            .partition_point(|segment| u64::from(segment.address) <= end);
+        if first == last || u64::from(self.segments[first].address) > start {
+            let segment = Segment {
+                address,
+                data: Vec::new(),
+            };
+            self.segments.insert(first, segment);
+            last += 1;
+        }
+
+        let following = self.segments.drain(first + 1..last).collect::<Vec<_>>();
+        let segment = &mut self.segments[first];
+        let base = u64::from(segment.address);
+        for (offset, data) in following
+            .iter()
+            .map(|next| (u64::from(next.address) - base, &next.data[..]))
+            .chain([(start - base, data)])
+        {
+            let offset = offset as usize;
+            if segment.data.len() < offset + data.len() {
+                segment.data.resize(offset + data.len(), 0);
+            }
+            segment.data[offset..offset + data.len()].copy_from_slice(data);
+        }
+    }
+
+    /// The sections of `program` that have contents.
+    pub fn from_program(program: &Program) -> Self {
+        let mut image = Self::new();
+        for section in program.sections().iter().filter(|s| !s.is_nobits()) {
+            image.insert(section.address(), section.data());
+        }
+        image
+    }
+
+    /// A flat binary loaded at `base_address`.
+    #[inline]
+    pub fn from_binary(bytes: &[u8], base_address: u32) -> Self {
+        let mut image = Self::new();
+        image.insert(base_address, bytes);
+        image
+    }
+
+    /// The lowest address of any segment.
+    #[inline]
+    pub fn base_address(&self) -> u32 {
+        self.segments.first().map_or(0, |segment| segment.address)
+    }
+
+    /// The image as a flat binary starting at [`Image::base_address`], with gaps between segments
+    /// filled with zeroes.
+    pub fn to_binary(&self) -> Vec<u8> {
+        let base = self.base_address();
+        let mut bytes = Vec::new();
+
+        for segment in &self.segments {
+            let start = (segment.address - base) as usize;
+            bytes.resize(start, 0);
+            bytes.extend_from_slice(&segment.data);
+        }
+
+        bytes
+    }
+
+    /// Parse an Intel HEX file, with 16-bit, segmented or linear 32-bit addresses.
+    pub fn from_ihex(text: &str) -> Result<Self, ImageError> {
+        let mut image = Self::new();
+        let mut base = 0u32;
+
+        for (index, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            let number = index + 1;
+            let invalid = ImageError::InvalidRecord { line: number };
+            let bytes = line
+                .strip_prefix(':')
+                .and_then(parse_hex_bytes)
+                .ok_or(invalid.clone())?;
+
+            let [len, address_high, address_low, kind, ref rest @ ..] = bytes[..] else {
+                return Err(invalid);
+            };
+            if rest.len() != usize::from(len) + 1 {
+                return Err(invalid);
+            }
+            if bytes.iter().fold(0u8, |sum, byte| sum.wrapping_add(*byte)) != 0 {
+                return Err(ImageError::ChecksumMismatch { line: number });
+            }
+
+            let data = &rest[..usize::from(len)];
+            let value = || match data {
+                [a, b] => Ok(u32::from(u16::from_be_bytes([*a, *b]))),
+                [a, b, c, d] => Ok(u32::from_be_bytes([*a, *b, *c, *d])),
+                _ => Err(invalid.clone()),
+            };
+
+            match kind {
+                IHEX_DATA => {
+                    let address = base
+                        .wrapping_add(u32::from(u16::from_be_bytes([address_high, address_low])));
+                    if u64::from(address) + data.len() as u64 > 1 << 32 {
+                        return Err(ImageError::AddressOverflow { line: number });
+                    }
+                    image.insert(address, data);
+                }
+                IHEX_END_OF_FILE => break,
+                IHEX_EXTENDED_SEGMENT_ADDRESS => base = value()? << 4,
+                IHEX_EXTENDED_LINEAR_ADDRESS => base = value()? << 16,
+                IHEX_START_SEGMENT_ADDRESS => {
+                    let value = value()?;
+                    image.entry = Some(((value >> 16) << 4) + (value & 0xffff));
+                }
+                IHEX_START_LINEAR_ADDRESS => image.entry = Some(value()?),
+                kind => return Err(ImageError::UnknownRecordType { line: number, kind }),
+            }
+        }
+
+        Ok(image)
+    }
+
+    /// Write the image as Intel HEX with linear 32-bit addresses.
+    pub fn to_ihex(&self) -> String {
+        let mut out = String::new();
+        let mut upper = 0;
+
+        for segment in &self.segments {
+            for (address, chunk) in records(segment, true) {
+                if address >> 16 != upper {
+                    upper = address >> 16;
+                    let data = (upper as u16).to_be_bytes();
+                    write_ihex(&mut out, 0, IHEX_EXTENDED_LINEAR_ADDRESS, &data);
+                }
+                write_ihex(&mut out, address as u16, IHEX_DATA, chunk);
+            }
+        }
+
+        if let Some(entry) = self.entry {
+            write_ihex(&mut out, 0, IHEX_START_LINEAR_ADDRESS, &entry.to_be_bytes());
+        }
+        write_ihex(&mut out, 0, IHEX_END_OF_FILE, &[]);
+
+        out
+    }
+
+    /// Parse a Motorola S-record file with 16, 24 or 32-bit addresses.
+    pub fn from_srec(text: &str) -> Result<Self, ImageError> {
+        let mut image = Self::new();
+
+        for (index, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            let number = index + 1;
+            let invalid = ImageError::InvalidRecord { line: number };
+            let mut chars = line.chars();
+            let (Some('S'), Some(kind)) = (chars.next(), chars.next()) else {
+                return Err(invalid);
+            };
+            let kind = kind.to_digit(10).ok_or(invalid.clone())? as u8;
+            let bytes = parse_hex_bytes(chars.as_str()).ok_or(invalid.clone())?;
+
+            let [len, ref rest @ ..] = bytes[..] else {
+                return Err(invalid);
+            };
+            if rest.len() != usize::from(len) {
+                return Err(invalid);
+            }
+            if bytes.iter().fold(0u8, |sum, byte| sum.wrapping_add(*byte)) != 0xff {
+                return Err(ImageError::ChecksumMismatch { line: number });
+            }
+
+            let address_len = match kind {
+                0 | 1 | 5 | 9 => 2,
+                2 | 6 | 8 => 3,
+                3 | 7 => 4,
+                kind => return Err(ImageError::UnknownRecordType { line: number, kind }),
+            };
+            if rest.len() < address_len + 1 {
+                return Err(invalid);
+            }
+            let (address, data) = rest[..rest.len() - 1].split_at(address_len);
+            let address = address
+                .iter()
+                .fold(0, |address, byte| (address << 8) | u32::from(*byte));
+
+            match kind {
+                1..=3 => {
+                    if u64::from(address) + data.len() as u64 > 1 << 32 {
+                        return Err(ImageError::AddressOverflow { line: number });
+                    }
+                    image.insert(address, data);
+                }
+                7..=9 => image.entry = Some(address),
+                // Header and record counts
+                _ => {}
+            }
+        }
+
+        Ok(image)
+    }
+
+    /// Write the image as S-records with 32-bit addresses (`S3`), followed by a record count and
+    /// the entry point, or `0` without one.
+    pub fn to_srec(&self) -> String {
+        let mut out = String::new();
+        write_srec(&mut out, 0, &[0, 0], &[]);
+
+        let mut count = 0u32;
+        for segment in &self.segments {
+            for (address, chunk) in records(segment, false) {
+                write_srec(&mut out, 3, &address.to_be_bytes(), chunk);
+                count += 1;
+            }
+        }
+
+        match u16::try_from(count) {
+            Ok(count) => write_srec(&mut out, 5, &count.to_be_bytes(), &[]),
+            Err(_) => write_srec(&mut out, 6, &count.to_be_bytes()[1..], &[]),
+        }
+        write_srec(&mut out, 7, &self.entry.unwrap_or(0).to_be_bytes(), &[]);
+
+        out
+    }
+
+    /// The words of the image, from the origin of `options`. Data below the origin is left out.
+    fn memory_lines(&self, options: &MemoryFormatOptions, markers: bool) -> Vec<MemoryLine> {
+        let width = options.word_width as u64;
+        let origin = u64::from(options.origin.unwrap_or(self.base_address()));
+        let origin = origin - origin % width;
+
+        // Words are aligned to their width, also when a segment is not, so segments can share one
+        let mut words: Vec<(u64, [u8; 8])> = Vec::new();
+        for segment in &self.segments {
+            for (i, byte) in segment.data.iter().enumerate() {
+                let address = u64::from(segment.address) + i as u64;
+                if address < origin {
+                    continue;
+                }
+
+                let start = address - address % width;
+                let offset = (address - start) as usize;
+                match words.last_mut() {
+                    Some((last, bytes)) if *last == start => bytes[offset] = *byte,
+                    _ => {
+                        let mut bytes = [0; 8];
+                        bytes[offset] = *byte;
+                        words.push((start, bytes));
+                    }
+                }
+            }
+        }
+
+        let mut lines = Vec::new();
+        let mut next = origin;
+        for (i, (start, bytes)) in words.into_iter().enumerate() {
+            if markers && (i == 0 || start != next) {
+                lines.push(MemoryLine::Marker(((start - origin) / width) as u32));
+            }
+            // Without markers, gaps are filled with zeroes
+            while !markers && next < start {
+                lines.push(MemoryLine::Word {
+                    address: next as u32,
+                    value: 0,
+                });
+                next += width;
+            }
+
+            lines.push(MemoryLine::Word {
+                address: start as u32,
+                value: u64::from_le_bytes(bytes),
+            });
+            next = start + width;
+        }
+
+        lines
+    }
+
+    fn to_readmem(&self, options: &MemoryFormatOptions, radix: u32) -> String {
+        let mut out = String::new();
+
+        for line in self.memory_lines(options, options.address_markers) {
+            let value = match line {
+                MemoryLine::Marker(index) => {
+                    writeln!(out, "@{index:x}").unwrap();
+                    continue;
+                }
+                MemoryLine::Word { value, .. } => value,
+            };
+
+            match radix {
+                16 => write!(out, "{value:0width$x}", width = 2 * options.word_width),
+                _ => write!(out, "{value:0width$b}", width = 8 * options.word_width),
+            }
+            .unwrap();
+            if let Some(comment) = line.comment(options) {
+                write!(out, " // {comment}").unwrap();
+            }
+            out.push('\n');
+        }
+
+        out
+    }
+
+    /// Write the image as hexadecimal text for Verilog's `$readmemh`, one word per line.
+    ///
+    /// Address markers are word indices from the origin of `options`.
+    #[inline]
+    pub fn to_readmemh(&self, options: &MemoryFormatOptions) -> String {
+        self.to_readmem(options, 16)
+    }
+
+    /// Write the image as binary text for Verilog's `$readmemb`, one word per line.
+    #[inline]
+    pub fn to_readmemb(&self, options: &MemoryFormatOptions) -> String {
+        self.to_readmem(options, 2)
+    }
+
+    /// Write the image as a Xilinx `.coe` file with a hexadecimal initialization vector.
+    ///
+    /// The vector cannot contain gaps, so address markers are not used. It cannot contain
+    /// comments either, so any disassembly is written as a comment block before it.
+    pub fn to_coe(&self, options: &MemoryFormatOptions) -> String {
+        let lines = self.memory_lines(options, false);
+        let mut out = String::new();
+
+        for line in &lines {
+            if let (MemoryLine::Word { address, value }, Some(comment)) =
+                (line, line.comment(options))
+            {
+                let width = 2 * options.word_width;
+                writeln!(out, "; {address:08x}: {value:0width$x}  {comment}").unwrap();
+            }
+        }
+
+        out.push_str("memory_initialization_radix=16;\nmemory_initialization_vector=\n");
+        for (i, line) in lines.iter().enumerate() {
+            let MemoryLine::Word { value, .. } = line else {
+                continue;
+            };
+            let end = if i + 1 == lines.len() { ';' } else { ',' };
+            writeln!(out, "{value:0width$x}{end}", width = 2 * options.word_width).unwrap();
+        }
+
+        out
+    }
+
+    /// Disassemble every segment at the address it is loaded to.
+    pub fn listing(&self, options: AsmFormatOptions) -> String {
+        let listing = Listing::new(options);
+        let mut out = String::new();
+
+        for segment in &self.segments {
+            out.push_str(&format!(
+                "\nDisassembly of segment at {:08x}:\n\n",
+                segment.address
+            ));
+            listing
+                .write(&mut out, &segment.data, segment.address)
+                .unwrap();
+        }
+
+        out
+    }
+}
+
+/// Layout of memory initialization files, see [`Image::to_readmemh`].
+#[derive(Debug, Clone)]
+pub struct MemoryFormatOptions {
+    word_width: usize,
+    address_markers: bool,
+    origin: Option<u32>,
+    disassembly: Option<AsmFormatOptions>,
+}
+
+impl MemoryFormatOptions {
+    /// Bytes per memory word, from `1` to `8`. Words are little-endian.
+    #[inline]
+    pub fn word_width(mut self, bytes: usize) -> Self {
+        assert!((1..=8).contains(&bytes), "word width must be 1 to 8 bytes");
+        self.word_width = bytes;
+        self
+    }
+
+    /// Start every segment with an `@index` marker rather than filling the gaps between segments
+    /// with zeroes.
+    #[inline]
+    pub fn address_markers(mut self, address_markers: bool) -> Self {
+        self.address_markers = address_markers;
+        self
+    }
+
+    /// The address of the first word of the memory. Defaults to the base address of the image.
+    #[inline]
+    pub fn origin(mut self, address: u32) -> Self {
+        self.origin = Some(address);
+        self
+    }
+
+    /// Add the disassembly of the instructions in every word as a comment.
+    #[inline]
+    pub fn disassembly(mut self, options: AsmFormatOptions) -> Self {
+        self.disassembly = Some(options);
+        self
+    }
+}
+
+impl Default for MemoryFormatOptions {
+    fn default() -> Self {
+        Self {
+            word_width: 4,
+            address_markers: true,
+            origin: None,
+            disassembly: None,
+        }
+    }
+}
+
+/// A line of a memory initialization file.
+enum MemoryLine {
+    /// Continue at the word with this index.
+    Marker(u32),
+    Word {
+        address: u32,
+        value: u64,
+    },
+}
+
+impl MemoryLine {
+    /// The disassembly of the instructions in the word at `address`, if there are any.
+    fn comment(&self, options: &MemoryFormatOptions) -> Option<String> {
+        let (Self::Word { address, value }, Some(format)) = (self, &options.disassembly) else {
+            return None;
+        };
+
+        let bytes = &value.to_le_bytes()[..options.word_width];
+        let instructions = disassemble(bytes, *address)
+            .xlen(format.xlen)
+            .filter(|entry| entry.instruction.is_some())
+            .map(|entry| entry.display(format).to_string())
+            .collect::<Vec<_>>();
+        (!instructions.is_empty()).then(|| instructions.join("; "))
+    }
+}
+
+/// Split `segment` into data records. Intel HEX records may not cross a 64 KiB boundary.
+fn records(segment: &Segment, split_at_64k: bool) -> impl Iterator<Item = (u32, &[u8])> {
+    let mut address = segment.address;
+    let mut data = &segment.data[..];
+
+    std::iter::from_fn(move || {
+        if data.is_empty() {
+            return None;
+        }
+
+        let mut len = data.len().min(RECORD_SIZE);
+        if split_at_64k {
+            len = len.min(0x1_0000 - (address & 0xffff) as usize);
+        }
+
+        let (chunk, rest) = data.split_at(len);
+        let record = (address, chunk);
+        address = address.wrapping_add(len as u32);
+        data = rest;
+        Some(record)
+    })
+}
+
+fn parse_hex_bytes(text: &str) -> Option<Vec<u8>> {
+    if !text.len().is_multiple_of(2) || !text.is_ascii() {
+        return None;
+    }
+
+    (0..text.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&text[i..i + 2], 16).ok())
+        .collect()
+}
+
+fn write_ihex(out: &mut String, address: u16, kind: u8, data: &[u8]) {
+    let mut bytes = vec![data.len() as u8];
+    bytes.extend_from_slice(&address.to_be_bytes());
+    bytes.push(kind);
+    bytes.extend_from_slice(data);
+    let sum = bytes.iter().fold(0u8, |sum, byte| sum.wrapping_add(*byte));
+    bytes.push(sum.wrapping_neg());
+
+    out.push(':');
+    for byte in bytes {
+        write!(out, "{byte:02X}").unwrap();
+    }
+    out.push('\n');
+}
+
+fn write_srec(out: &mut String, kind: u8, address: &[u8], data: &[u8]) {
+    let mut bytes = vec![(address.len() + data.len() + 1) as u8];
+    bytes.extend_from_slice(address);
+    bytes.extend_from_slice(data);
+    let sum = bytes.iter().fold(0u8, |sum, byte| sum.wrapping_add(*byte));
+    bytes.push(!sum);
+
+    write!(out, "S{kind}").unwrap();
+    for byte in bytes {
+        write!(out, "{byte:02X}").unwrap();
+    }
+    out.push('\n');
+}
+
+#[test]
+fn formats() {
+    let program = crate::assembler::assemble(
+        "
+            addi a0, zero, 1
+            ret
+            .data
+            .ascii \"0123456789abcdef!\"
+        ",
+        0x8000_fff8,
+    )
+    .unwrap();
+    let mut image = Image::from_program(&program);
+    image.entry = Some(0x8000_fff8);
+
+    let ihex = image.to_ihex();
+    assert_eq!(
+        ihex,
+        "\
+:0200000480007A
+:08FFF8001305100067800000F2
+:02000004800179
+:10000000303132333435363738396162636465668E
+:0100100021CE
+:040000058000FFF880
+:00000001FF
+"
+    );
+    assert_eq!(Image::from_ihex(&ihex), Ok(image.clone()));
+
+    let srec = image.to_srec();
+    assert_eq!(
+        srec,
+        "\
+S0030000FC
+S3158000FFF813051000678000003031323334353637C8
+S30E8001000838396162636465662181
+S5030002FA
+S7058000FFF883
+"
+    );
+    assert_eq!(Image::from_srec(&srec), Ok(image.clone()));
+    assert_eq!(image.to_binary(), program.to_bytes());
+
+    assert_eq!(
+        Image::from_ihex(":0300300002337A1F"),
+        Err(ImageError::ChecksumMismatch { line: 1 })
+    );
+    assert_eq!(
+        Image::from_ihex(":0300300002337A1E\n:00000001FF")
+            .unwrap()
+            .listing(AsmFormatOptions::default()),
+        "
+Disassembly of segment at 00000030:
+
+00000030:  3302      c.fldsp\tft6,32(sp)
+00000032:  7a        .byte\t0x7a
+"
+    );
+}
+
+#[test]
+fn memory_formats() {
+    let mut image = Image::new();
+    image.insert(
+        0x8000_0000,
+        &[0x13, 0x05, 0x10, 0x00, 0x67, 0x80, 0x00, 0x00],
+    );
+    image.insert(0x8000_0012, &[0xaa, 0xbb]);
+
+    let options = MemoryFormatOptions::default();
+    assert_eq!(
+        image.to_readmemh(
+            &options.clone().disassembly(
+                AsmFormatOptions::default()
+                    .verb_arg_spacing(" ")
+                    .arg_spacing(" ")
+            )
+        ),
+        "\
+@0
+00100513 // li a0, 0x1
+00008067 // ret
+@4
+bbaa0000 // c.fsdsp fa0, 496(sp)
+"
+    );
+    // The gap up to the last segment is filled with zeroes
+    let readmemb = image.to_readmemb(&options.clone().word_width(2).address_markers(false));
+    let lines = readmemb.lines().collect::<Vec<_>>();
+    assert_eq!(lines.len(), 10);
+    assert_eq!(
+        lines[..4],
+        [
+            "0000010100010011",
+            "0000000000010000",
+            "1000000001100111",
+            "0000000000000000"
+        ]
+    );
+    assert_eq!(
+        image.to_coe(&options.clone().origin(0x7fff_fff8)),
+        "\
+memory_initialization_radix=16;
+memory_initialization_vector=
+00000000,
+00000000,
+00100513,
+00008067,
+00000000,
+00000000,
+bbaa0000;
+"
+    );
+
+    // Data below the origin is left out, also without markers
+    let mut image = Image::new();
+    image.insert(0x100, &[1, 2, 3, 4]);
+    image.insert(0x200, &[5, 6, 7, 8]);
+    assert_eq!(
+        image.to_readmemh(&options.clone().origin(0x200)),
+        "@0\n08070605\n"
+    );
+    assert_eq!(
+        image.to_readmemh(&options.clone().origin(0x300).address_markers(false)),
+        ""
+    );
+    // Segments sharing a word are written as one word
+    let mut image = Image::new();
+    image.insert(0x100, &[1, 2]);
+    image.insert(0x103, &[3]);
+    assert_eq!(image.segments.len(), 2);
+    assert_eq!(image.to_readmemh(&options), "@0\n03000201\n");
+}
+
+#[test]
+fn overlapping_inserts() {
+    let mut image = Image::new();
+    image.insert(0x100, &[1, 2, 3, 4]);
+    image.insert(0x100, &[9]);
+    assert_eq!(image.to_binary(), [9, 2, 3, 4]);
+
+    // Joining the segments on either side
+    image.insert(0x108, &[5, 6]);
+    image.insert(0x0fe, &[7, 8]);
+    image.insert(0x103, &[0xa, 0xb, 0xc, 0xd, 0xe]);
+    assert_eq!(
+        image.segments,
+        [Segment {
+            address: 0x0fe,
+            data: vec![7, 8, 9, 2, 3, 0xa, 0xb, 0xc, 0xd, 0xe, 5, 6]
+        }]
+    );
+
+    let ihex = ":0401000001020304F1\n:01010200AA52\n:00000001FF";
+    assert_eq!(Image::from_ihex(ihex).unwrap().to_binary(), [1, 2, 0xaa, 4]);
+}
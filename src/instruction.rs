@@ -0,0 +1,227 @@
+//! Instructions of every supported extension behind a single type, decoded for a given
+//! [`Xlen`].
+
+use riscv_encoding::rv32i::Rv32IInstruction;
+
+use crate::privileged::PrivilegedInstruction;
+use crate::rv32a::Rv32AInstruction;
+use crate::rv32b::Rv32BInstruction;
+use crate::rv32d::Rv32DInstruction;
+use crate::rv32f::Rv32FInstruction;
+use crate::rv32m::Rv32MInstruction;
+use crate::rv64a::Rv64AInstruction;
+use crate::rv64b::Rv64BInstruction;
+use crate::rv64d::Rv64DInstruction;
+use crate::rv64f::Rv64FInstruction;
+use crate::rv64i::Rv64IInstruction;
+use crate::rv64m::Rv64MInstruction;
+use crate::rvc::RvcInstruction;
+use crate::rvv::RvvInstruction;
+use crate::zicsr::ZicsrInstruction;
+use crate::{AsmArgument, AsmInstruction, Xlen};
+
+#[derive(Debug, Clone, Copy)]
+pub enum Instruction {
+    Rv32I(Rv32IInstruction),
+    Rv64I(Rv64IInstruction),
+    Rv32M(Rv32MInstruction),
+    Rv64M(Rv64MInstruction),
+    Rv32A(Rv32AInstruction),
+    Rv64A(Rv64AInstruction),
+    Rv32F(Rv32FInstruction),
+    Rv64F(Rv64FInstruction),
+    Rv32D(Rv32DInstruction),
+    Rv64D(Rv64DInstruction),
+    Rv32B(Rv32BInstruction),
+    Rv64B(Rv64BInstruction),
+    Rvc(RvcInstruction),
+    Zicsr(ZicsrInstruction),
+    Privileged(PrivilegedInstruction),
+    Rvv(RvvInstruction),
+}
+
+impl Instruction {
+    /// Decode `word` as an instruction of a core with the register width `xlen`.
+    ///
+    /// Encodings that are only valid on RV64, such as `ld` or shifts by more than 31, are not
+    /// decoded for [`Xlen::Rv32`].
+    pub fn decode(word: u32, xlen: Xlen) -> Option<Self> {
+        if xlen == Xlen::Rv64 {
+            if let Ok(instruction) = Rv64IInstruction::try_from(word) {
+                return Some(Self::Rv64I(instruction));
+            }
+            if let Ok(instruction) = Rv64MInstruction::try_from(word) {
+                return Some(Self::Rv64M(instruction));
+            }
+            if let Ok(instruction) = Rv64AInstruction::try_from(word) {
+                return Some(Self::Rv64A(instruction));
+            }
+            if let Ok(instruction) = Rv64FInstruction::try_from(word) {
+                return Some(Self::Rv64F(instruction));
+            }
+            if let Ok(instruction) = Rv64DInstruction::try_from(word) {
+                return Some(Self::Rv64D(instruction));
+            }
+            if let Ok(instruction) = Rv64BInstruction::try_from(word) {
+                return Some(Self::Rv64B(instruction));
+            }
+        }
+
+        if let Ok(instruction) = Rv32MInstruction::try_from(word) {
+            return Some(Self::Rv32M(instruction));
+        }
+        if let Ok(instruction) = Rv32AInstruction::try_from(word) {
+            return Some(Self::Rv32A(instruction));
+        }
+        if let Ok(instruction) = Rv32FInstruction::try_from(word) {
+            return Some(Self::Rv32F(instruction));
+        }
+        if let Ok(instruction) = Rv32DInstruction::try_from(word) {
+            return Some(Self::Rv32D(instruction));
+        }
+        if let Ok(instruction) = Rv32BInstruction::try_from(word) {
+            // `rev8` and `zext.h` have other encodings on RV64
+            let rv32_only = matches!(
+                instruction,
+                Rv32BInstruction::Rev8(_) | Rv32BInstruction::ZextH(_)
+            );
+            if xlen == Xlen::Rv32 || !rv32_only {
+                return Some(Self::Rv32B(instruction));
+            }
+        }
+        if let Ok(instruction) = ZicsrInstruction::try_from(word) {
+            return Some(Self::Zicsr(instruction));
+        }
+        if let Some(instruction) = PrivilegedInstruction::decode(word, xlen) {
+            return Some(Self::Privileged(instruction));
+        }
+        if let Ok(instruction) = RvvInstruction::try_from(word) {
+            return Some(Self::Rvv(instruction));
+        }
+
+        Rv32IInstruction::try_from(word).ok().map(Self::Rv32I)
+    }
+
+    /// Decode the 16-bit `half` as a compressed instruction, see [`RvcInstruction::decode`].
+    #[inline]
+    pub fn decode_compressed(half: u16, xlen: Xlen) -> Option<Self> {
+        RvcInstruction::decode(half, xlen).map(Self::Rvc)
+    }
+
+    /// Length in bytes of the instruction starting with the byte `low`: 2 for compressed
+    /// instructions, whose two lowest bits are not both set, and 4 otherwise.
+    #[inline]
+    pub fn len(low: u8) -> usize {
+        match low & 0b11 {
+            0b11 => 4,
+            _ => 2,
+        }
+    }
+}
+
+impl AsmInstruction for Instruction {
+    fn verb(&self) -> &'static str {
+        match self {
+            Self::Rv32I(i) => i.verb(),
+            Self::Rv64I(i) => i.verb(),
+            Self::Rv32M(i) => i.verb(),
+            Self::Rv64M(i) => i.verb(),
+            Self::Rv32A(i) => i.verb(),
+            Self::Rv64A(i) => i.verb(),
+            Self::Rv32F(i) => i.verb(),
+            Self::Rv64F(i) => i.verb(),
+            Self::Rv32D(i) => i.verb(),
+            Self::Rv64D(i) => i.verb(),
+            Self::Rv32B(i) => i.verb(),
+            Self::Rv64B(i) => i.verb(),
+            Self::Rvc(i) => i.verb(),
+            Self::Zicsr(i) => i.verb(),
+            Self::Privileged(i) => i.verb(),
+            Self::Rvv(i) => i.verb(),
+        }
+    }
+
+    fn arguments(&self) -> Vec<AsmArgument> {
+        match self {
+            Self::Rv32I(i) => i.arguments(),
+            Self::Rv64I(i) => i.arguments(),
+            Self::Rv32M(i) => i.arguments(),
+            Self::Rv64M(i) => i.arguments(),
+            Self::Rv32A(i) => i.arguments(),
+            Self::Rv64A(i) => i.arguments(),
+            Self::Rv32F(i) => i.arguments(),
+            Self::Rv64F(i) => i.arguments(),
+            Self::Rv32D(i) => i.arguments(),
+            Self::Rv64D(i) => i.arguments(),
+            Self::Rv32B(i) => i.arguments(),
+            Self::Rv64B(i) => i.arguments(),
+            Self::Rvc(i) => i.arguments(),
+            Self::Zicsr(i) => i.arguments(),
+            Self::Privileged(i) => i.arguments(),
+            Self::Rvv(i) => i.arguments(),
+        }
+    }
+
+    fn is_pc_relative(&self) -> bool {
+        match self {
+            Self::Rv32I(i) => i.is_pc_relative(),
+            Self::Rv64I(i) => i.is_pc_relative(),
+            Self::Rv32M(i) => i.is_pc_relative(),
+            Self::Rv64M(i) => i.is_pc_relative(),
+            Self::Rv32A(i) => i.is_pc_relative(),
+            Self::Rv64A(i) => i.is_pc_relative(),
+            Self::Rv32F(i) => i.is_pc_relative(),
+            Self::Rv64F(i) => i.is_pc_relative(),
+            Self::Rv32D(i) => i.is_pc_relative(),
+            Self::Rv64D(i) => i.is_pc_relative(),
+            Self::Rv32B(i) => i.is_pc_relative(),
+            Self::Rv64B(i) => i.is_pc_relative(),
+            Self::Rvc(i) => i.is_pc_relative(),
+            Self::Zicsr(i) => i.is_pc_relative(),
+            Self::Privileged(i) => i.is_pc_relative(),
+            Self::Rvv(i) => i.is_pc_relative(),
+        }
+    }
+
+    fn expanded(&self) -> Option<u32> {
+        match self {
+            Self::Rvc(i) => i.expanded(),
+            _ => None,
+        }
+    }
+}
+
+#[test]
+fn decode() {
+    use crate::AsmFormatOptions;
+
+    let display = |word, xlen| {
+        Instruction::decode(word, xlen).map(|instruction| {
+            instruction
+                .display(AsmFormatOptions::default().arg_spacing(" "))
+                .to_string()
+        })
+    };
+
+    assert_eq!(
+        display(0x02c5c533, Xlen::Rv32).as_deref(),
+        Some("div\ta0, a1, a2")
+    );
+    assert_eq!(
+        display(0x02b50533, Xlen::Rv32).as_deref(),
+        Some("mul\ta0, a0, a1")
+    );
+    assert_eq!(
+        display(0x02c5f53b, Xlen::Rv64).as_deref(),
+        Some("remuw\ta0, a1, a2")
+    );
+    assert_eq!(display(0x02c5f53b, Xlen::Rv32), None);
+    assert_eq!(
+        display(0x00b50533, Xlen::Rv64).as_deref(),
+        Some("add\ta0, a0, a1")
+    );
+    assert_eq!(
+        crate::parse::parse_word("mulhsu t0, t1, t2"),
+        Ok(0x027322b3)
+    );
+}
@@ -2,12 +2,28 @@ use std::fmt::{Display, Error, Write};
 
 pub use riscv_encoding;
 
+use expr::Expr;
+use instruction::Instruction;
+use parse::ParseError;
+use symbols::SymbolTable;
+
 #[derive(Debug, Clone)]
 pub struct AsmFormatOptions {
     raw_reg_names: bool,
     verb_arg_spacing: &'static str,
     arg_spacing: &'static str,
     immediate_format: AsmImmediateFormat,
+    xlen: Xlen,
+    expand_compressed: bool,
+}
+
+/// Register width of the target, selecting the base ISA that instructions are decoded and
+/// assembled for.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Xlen {
+    #[default]
+    Rv32,
+    Rv64,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -18,6 +34,50 @@ pub enum AsmImmediateFormat {
     Bin,
 }
 
+impl AsmFormatOptions {
+    /// Print registers as `x10` rather than by their ABI name `a0`.
+    #[inline]
+    pub fn raw_reg_names(mut self, raw_reg_names: bool) -> Self {
+        self.raw_reg_names = raw_reg_names;
+        self
+    }
+
+    /// Text between the verb and the first argument.
+    #[inline]
+    pub fn verb_arg_spacing(mut self, spacing: &'static str) -> Self {
+        self.verb_arg_spacing = spacing;
+        self
+    }
+
+    /// Text after the comma between two arguments.
+    #[inline]
+    pub fn arg_spacing(mut self, spacing: &'static str) -> Self {
+        self.arg_spacing = spacing;
+        self
+    }
+
+    #[inline]
+    pub fn immediate_format(mut self, format: AsmImmediateFormat) -> Self {
+        self.immediate_format = format;
+        self
+    }
+
+    /// Decode instructions in listings as RV64I rather than RV32I.
+    #[inline]
+    pub fn xlen(mut self, xlen: Xlen) -> Self {
+        self.xlen = xlen;
+        self
+    }
+
+    /// Show compressed instructions as the 32-bit instructions they expand to, e.g.
+    /// `addi a0, a0, 1` rather than `c.addi a0, 1`.
+    #[inline]
+    pub fn expand_compressed(mut self, expand_compressed: bool) -> Self {
+        self.expand_compressed = expand_compressed;
+        self
+    }
+}
+
 impl Default for AsmFormatOptions {
     fn default() -> Self {
         Self {
@@ -25,15 +85,64 @@ impl Default for AsmFormatOptions {
             verb_arg_spacing: "\t",
             arg_spacing: "",
             immediate_format: AsmImmediateFormat::Hex,
+            xlen: Xlen::Rv32,
+            expand_compressed: false,
         }
     }
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum AsmArgument<'a> {
     Label(&'a str),
     Immediate(i64),
     Register(AsmRegister),
+    FloatRegister(AsmFloatRegister),
+    VectorRegister(AsmVectorRegister),
+    /// A CSR, shown by its name from [`csr`] where it has one for the register width.
+    Csr(u16),
     OffsetImmediate(i64, AsmRegister),
+    /// `(reg)`, the address operand of the atomics, which take no offset.
+    Address(AsmRegister),
+    /// `%hi(expr)`, `%pcrel_lo(label)`, ...
+    Relocation(AsmRelocation, Expr<'a>),
+    /// `%lo(expr)(reg)`
+    OffsetRelocation(AsmRelocation, Expr<'a>, AsmRegister),
+}
+
+/// Operators selecting part of an address that is only known once the program is laid out.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum AsmRelocation {
+    /// Upper 20 bits of an absolute address, for `lui`.
+    Hi,
+    /// Lower 12 bits of an absolute address.
+    Lo,
+    /// Upper 20 bits of a PC-relative address, for `auipc`.
+    PcrelHi,
+    /// Lower 12 bits of the PC-relative address computed by the `auipc` at the given label.
+    PcrelLo,
+}
+
+impl AsmRelocation {
+    #[inline]
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Hi => "hi",
+            Self::Lo => "lo",
+            Self::PcrelHi => "pcrel_hi",
+            Self::PcrelLo => "pcrel_lo",
+        }
+    }
+
+    /// Apply the operator to the (possibly PC-relative) value of its operand.
+    ///
+    /// The upper part is rounded by `0x800`, as the lower part is sign-extended when it is added.
+    #[inline]
+    pub fn apply(self, value: u32) -> i64 {
+        match self {
+            Self::Hi | Self::PcrelHi => i64::from(value.wrapping_add(0x800) >> 12),
+            Self::Lo | Self::PcrelLo => i64::from(((value << 20) as i32) >> 20),
+        }
+    }
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
@@ -150,7 +259,7 @@ impl AsmDisplay for AsmRegister {
 }
 
 impl TryFrom<u8> for AsmRegister {
-    type Error = ();
+    type Error = ParseError;
 
     fn try_from(value: u8) -> Result<Self, Self::Error> {
         match value {
@@ -186,7 +295,7 @@ impl TryFrom<u8> for AsmRegister {
             29 => Ok(Self::R29),
             30 => Ok(Self::R30),
             31 => Ok(Self::R31),
-            _ => Err(()),
+            _ => Err(ParseError::UnknownRegister(format!("x{value}"))),
         }
     }
 }
@@ -230,11 +339,444 @@ impl From<AsmRegister> for u8 {
     }
 }
 
+/// A register of the F and D extensions.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum AsmFloatRegister {
+    F0,
+    F1,
+    F2,
+    F3,
+    F4,
+    F5,
+    F6,
+    F7,
+    F8,
+    F9,
+    F10,
+    F11,
+    F12,
+    F13,
+    F14,
+    F15,
+    F16,
+    F17,
+    F18,
+    F19,
+    F20,
+    F21,
+    F22,
+    F23,
+    F24,
+    F25,
+    F26,
+    F27,
+    F28,
+    F29,
+    F30,
+    F31,
+}
+
+impl AsmDisplay for AsmFloatRegister {
+    fn fmt<W: Write>(&self, f: &mut W, options: &AsmFormatOptions) -> Result<(), Error> {
+        if options.raw_reg_names {
+            match self {
+                Self::F0 => write!(f, "f0"),
+                Self::F1 => write!(f, "f1"),
+                Self::F2 => write!(f, "f2"),
+                Self::F3 => write!(f, "f3"),
+                Self::F4 => write!(f, "f4"),
+                Self::F5 => write!(f, "f5"),
+                Self::F6 => write!(f, "f6"),
+                Self::F7 => write!(f, "f7"),
+                Self::F8 => write!(f, "f8"),
+                Self::F9 => write!(f, "f9"),
+                Self::F10 => write!(f, "f10"),
+                Self::F11 => write!(f, "f11"),
+                Self::F12 => write!(f, "f12"),
+                Self::F13 => write!(f, "f13"),
+                Self::F14 => write!(f, "f14"),
+                Self::F15 => write!(f, "f15"),
+                Self::F16 => write!(f, "f16"),
+                Self::F17 => write!(f, "f17"),
+                Self::F18 => write!(f, "f18"),
+                Self::F19 => write!(f, "f19"),
+                Self::F20 => write!(f, "f20"),
+                Self::F21 => write!(f, "f21"),
+                Self::F22 => write!(f, "f22"),
+                Self::F23 => write!(f, "f23"),
+                Self::F24 => write!(f, "f24"),
+                Self::F25 => write!(f, "f25"),
+                Self::F26 => write!(f, "f26"),
+                Self::F27 => write!(f, "f27"),
+                Self::F28 => write!(f, "f28"),
+                Self::F29 => write!(f, "f29"),
+                Self::F30 => write!(f, "f30"),
+                Self::F31 => write!(f, "f31"),
+            }
+        } else {
+            match self {
+                Self::F0 => write!(f, "ft0"),
+                Self::F1 => write!(f, "ft1"),
+                Self::F2 => write!(f, "ft2"),
+                Self::F3 => write!(f, "ft3"),
+                Self::F4 => write!(f, "ft4"),
+                Self::F5 => write!(f, "ft5"),
+                Self::F6 => write!(f, "ft6"),
+                Self::F7 => write!(f, "ft7"),
+                Self::F8 => write!(f, "fs0"),
+                Self::F9 => write!(f, "fs1"),
+                Self::F10 => write!(f, "fa0"),
+                Self::F11 => write!(f, "fa1"),
+                Self::F12 => write!(f, "fa2"),
+                Self::F13 => write!(f, "fa3"),
+                Self::F14 => write!(f, "fa4"),
+                Self::F15 => write!(f, "fa5"),
+                Self::F16 => write!(f, "fa6"),
+                Self::F17 => write!(f, "fa7"),
+                Self::F18 => write!(f, "fs2"),
+                Self::F19 => write!(f, "fs3"),
+                Self::F20 => write!(f, "fs4"),
+                Self::F21 => write!(f, "fs5"),
+                Self::F22 => write!(f, "fs6"),
+                Self::F23 => write!(f, "fs7"),
+                Self::F24 => write!(f, "fs8"),
+                Self::F25 => write!(f, "fs9"),
+                Self::F26 => write!(f, "fs10"),
+                Self::F27 => write!(f, "fs11"),
+                Self::F28 => write!(f, "ft8"),
+                Self::F29 => write!(f, "ft9"),
+                Self::F30 => write!(f, "ft10"),
+                Self::F31 => write!(f, "ft11"),
+            }
+        }
+    }
+}
+
+impl TryFrom<u8> for AsmFloatRegister {
+    type Error = ParseError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Self::F0),
+            1 => Ok(Self::F1),
+            2 => Ok(Self::F2),
+            3 => Ok(Self::F3),
+            4 => Ok(Self::F4),
+            5 => Ok(Self::F5),
+            6 => Ok(Self::F6),
+            7 => Ok(Self::F7),
+            8 => Ok(Self::F8),
+            9 => Ok(Self::F9),
+            10 => Ok(Self::F10),
+            11 => Ok(Self::F11),
+            12 => Ok(Self::F12),
+            13 => Ok(Self::F13),
+            14 => Ok(Self::F14),
+            15 => Ok(Self::F15),
+            16 => Ok(Self::F16),
+            17 => Ok(Self::F17),
+            18 => Ok(Self::F18),
+            19 => Ok(Self::F19),
+            20 => Ok(Self::F20),
+            21 => Ok(Self::F21),
+            22 => Ok(Self::F22),
+            23 => Ok(Self::F23),
+            24 => Ok(Self::F24),
+            25 => Ok(Self::F25),
+            26 => Ok(Self::F26),
+            27 => Ok(Self::F27),
+            28 => Ok(Self::F28),
+            29 => Ok(Self::F29),
+            30 => Ok(Self::F30),
+            31 => Ok(Self::F31),
+            _ => Err(ParseError::UnknownRegister(format!("f{value}"))),
+        }
+    }
+}
+
+impl From<AsmFloatRegister> for u8 {
+    fn from(value: AsmFloatRegister) -> Self {
+        match value {
+            AsmFloatRegister::F0 => 0,
+            AsmFloatRegister::F1 => 1,
+            AsmFloatRegister::F2 => 2,
+            AsmFloatRegister::F3 => 3,
+            AsmFloatRegister::F4 => 4,
+            AsmFloatRegister::F5 => 5,
+            AsmFloatRegister::F6 => 6,
+            AsmFloatRegister::F7 => 7,
+            AsmFloatRegister::F8 => 8,
+            AsmFloatRegister::F9 => 9,
+            AsmFloatRegister::F10 => 10,
+            AsmFloatRegister::F11 => 11,
+            AsmFloatRegister::F12 => 12,
+            AsmFloatRegister::F13 => 13,
+            AsmFloatRegister::F14 => 14,
+            AsmFloatRegister::F15 => 15,
+            AsmFloatRegister::F16 => 16,
+            AsmFloatRegister::F17 => 17,
+            AsmFloatRegister::F18 => 18,
+            AsmFloatRegister::F19 => 19,
+            AsmFloatRegister::F20 => 20,
+            AsmFloatRegister::F21 => 21,
+            AsmFloatRegister::F22 => 22,
+            AsmFloatRegister::F23 => 23,
+            AsmFloatRegister::F24 => 24,
+            AsmFloatRegister::F25 => 25,
+            AsmFloatRegister::F26 => 26,
+            AsmFloatRegister::F27 => 27,
+            AsmFloatRegister::F28 => 28,
+            AsmFloatRegister::F29 => 29,
+            AsmFloatRegister::F30 => 30,
+            AsmFloatRegister::F31 => 31,
+        }
+    }
+}
+
+/// A register of the V extension.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum AsmVectorRegister {
+    V0,
+    V1,
+    V2,
+    V3,
+    V4,
+    V5,
+    V6,
+    V7,
+    V8,
+    V9,
+    V10,
+    V11,
+    V12,
+    V13,
+    V14,
+    V15,
+    V16,
+    V17,
+    V18,
+    V19,
+    V20,
+    V21,
+    V22,
+    V23,
+    V24,
+    V25,
+    V26,
+    V27,
+    V28,
+    V29,
+    V30,
+    V31,
+}
+
+impl AsmDisplay for AsmVectorRegister {
+    fn fmt<W: Write>(&self, f: &mut W, _options: &AsmFormatOptions) -> Result<(), Error> {
+        match self {
+            Self::V0 => write!(f, "v0"),
+            Self::V1 => write!(f, "v1"),
+            Self::V2 => write!(f, "v2"),
+            Self::V3 => write!(f, "v3"),
+            Self::V4 => write!(f, "v4"),
+            Self::V5 => write!(f, "v5"),
+            Self::V6 => write!(f, "v6"),
+            Self::V7 => write!(f, "v7"),
+            Self::V8 => write!(f, "v8"),
+            Self::V9 => write!(f, "v9"),
+            Self::V10 => write!(f, "v10"),
+            Self::V11 => write!(f, "v11"),
+            Self::V12 => write!(f, "v12"),
+            Self::V13 => write!(f, "v13"),
+            Self::V14 => write!(f, "v14"),
+            Self::V15 => write!(f, "v15"),
+            Self::V16 => write!(f, "v16"),
+            Self::V17 => write!(f, "v17"),
+            Self::V18 => write!(f, "v18"),
+            Self::V19 => write!(f, "v19"),
+            Self::V20 => write!(f, "v20"),
+            Self::V21 => write!(f, "v21"),
+            Self::V22 => write!(f, "v22"),
+            Self::V23 => write!(f, "v23"),
+            Self::V24 => write!(f, "v24"),
+            Self::V25 => write!(f, "v25"),
+            Self::V26 => write!(f, "v26"),
+            Self::V27 => write!(f, "v27"),
+            Self::V28 => write!(f, "v28"),
+            Self::V29 => write!(f, "v29"),
+            Self::V30 => write!(f, "v30"),
+            Self::V31 => write!(f, "v31"),
+        }
+    }
+}
+
+impl TryFrom<u8> for AsmVectorRegister {
+    type Error = ParseError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Self::V0),
+            1 => Ok(Self::V1),
+            2 => Ok(Self::V2),
+            3 => Ok(Self::V3),
+            4 => Ok(Self::V4),
+            5 => Ok(Self::V5),
+            6 => Ok(Self::V6),
+            7 => Ok(Self::V7),
+            8 => Ok(Self::V8),
+            9 => Ok(Self::V9),
+            10 => Ok(Self::V10),
+            11 => Ok(Self::V11),
+            12 => Ok(Self::V12),
+            13 => Ok(Self::V13),
+            14 => Ok(Self::V14),
+            15 => Ok(Self::V15),
+            16 => Ok(Self::V16),
+            17 => Ok(Self::V17),
+            18 => Ok(Self::V18),
+            19 => Ok(Self::V19),
+            20 => Ok(Self::V20),
+            21 => Ok(Self::V21),
+            22 => Ok(Self::V22),
+            23 => Ok(Self::V23),
+            24 => Ok(Self::V24),
+            25 => Ok(Self::V25),
+            26 => Ok(Self::V26),
+            27 => Ok(Self::V27),
+            28 => Ok(Self::V28),
+            29 => Ok(Self::V29),
+            30 => Ok(Self::V30),
+            31 => Ok(Self::V31),
+            _ => Err(ParseError::UnknownRegister(format!("v{value}"))),
+        }
+    }
+}
+
+impl From<AsmVectorRegister> for u8 {
+    fn from(value: AsmVectorRegister) -> Self {
+        match value {
+            AsmVectorRegister::V0 => 0,
+            AsmVectorRegister::V1 => 1,
+            AsmVectorRegister::V2 => 2,
+            AsmVectorRegister::V3 => 3,
+            AsmVectorRegister::V4 => 4,
+            AsmVectorRegister::V5 => 5,
+            AsmVectorRegister::V6 => 6,
+            AsmVectorRegister::V7 => 7,
+            AsmVectorRegister::V8 => 8,
+            AsmVectorRegister::V9 => 9,
+            AsmVectorRegister::V10 => 10,
+            AsmVectorRegister::V11 => 11,
+            AsmVectorRegister::V12 => 12,
+            AsmVectorRegister::V13 => 13,
+            AsmVectorRegister::V14 => 14,
+            AsmVectorRegister::V15 => 15,
+            AsmVectorRegister::V16 => 16,
+            AsmVectorRegister::V17 => 17,
+            AsmVectorRegister::V18 => 18,
+            AsmVectorRegister::V19 => 19,
+            AsmVectorRegister::V20 => 20,
+            AsmVectorRegister::V21 => 21,
+            AsmVectorRegister::V22 => 22,
+            AsmVectorRegister::V23 => 23,
+            AsmVectorRegister::V24 => 24,
+            AsmVectorRegister::V25 => 25,
+            AsmVectorRegister::V26 => 26,
+            AsmVectorRegister::V27 => 27,
+            AsmVectorRegister::V28 => 28,
+            AsmVectorRegister::V29 => 29,
+            AsmVectorRegister::V30 => 30,
+            AsmVectorRegister::V31 => 31,
+        }
+    }
+}
+
+/// The rounding mode operand of floating-point instructions.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum AsmRoundingMode {
+    /// Round to nearest, ties to even.
+    Rne,
+    /// Round towards zero.
+    Rtz,
+    /// Round down.
+    Rdn,
+    /// Round up.
+    Rup,
+    /// Round to nearest, ties to max magnitude.
+    Rmm,
+    /// The dynamic rounding mode in `frm`.
+    Dyn,
+}
+
+impl AsmRoundingMode {
+    #[inline]
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Rne => "rne",
+            Self::Rtz => "rtz",
+            Self::Rdn => "rdn",
+            Self::Rup => "rup",
+            Self::Rmm => "rmm",
+            Self::Dyn => "dyn",
+        }
+    }
+
+    /// The operand for the `rm` field of an instruction, or `None` if it is the `default` of the
+    /// instruction and is left out.
+    #[inline]
+    pub(crate) fn operand(rm: u8, default: Self) -> Option<AsmArgument<'static>> {
+        let mode = Self::try_from(rm).ok()?;
+        (mode != default).then_some(AsmArgument::Label(mode.name()))
+    }
+}
+
+impl TryFrom<u8> for AsmRoundingMode {
+    type Error = ();
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0b000 => Ok(Self::Rne),
+            0b001 => Ok(Self::Rtz),
+            0b010 => Ok(Self::Rdn),
+            0b011 => Ok(Self::Rup),
+            0b100 => Ok(Self::Rmm),
+            0b111 => Ok(Self::Dyn),
+            _ => Err(()),
+        }
+    }
+}
+
+impl From<AsmRoundingMode> for u8 {
+    fn from(value: AsmRoundingMode) -> Self {
+        match value {
+            AsmRoundingMode::Rne => 0b000,
+            AsmRoundingMode::Rtz => 0b001,
+            AsmRoundingMode::Rdn => 0b010,
+            AsmRoundingMode::Rup => 0b011,
+            AsmRoundingMode::Rmm => 0b100,
+            AsmRoundingMode::Dyn => 0b111,
+        }
+    }
+}
+
 impl<'a> AsmArgument<'a> {
     #[inline]
     pub fn reg(num: u8) -> Option<Self> {
         Some(AsmArgument::Register(AsmRegister::try_from(num).ok()?))
     }
+
+    #[inline]
+    pub fn freg(num: u8) -> Option<Self> {
+        Some(AsmArgument::FloatRegister(
+            AsmFloatRegister::try_from(num).ok()?,
+        ))
+    }
+
+    #[inline]
+    pub fn vreg(num: u8) -> Option<Self> {
+        Some(AsmArgument::VectorRegister(
+            AsmVectorRegister::try_from(num).ok()?,
+        ))
+    }
 }
 
 impl<'a> AsmDisplay for AsmArgument<'a> {
@@ -248,11 +790,28 @@ impl<'a> AsmDisplay for AsmArgument<'a> {
                 AsmImmediateFormat::SignedDecimal => write!(f, "{imm}"),
             },
             Self::Register(reg) => reg.fmt(f, options),
+            Self::FloatRegister(reg) => reg.fmt(f, options),
+            Self::VectorRegister(reg) => reg.fmt(f, options),
+            Self::Csr(number) => match csr::name(*number, options.xlen) {
+                Some(name) => f.write_str(name),
+                None => AsmDisplay::fmt(&Self::Immediate(i64::from(*number)), f, options),
+            },
             Self::OffsetImmediate(imm, reg) => {
                 write!(f, "{imm}(")?;
                 AsmDisplay::fmt(reg, f, options)?;
                 write!(f, ")")
             }
+            Self::Address(reg) => {
+                write!(f, "(")?;
+                AsmDisplay::fmt(reg, f, options)?;
+                write!(f, ")")
+            }
+            Self::Relocation(relocation, expr) => write!(f, "%{}({expr})", relocation.name()),
+            Self::OffsetRelocation(relocation, expr, reg) => {
+                write!(f, "%{}({expr})(", relocation.name())?;
+                AsmDisplay::fmt(reg, f, options)?;
+                write!(f, ")")
+            }
         }
     }
 }
@@ -264,10 +823,23 @@ trait AsmDisplay {
 pub trait AsmInstruction: Sized {
     fn verb(&self) -> &'static str;
     fn arguments(&self) -> Vec<AsmArgument>;
+    /// Whether the last argument is an offset from the address of the instruction, as for
+    /// branches and jumps.
+    #[inline]
+    fn is_pc_relative(&self) -> bool {
+        false
+    }
+    /// The 32-bit instruction word a compressed instruction expands to.
+    #[inline]
+    fn expanded(&self) -> Option<u32> {
+        None
+    }
     fn display(&self, options: AsmFormatOptions) -> AsmDisplayInstruction<Self> {
         AsmDisplayInstruction {
             options,
             instruction: self,
+            pc: None,
+            symbols: None,
         }
     }
 }
@@ -275,17 +847,72 @@ pub trait AsmInstruction: Sized {
 pub struct AsmDisplayInstruction<'a, I> {
     options: AsmFormatOptions,
     instruction: &'a I,
+    pc: Option<u32>,
+    symbols: Option<&'a SymbolTable>,
+}
+
+impl<'a, I> AsmDisplayInstruction<'a, I> {
+    /// Format the instruction placed at `pc`, showing branch and jump targets as absolute
+    /// addresses like objdump does, e.g. `j 80000030`.
+    #[inline]
+    pub fn at(mut self, pc: u32) -> Self {
+        self.pc = Some(pc);
+        self
+    }
+
+    /// Name branch and jump targets after `symbols` when formatting at a PC. Targets at the
+    /// start of a symbol become an [`AsmArgument::Label`], others are shown as
+    /// `80000034 <main+0x4>`.
+    #[inline]
+    pub fn with_symbols(mut self, symbols: &'a SymbolTable) -> Self {
+        self.symbols = Some(symbols);
+        self
+    }
 }
 
 impl<'a, I: AsmInstruction> AsmDisplay for AsmDisplayInstruction<'a, I> {
     fn fmt<W: Write>(&self, f: &mut W, options: &AsmFormatOptions) -> Result<(), Error> {
+        let expanded = match options.expand_compressed {
+            true => self.instruction.expanded(),
+            false => None,
+        };
+        if let Some(instruction) = expanded.and_then(|word| Instruction::decode(word, options.xlen))
+        {
+            let display = AsmDisplayInstruction {
+                options: options.clone(),
+                instruction: &instruction,
+                pc: self.pc,
+                symbols: self.symbols,
+            };
+            return AsmDisplay::fmt(&display, f, options);
+        }
+
         f.write_str(self.instruction.verb())?;
         f.write_str(options.verb_arg_spacing)?;
 
-        for (i, arg) in self.instruction.arguments().iter().enumerate() {
-            AsmDisplay::fmt(arg, f, options)?;
+        let arguments = self.instruction.arguments();
+        for (i, arg) in arguments.iter().enumerate() {
+            let is_last = i == arguments.len() - 1;
+
+            match (arg, self.pc) {
+                (AsmArgument::Immediate(offset), Some(pc))
+                    if is_last && self.instruction.is_pc_relative() =>
+                {
+                    let target = pc.wrapping_add(*offset as u32);
+                    match self.symbols {
+                        Some(symbols) => match symbols.at(target) {
+                            Some(symbol) => {
+                                AsmDisplay::fmt(&AsmArgument::Label(&symbol.name), f, options)?
+                            }
+                            None => write!(f, "{}", symbols.describe(target))?,
+                        },
+                        None => write!(f, "{target:x}")?,
+                    }
+                }
+                _ => AsmDisplay::fmt(arg, f, options)?,
+            }
 
-            if i != self.instruction.arguments().len() - 1 {
+            if !is_last {
                 f.write_str(",")?;
                 f.write_str(options.arg_spacing)?;
             }
@@ -301,4 +928,32 @@ impl<'a, I: AsmInstruction> Display for AsmDisplayInstruction<'a, I> {
     }
 }
 
+pub mod assembler;
+pub mod csr;
+pub mod diagnostic;
+pub mod disassemble;
+pub mod elf;
+mod encode;
+pub mod expr;
+mod fields;
+pub mod image;
+pub mod instruction;
+pub mod parse;
+pub mod privileged;
+pub mod pseudo;
+pub mod rv32a;
+pub mod rv32b;
+pub mod rv32d;
+pub mod rv32f;
 mod rv32i;
+pub mod rv32m;
+pub mod rv64a;
+pub mod rv64b;
+pub mod rv64d;
+pub mod rv64f;
+pub mod rv64i;
+pub mod rv64m;
+pub mod rvc;
+pub mod rvv;
+pub mod symbols;
+pub mod zicsr;
@@ -0,0 +1,587 @@
+//! Parsing of textual RV32I and RV64I assembly back into instructions.
+//!
+//! Every spelling the formatter can produce is accepted: ABI and `xN` register names, immediates
+//! in any [`AsmImmediateFormat`](crate::AsmImmediateFormat), `imm(reg)` offsets and the aliases
+//! that [`AsmInstruction::verb`](crate::AsmInstruction::verb) collapses encodings into.
+
+use std::fmt::Display;
+use std::str::FromStr;
+
+use riscv_encoding::rv32i::Rv32IInstruction;
+
+use crate::expr::{parse_expr, Expr};
+use crate::{
+    pseudo, AsmArgument, AsmFloatRegister, AsmRegister, AsmRelocation, AsmRoundingMode, Xlen,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    EmptyLine,
+    UnknownInstruction(String),
+    UnknownDirective(String),
+    UnknownRegister(String),
+    InvalidImmediate(String),
+    InvalidOperand(String),
+    InvalidString(String),
+    InvalidExpression(String),
+    NestingDepth,
+    DivisionByZero,
+    Overflow,
+    OperandCount {
+        verb: String,
+        expected: usize,
+        found: usize,
+    },
+    ExpectedRegister(usize),
+    ExpectedFloatRegister(usize),
+    ExpectedImmediate(usize),
+    ExpectedOffset(usize),
+    ExpectedAddress(usize),
+    InvalidFenceSet(usize),
+    InvalidRoundingMode(usize),
+    InvalidCsr(usize),
+    ImmediateOutOfRange {
+        value: i64,
+        min: i64,
+        max: i64,
+    },
+    MisalignedOffset(i64),
+    UnresolvedLabel(String),
+    MultipleInstructions(String),
+    Undecodable(u32),
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::EmptyLine => write!(f, "expected an instruction"),
+            Self::UnknownInstruction(verb) => write!(f, "unknown instruction `{verb}`"),
+            Self::UnknownDirective(directive) => write!(f, "unknown directive `{directive}`"),
+            Self::UnknownRegister(reg) => write!(f, "unknown register `{reg}`"),
+            Self::InvalidImmediate(imm) => write!(f, "invalid immediate `{imm}`"),
+            Self::InvalidOperand(arg) => write!(f, "invalid operand `{arg}`"),
+            Self::InvalidString(string) => write!(f, "invalid string literal {string}"),
+            Self::InvalidExpression(expr) => write!(f, "invalid expression `{expr}`"),
+            Self::NestingDepth => write!(f, "expression is nested too deeply"),
+            Self::DivisionByZero => write!(f, "division by zero"),
+            Self::Overflow => write!(f, "arithmetic overflow in expression"),
+            Self::OperandCount {
+                verb,
+                expected,
+                found,
+            } => write!(f, "`{verb}` expects {expected} operand(s), found {found}"),
+            Self::ExpectedRegister(i) => write!(f, "operand {} must be a register", i + 1),
+            Self::ExpectedFloatRegister(i) => {
+                write!(f, "operand {} must be a floating-point register", i + 1)
+            }
+            Self::ExpectedImmediate(i) => write!(f, "operand {} must be an immediate", i + 1),
+            Self::ExpectedOffset(i) => {
+                write!(f, "operand {} must be of the form `imm(reg)`", i + 1)
+            }
+            Self::ExpectedAddress(i) => write!(f, "operand {} must be of the form `(reg)`", i + 1),
+            Self::InvalidFenceSet(i) => {
+                write!(f, "operand {} must be a combination of `iorw`", i + 1)
+            }
+            Self::InvalidRoundingMode(i) => write!(
+                f,
+                "operand {} must be one of the rounding modes `rne`, `rtz`, `rdn`, `rup`, `rmm` \
+                 or `dyn`",
+                i + 1
+            ),
+            Self::InvalidCsr(i) => write!(
+                f,
+                "operand {} must be the name or number of a control and status register",
+                i + 1
+            ),
+            Self::ImmediateOutOfRange { value, min, max } => {
+                write!(f, "immediate {value} out of range [{min}, {max}]")
+            }
+            Self::MisalignedOffset(offset) => write!(f, "offset {offset} is not 2-byte aligned"),
+            Self::UnresolvedLabel(label) => write!(f, "unresolved label `{label}`"),
+            Self::MultipleInstructions(verb) => {
+                write!(f, "`{verb}` expands to more than one instruction")
+            }
+            Self::Undecodable(word) => write!(f, "0x{word:08x} is not a valid RV32I instruction"),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+impl FromStr for AsmRegister {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let num = match s {
+            "zero" => 0,
+            "ra" => 1,
+            "sp" => 2,
+            "gp" => 3,
+            "tp" => 4,
+            "t0" => 5,
+            "t1" => 6,
+            "t2" => 7,
+            "s0" | "fp" => 8,
+            "s1" => 9,
+            "a0" => 10,
+            "a1" => 11,
+            "a2" => 12,
+            "a3" => 13,
+            "a4" => 14,
+            "a5" => 15,
+            "a6" => 16,
+            "a7" => 17,
+            "s2" => 18,
+            "s3" => 19,
+            "s4" => 20,
+            "s5" => 21,
+            "s6" => 22,
+            "s7" => 23,
+            "s8" => 24,
+            "s9" => 25,
+            "s10" => 26,
+            "s11" => 27,
+            "t3" => 28,
+            "t4" => 29,
+            "t5" => 30,
+            "t6" => 31,
+            _ => s
+                .strip_prefix('x')
+                .filter(|n| n.len() == 1 || !n.starts_with('0'))
+                .and_then(|n| n.parse::<u8>().ok())
+                .ok_or_else(|| ParseError::UnknownRegister(s.to_string()))?,
+        };
+
+        AsmRegister::try_from(num).map_err(|_| ParseError::UnknownRegister(s.to_string()))
+    }
+}
+
+impl FromStr for AsmFloatRegister {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let num = match s {
+            "ft0" => 0,
+            "ft1" => 1,
+            "ft2" => 2,
+            "ft3" => 3,
+            "ft4" => 4,
+            "ft5" => 5,
+            "ft6" => 6,
+            "ft7" => 7,
+            "fs0" => 8,
+            "fs1" => 9,
+            "fa0" => 10,
+            "fa1" => 11,
+            "fa2" => 12,
+            "fa3" => 13,
+            "fa4" => 14,
+            "fa5" => 15,
+            "fa6" => 16,
+            "fa7" => 17,
+            "fs2" => 18,
+            "fs3" => 19,
+            "fs4" => 20,
+            "fs5" => 21,
+            "fs6" => 22,
+            "fs7" => 23,
+            "fs8" => 24,
+            "fs9" => 25,
+            "fs10" => 26,
+            "fs11" => 27,
+            "ft8" => 28,
+            "ft9" => 29,
+            "ft10" => 30,
+            "ft11" => 31,
+            _ => s
+                .strip_prefix('f')
+                .filter(|n| n.len() == 1 || !n.starts_with('0'))
+                .and_then(|n| n.parse::<u8>().ok())
+                .ok_or_else(|| ParseError::UnknownRegister(s.to_string()))?,
+        };
+
+        AsmFloatRegister::try_from(num).map_err(|_| ParseError::UnknownRegister(s.to_string()))
+    }
+}
+
+impl FromStr for AsmRoundingMode {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "rne" => Self::Rne,
+            "rtz" => Self::Rtz,
+            "rdn" => Self::Rdn,
+            "rup" => Self::Rup,
+            "rmm" => Self::Rmm,
+            "dyn" => Self::Dyn,
+            _ => return Err(ParseError::InvalidOperand(s.to_string())),
+        })
+    }
+}
+
+/// Parse an immediate as printed with any of the [`AsmImmediateFormat`](crate::AsmImmediateFormat)s.
+///
+/// Hexadecimal and binary values are read as 64-bit two's complement, so the `0xffff...` spelling
+/// of a negative immediate parses back to the negative value.
+pub fn parse_immediate(s: &str) -> Result<i64, ParseError> {
+    let s = s.trim();
+    let (negative, digits) = match s.strip_prefix('-') {
+        Some(digits) => (true, digits),
+        None => (false, s.strip_prefix('+').unwrap_or(s)),
+    };
+
+    let value = if let Some(hex) = digits
+        .strip_prefix("0x")
+        .or_else(|| digits.strip_prefix("0X"))
+    {
+        u64::from_str_radix(hex, 16)
+    } else if let Some(bin) = digits
+        .strip_prefix("0b")
+        .or_else(|| digits.strip_prefix("0B"))
+    {
+        u64::from_str_radix(bin, 2)
+    } else {
+        digits.parse::<u64>()
+    }
+    .map_err(|_| ParseError::InvalidImmediate(s.to_string()))? as i64;
+
+    Ok(if negative {
+        value.wrapping_neg()
+    } else {
+        value
+    })
+}
+
+#[inline]
+pub(crate) fn is_identifier(s: &str) -> bool {
+    let mut chars = s.chars();
+    matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_' || c == '.' || c == '$')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.' || c == '$')
+}
+
+/// Parse a double-quoted string literal with C-style escapes.
+pub(crate) fn parse_string(s: &str) -> Result<Vec<u8>, ParseError> {
+    let invalid = || ParseError::InvalidString(s.to_string());
+    let inner = s
+        .strip_prefix('"')
+        .and_then(|s| s.strip_suffix('"'))
+        .ok_or_else(invalid)?;
+
+    let mut bytes = Vec::with_capacity(inner.len());
+    let mut chars = inner.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            let mut buf = [0; 4];
+            bytes.extend_from_slice(c.encode_utf8(&mut buf).as_bytes());
+            continue;
+        }
+
+        let byte = match chars.next().ok_or_else(invalid)? {
+            'n' => b'\n',
+            't' => b'\t',
+            'r' => b'\r',
+            'b' => 0x08,
+            'f' => 0x0C,
+            'v' => 0x0B,
+            'a' => 0x07,
+            'e' => 0x1B,
+            '\\' => b'\\',
+            '"' => b'"',
+            '\'' => b'\'',
+            'x' => {
+                let mut value = 0u32;
+                let mut digits = 0;
+                while let Some(digit) = chars.peek().and_then(|c| c.to_digit(16)) {
+                    value = (value << 4) | digit;
+                    digits += 1;
+                    chars.next();
+                }
+                if digits == 0 {
+                    return Err(invalid());
+                }
+                value as u8
+            }
+            c @ '0'..='7' => {
+                let mut value = c.to_digit(8).unwrap();
+                for _ in 0..2 {
+                    match chars.peek().and_then(|c| c.to_digit(8)) {
+                        Some(digit) => {
+                            value = (value << 3) | digit;
+                            chars.next();
+                        }
+                        None => break,
+                    }
+                }
+                value as u8
+            }
+            _ => return Err(invalid()),
+        };
+        bytes.push(byte);
+    }
+
+    Ok(bytes)
+}
+
+/// An operand whose expressions have not been evaluated yet.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Operand<'a> {
+    Register(AsmRegister),
+    FloatRegister(AsmFloatRegister),
+    Expr(Expr<'a>),
+    /// `expr(reg)`
+    Offset(Expr<'a>, AsmRegister),
+    /// `%hi(expr)`
+    Relocation(AsmRelocation, Expr<'a>),
+    /// `%lo(expr)(reg)`
+    OffsetRelocation(AsmRelocation, Expr<'a>, AsmRegister),
+}
+
+/// Parse `%name(expr)`, or return `None` if `s` is not a relocation operator.
+fn parse_relocation(s: &str) -> Option<Result<(AsmRelocation, Expr<'_>), ParseError>> {
+    let (name, rest) = s.strip_prefix('%')?.split_once('(')?;
+    let relocation = match name.trim() {
+        "hi" => AsmRelocation::Hi,
+        "lo" => AsmRelocation::Lo,
+        "pcrel_hi" => AsmRelocation::PcrelHi,
+        "pcrel_lo" => AsmRelocation::PcrelLo,
+        _ => return Some(Err(ParseError::InvalidOperand(s.to_string()))),
+    };
+
+    let Some(inner) = rest.trim_end().strip_suffix(')') else {
+        return Some(Err(ParseError::InvalidOperand(s.to_string())));
+    };
+    Some(parse_expr(inner).map(|expr| (relocation, expr)))
+}
+
+/// Parse a single operand without evaluating it.
+pub(crate) fn parse_operand(s: &str) -> Result<Operand<'_>, ParseError> {
+    let s = s.trim();
+    if s.is_empty() {
+        return Err(ParseError::InvalidOperand(s.to_string()));
+    }
+
+    if let Ok(reg) = s.parse() {
+        return Ok(Operand::Register(reg));
+    }
+    if let Ok(reg) = s.parse() {
+        return Ok(Operand::FloatRegister(reg));
+    }
+
+    // `expr(reg)`, but not a parenthesized expression such as `(1 << 12)`
+    let offset = s.strip_suffix(')').and_then(|inner| {
+        let (expr, reg) = inner.rsplit_once('(')?;
+        Some((expr.trim(), reg.trim().parse().ok()?))
+    });
+    if let Some((expr, reg)) = offset {
+        if let Some(relocation) = parse_relocation(expr) {
+            let (relocation, expr) = relocation?;
+            return Ok(Operand::OffsetRelocation(relocation, expr, reg));
+        }
+
+        let expr = match expr {
+            "" => Expr::Number(0),
+            expr => parse_expr(expr)?,
+        };
+        return Ok(Operand::Offset(expr, reg));
+    }
+
+    if let Some(relocation) = parse_relocation(s) {
+        let (relocation, expr) = relocation?;
+        return Ok(Operand::Relocation(relocation, expr));
+    }
+
+    Ok(Operand::Expr(parse_expr(s)?))
+}
+
+/// Parse a single operand.
+///
+/// Bare identifiers that are not register names become [`AsmArgument::Label`]s. Any other
+/// expression must be constant and is evaluated to an [`AsmArgument::Immediate`]. `%hi` and `%lo`
+/// of a constant are evaluated as well, other relocation operators are kept as they are.
+pub fn parse_argument(s: &str) -> Result<AsmArgument<'_>, ParseError> {
+    let constant = |relocation: AsmRelocation, expr: &Expr| match relocation {
+        AsmRelocation::Hi | AsmRelocation::Lo => expr.constant().ok(),
+        AsmRelocation::PcrelHi | AsmRelocation::PcrelLo => None,
+    };
+
+    Ok(match parse_operand(s)? {
+        Operand::Register(reg) => AsmArgument::Register(reg),
+        Operand::FloatRegister(reg) => AsmArgument::FloatRegister(reg),
+        Operand::Expr(Expr::Symbol(label)) => AsmArgument::Label(label),
+        Operand::Expr(expr) => AsmArgument::Immediate(expr.constant()?),
+        Operand::Offset(expr, reg) => AsmArgument::OffsetImmediate(expr.constant()?, reg),
+        Operand::Relocation(relocation, expr) => match constant(relocation, &expr) {
+            Some(value) => AsmArgument::Immediate(relocation.apply(value as u32)),
+            None => AsmArgument::Relocation(relocation, expr),
+        },
+        Operand::OffsetRelocation(relocation, expr, reg) => match constant(relocation, &expr) {
+            Some(value) => AsmArgument::OffsetImmediate(relocation.apply(value as u32), reg),
+            None => AsmArgument::OffsetRelocation(relocation, expr, reg),
+        },
+    })
+}
+
+/// Byte positions of the characters in `line` that are not inside a string literal.
+fn unquoted(line: &str) -> impl Iterator<Item = (usize, char)> + '_ {
+    let mut in_string = false;
+    let mut escaped = false;
+
+    line.char_indices().filter(move |&(_, c)| {
+        if in_string {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+            return false;
+        }
+
+        in_string = c == '"';
+        !in_string
+    })
+}
+
+/// Strip a trailing `#` comment and surrounding whitespace.
+#[inline]
+pub(crate) fn strip_comment(line: &str) -> &str {
+    unquoted(line)
+        .find(|&(_, c)| c == '#')
+        .map_or(line, |(i, _)| &line[..i])
+        .trim()
+}
+
+/// Split a statement into its verb and the raw text of its operands.
+///
+/// Operands are separated by commas that are outside of string literals and parentheses.
+pub(crate) fn split_statement(line: &str) -> (&str, Vec<&str>) {
+    let line = line.trim();
+    let (verb, rest) = line
+        .split_once(char::is_whitespace)
+        .map_or((line, ""), |(verb, rest)| (verb, rest.trim()));
+
+    if rest.is_empty() {
+        return (verb, Vec::new());
+    }
+
+    let mut operands = Vec::new();
+    let mut depth = 0usize;
+    let mut start = 0;
+    for (i, c) in unquoted(rest) {
+        match c {
+            '(' => depth += 1,
+            ')' => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => {
+                operands.push(rest[start..i].trim());
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    operands.push(rest[start..].trim());
+
+    (verb, operands)
+}
+
+/// Assemble a single line of assembly placed at address `pc` into its instruction words,
+/// expanding pseudo-instructions.
+pub(crate) fn parse_words(line: &str, pc: u32, xlen: Xlen) -> Result<Vec<u32>, ParseError> {
+    let line = strip_comment(line);
+    if line.is_empty() {
+        return Err(ParseError::EmptyLine);
+    }
+
+    let (verb, operands) = split_statement(line);
+    let args = operands
+        .into_iter()
+        .map(parse_argument)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    pseudo::expand_words(&verb.to_ascii_lowercase(), &args, pc, xlen)
+}
+
+/// Assemble a single line of RV32I assembly into its 32-bit instruction word.
+#[inline]
+pub fn parse_word(line: &str) -> Result<u32, ParseError> {
+    parse_word_xlen(line, Xlen::Rv32)
+}
+
+/// Assemble a single line of assembly for a core with the register width `xlen` into its 32-bit
+/// instruction word. The RV64I instructions and shift amounts above 31 are only accepted for
+/// [`Xlen::Rv64`].
+pub fn parse_word_xlen(line: &str, xlen: Xlen) -> Result<u32, ParseError> {
+    match parse_words(line, 0, xlen)?.as_slice() {
+        [word] => Ok(*word),
+        _ => Err(ParseError::MultipleInstructions(
+            split_statement(strip_comment(line)).0.to_string(),
+        )),
+    }
+}
+
+/// Parse a single line of assembly, e.g. `addi a0, a1, 0x10`, into an instruction.
+pub fn parse_instruction(line: &str) -> Result<Rv32IInstruction, ParseError> {
+    let word = parse_word(line)?;
+    Rv32IInstruction::try_from(word).map_err(|_| ParseError::Undecodable(word))
+}
+
+/// Parse a single line of assembly placed at address `pc`, expanding pseudo-instructions such as
+/// `li` or `call` into all of the instructions they stand for.
+pub fn parse_line(line: &str, pc: u32) -> Result<Vec<Rv32IInstruction>, ParseError> {
+    parse_words(line, pc, Xlen::Rv32)?
+        .into_iter()
+        .map(|word| Rv32IInstruction::try_from(word).map_err(|_| ParseError::Undecodable(word)))
+        .collect()
+}
+
+#[test]
+fn round_trip() {
+    use crate::{AsmFormatOptions, AsmInstruction};
+
+    for word in [
+        0xdeadc537, 0x01058593, 0xfff5c513, 0x00b50463, 0xff5ff06f, 0x00008067, 0xffc12083,
+        0x00112623, 0x4015d513, 0x40b50533, 0x40b55533, 0x0ff0000f,
+    ] {
+        let instr = Rv32IInstruction::try_from(word).unwrap();
+        for immediate_format in [
+            crate::AsmImmediateFormat::Hex,
+            crate::AsmImmediateFormat::Bin,
+            crate::AsmImmediateFormat::SignedDecimal,
+            crate::AsmImmediateFormat::UnsignedDecimal,
+        ] {
+            for raw_reg_names in [false, true] {
+                let options = AsmFormatOptions {
+                    raw_reg_names,
+                    immediate_format,
+                    ..AsmFormatOptions::default()
+                };
+                let text = instr.display(options).to_string();
+                assert_eq!(parse_word(&text), Ok(word), "{text}");
+            }
+        }
+    }
+}
+
+#[test]
+fn immediate_range() {
+    let out_of_range = |value| {
+        Err(ParseError::ImmediateOutOfRange {
+            value,
+            min: -0x800,
+            max: 0x7ff,
+        })
+    };
+
+    assert_eq!(parse_word("addi a0, a0, 2047"), Ok(0x7ff50513));
+    assert_eq!(parse_word("addi a0, a0, -2048"), Ok(0x80050513));
+    assert_eq!(parse_word("addi a0, a0, 2048"), out_of_range(0x800));
+    assert_eq!(parse_word("addi a0, a0, 0xfff"), out_of_range(0xfff));
+    assert_eq!(parse_word("lw a0, 4095(a1)"), out_of_range(0xfff));
+    assert_eq!(parse_word("sw a0, 2048(a1)"), out_of_range(0x800));
+    assert_eq!(parse_word("jalr ra, 2048(a1)"), out_of_range(0x800));
+    assert_eq!(parse_word("not a0, a0"), Ok(0xfff54513));
+    assert_eq!(
+        parse_word("xori a0, a0, 0xffffffffffffffff"),
+        Ok(0xfff54513)
+    );
+}
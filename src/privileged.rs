@@ -0,0 +1,489 @@
+//! Instructions of the privileged architecture: trap returns, `wfi`, the address-translation
+//! fences and the hypervisor virtual-machine loads and stores.
+
+use crate::encode::OP_SYSTEM;
+use crate::fields::instruction;
+use crate::{AsmArgument, AsmInstruction, AsmRegister, Xlen};
+
+// mret
+// sret
+// wfi
+// sfence.vma           [REG[,REG]]
+// hfence.vvma          [REG[,REG]]
+// hfence.gvma          [REG[,REG]]
+// hlv.b                REG,(REG)
+// hlv.bu               REG,(REG)
+// hlv.h                REG,(REG)
+// hlv.hu               REG,(REG)
+// hlvx.hu              REG,(REG)
+// hlv.w                REG,(REG)
+// hlvx.wu              REG,(REG)
+// hlv.wu               REG,(REG)
+// hlv.d                REG,(REG)
+// hsv.b                REG,(REG)
+// hsv.h                REG,(REG)
+// hsv.w                REG,(REG)
+// hsv.d                REG,(REG)
+
+instruction!(Mret {});
+instruction!(Sret {});
+instruction!(Wfi {});
+instruction!(SfenceVma { rs1, rs2 });
+instruction!(HfenceVvma { rs1, rs2 });
+instruction!(HfenceGvma { rs1, rs2 });
+instruction!(HlvB { rd, rs1 });
+instruction!(HlvBu { rd, rs1 });
+instruction!(HlvH { rd, rs1 });
+instruction!(HlvHu { rd, rs1 });
+instruction!(HlvxHu { rd, rs1 });
+instruction!(HlvW { rd, rs1 });
+instruction!(HlvxWu { rd, rs1 });
+instruction!(HlvWu { rd, rs1 });
+instruction!(HlvD { rd, rs1 });
+instruction!(HsvB { rs1, rs2 });
+instruction!(HsvH { rs1, rs2 });
+instruction!(HsvW { rs1, rs2 });
+instruction!(HsvD { rs1, rs2 });
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PrivilegedInstruction {
+    Mret(Mret),
+    Sret(Sret),
+    Wfi(Wfi),
+    SfenceVma(SfenceVma),
+    HfenceVvma(HfenceVvma),
+    HfenceGvma(HfenceGvma),
+    HlvB(HlvB),
+    HlvBu(HlvBu),
+    HlvH(HlvH),
+    HlvHu(HlvHu),
+    HlvxHu(HlvxHu),
+    HlvW(HlvW),
+    HlvxWu(HlvxWu),
+    HlvWu(HlvWu),
+    HlvD(HlvD),
+    HsvB(HsvB),
+    HsvH(HsvH),
+    HsvW(HsvW),
+    HsvD(HsvD),
+}
+
+impl PrivilegedInstruction {
+    /// Decode `word` as a privileged instruction of a core with the register width `xlen`.
+    ///
+    /// `hlv.wu`, `hlv.d` and `hsv.d` only exist on RV64.
+    pub fn decode(word: u32, xlen: Xlen) -> Option<Self> {
+        let rd = (word >> 7) & 0x1f;
+        let funct3 = (word >> 12) & 0b111;
+        let rs2 = (word >> 20) & 0x1f;
+        let funct7 = word >> 25;
+        let rv64 = xlen == Xlen::Rv64;
+
+        if word & 0x7f != OP_SYSTEM {
+            return None;
+        }
+
+        Some(match (funct3, funct7, rs2) {
+            _ if word == 0x3020_0073 => Self::Mret(Mret(word)),
+            _ if word == 0x1020_0073 => Self::Sret(Sret(word)),
+            _ if word == 0x1050_0073 => Self::Wfi(Wfi(word)),
+            (0b000, 0b0001001, _) if rd == 0 => Self::SfenceVma(SfenceVma(word)),
+            (0b000, 0b0010001, _) if rd == 0 => Self::HfenceVvma(HfenceVvma(word)),
+            (0b000, 0b0110001, _) if rd == 0 => Self::HfenceGvma(HfenceGvma(word)),
+            (0b100, 0b0110000, 0b00000) => Self::HlvB(HlvB(word)),
+            (0b100, 0b0110000, 0b00001) => Self::HlvBu(HlvBu(word)),
+            (0b100, 0b0110010, 0b00000) => Self::HlvH(HlvH(word)),
+            (0b100, 0b0110010, 0b00001) => Self::HlvHu(HlvHu(word)),
+            (0b100, 0b0110010, 0b00011) => Self::HlvxHu(HlvxHu(word)),
+            (0b100, 0b0110100, 0b00000) => Self::HlvW(HlvW(word)),
+            (0b100, 0b0110100, 0b00011) => Self::HlvxWu(HlvxWu(word)),
+            (0b100, 0b0110100, 0b00001) if rv64 => Self::HlvWu(HlvWu(word)),
+            (0b100, 0b0110110, 0b00000) if rv64 => Self::HlvD(HlvD(word)),
+            (0b100, 0b0110001, _) if rd == 0 => Self::HsvB(HsvB(word)),
+            (0b100, 0b0110011, _) if rd == 0 => Self::HsvH(HsvH(word)),
+            (0b100, 0b0110101, _) if rd == 0 => Self::HsvW(HsvW(word)),
+            (0b100, 0b0110111, _) if rd == 0 && rv64 => Self::HsvD(HsvD(word)),
+            _ => return None,
+        })
+    }
+}
+
+impl AsmInstruction for PrivilegedInstruction {
+    fn verb(&self) -> &'static str {
+        match self {
+            Self::Mret(i) => i.verb(),
+            Self::Sret(i) => i.verb(),
+            Self::Wfi(i) => i.verb(),
+            Self::SfenceVma(i) => i.verb(),
+            Self::HfenceVvma(i) => i.verb(),
+            Self::HfenceGvma(i) => i.verb(),
+            Self::HlvB(i) => i.verb(),
+            Self::HlvBu(i) => i.verb(),
+            Self::HlvH(i) => i.verb(),
+            Self::HlvHu(i) => i.verb(),
+            Self::HlvxHu(i) => i.verb(),
+            Self::HlvW(i) => i.verb(),
+            Self::HlvxWu(i) => i.verb(),
+            Self::HlvWu(i) => i.verb(),
+            Self::HlvD(i) => i.verb(),
+            Self::HsvB(i) => i.verb(),
+            Self::HsvH(i) => i.verb(),
+            Self::HsvW(i) => i.verb(),
+            Self::HsvD(i) => i.verb(),
+        }
+    }
+
+    fn arguments(&self) -> Vec<AsmArgument> {
+        match self {
+            Self::Mret(i) => i.arguments(),
+            Self::Sret(i) => i.arguments(),
+            Self::Wfi(i) => i.arguments(),
+            Self::SfenceVma(i) => i.arguments(),
+            Self::HfenceVvma(i) => i.arguments(),
+            Self::HfenceGvma(i) => i.arguments(),
+            Self::HlvB(i) => i.arguments(),
+            Self::HlvBu(i) => i.arguments(),
+            Self::HlvH(i) => i.arguments(),
+            Self::HlvHu(i) => i.arguments(),
+            Self::HlvxHu(i) => i.arguments(),
+            Self::HlvW(i) => i.arguments(),
+            Self::HlvxWu(i) => i.arguments(),
+            Self::HlvWu(i) => i.arguments(),
+            Self::HlvD(i) => i.arguments(),
+            Self::HsvB(i) => i.arguments(),
+            Self::HsvH(i) => i.arguments(),
+            Self::HsvW(i) => i.arguments(),
+            Self::HsvD(i) => i.arguments(),
+        }
+    }
+}
+
+/// The operands of a fence, leaving out trailing `zero` registers.
+#[inline]
+fn fence_operands<'a>(rs1: u8, rs2: u8) -> Vec<AsmArgument<'a>> {
+    match (rs1, rs2) {
+        (0, 0) => vec![],
+        (_, 0) => vec![AsmArgument::reg(rs1).unwrap()],
+        _ => vec![
+            AsmArgument::reg(rs1).unwrap(),
+            AsmArgument::reg(rs2).unwrap(),
+        ],
+    }
+}
+
+impl AsmInstruction for Mret {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "mret"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![]
+    }
+}
+
+impl AsmInstruction for Sret {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "sret"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![]
+    }
+}
+
+impl AsmInstruction for Wfi {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "wfi"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![]
+    }
+}
+
+impl AsmInstruction for SfenceVma {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "sfence.vma"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        fence_operands(self.rs1(), self.rs2())
+    }
+}
+
+impl AsmInstruction for HfenceVvma {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "hfence.vvma"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        fence_operands(self.rs1(), self.rs2())
+    }
+}
+
+impl AsmInstruction for HfenceGvma {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "hfence.gvma"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        fence_operands(self.rs1(), self.rs2())
+    }
+}
+
+impl AsmInstruction for HlvB {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "hlv.b"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::Address(AsmRegister::try_from(self.rs1()).unwrap()),
+        ]
+    }
+}
+
+impl AsmInstruction for HlvBu {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "hlv.bu"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::Address(AsmRegister::try_from(self.rs1()).unwrap()),
+        ]
+    }
+}
+
+impl AsmInstruction for HlvH {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "hlv.h"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::Address(AsmRegister::try_from(self.rs1()).unwrap()),
+        ]
+    }
+}
+
+impl AsmInstruction for HlvHu {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "hlv.hu"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::Address(AsmRegister::try_from(self.rs1()).unwrap()),
+        ]
+    }
+}
+
+impl AsmInstruction for HlvxHu {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "hlvx.hu"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::Address(AsmRegister::try_from(self.rs1()).unwrap()),
+        ]
+    }
+}
+
+impl AsmInstruction for HlvW {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "hlv.w"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::Address(AsmRegister::try_from(self.rs1()).unwrap()),
+        ]
+    }
+}
+
+impl AsmInstruction for HlvxWu {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "hlvx.wu"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::Address(AsmRegister::try_from(self.rs1()).unwrap()),
+        ]
+    }
+}
+
+impl AsmInstruction for HlvWu {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "hlv.wu"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::Address(AsmRegister::try_from(self.rs1()).unwrap()),
+        ]
+    }
+}
+
+impl AsmInstruction for HlvD {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "hlv.d"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::Address(AsmRegister::try_from(self.rs1()).unwrap()),
+        ]
+    }
+}
+
+impl AsmInstruction for HsvB {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "hsv.b"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rs2()).unwrap(),
+            AsmArgument::Address(AsmRegister::try_from(self.rs1()).unwrap()),
+        ]
+    }
+}
+
+impl AsmInstruction for HsvH {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "hsv.h"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rs2()).unwrap(),
+            AsmArgument::Address(AsmRegister::try_from(self.rs1()).unwrap()),
+        ]
+    }
+}
+
+impl AsmInstruction for HsvW {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "hsv.w"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rs2()).unwrap(),
+            AsmArgument::Address(AsmRegister::try_from(self.rs1()).unwrap()),
+        ]
+    }
+}
+
+impl AsmInstruction for HsvD {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "hsv.d"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rs2()).unwrap(),
+            AsmArgument::Address(AsmRegister::try_from(self.rs1()).unwrap()),
+        ]
+    }
+}
+
+#[test]
+fn operands() {
+    use crate::parse::{parse_word, parse_word_xlen};
+    use crate::AsmFormatOptions;
+
+    let display = |word, xlen| {
+        PrivilegedInstruction::decode(word, xlen).map(|instruction| {
+            instruction
+                .display(AsmFormatOptions::default().arg_spacing(" "))
+                .to_string()
+        })
+    };
+
+    assert_eq!(
+        display(0x12000073, Xlen::Rv32).as_deref(),
+        Some("sfence.vma\t")
+    );
+    assert_eq!(
+        display(0x12050073, Xlen::Rv32).as_deref(),
+        Some("sfence.vma\ta0")
+    );
+    assert_eq!(
+        display(0x12b00073, Xlen::Rv32).as_deref(),
+        Some("sfence.vma\tzero, a1")
+    );
+    assert_eq!(display(0x30200073, Xlen::Rv32).as_deref(), Some("mret\t"));
+    assert_eq!(
+        display(0x6005c573, Xlen::Rv32).as_deref(),
+        Some("hlv.b\ta0, (a1)")
+    );
+    assert_eq!(
+        display(0x6ad64073, Xlen::Rv32).as_deref(),
+        Some("hsv.w\ta3, (a2)")
+    );
+    assert_eq!(display(0x6c05c573, Xlen::Rv32), None);
+    assert_eq!(
+        display(0x6c05c573, Xlen::Rv64).as_deref(),
+        Some("hlv.d\ta0, (a1)")
+    );
+
+    assert_eq!(parse_word("wfi"), Ok(0x10500073));
+    assert_eq!(parse_word("hfence.gvma a0, a1"), Ok(0x62b50073));
+    assert_eq!(parse_word("hlvx.hu t0, (sp)"), Ok(0x643142f3));
+    assert_eq!(
+        parse_word_xlen("hsv.d a0, (a1)", Xlen::Rv64),
+        Ok(0x6ea5c073)
+    );
+    assert!(parse_word("hsv.d a0, (a1)").is_err());
+}
@@ -0,0 +1,373 @@
+//! Expansion of pseudo-instructions into base RV32I or RV64I instructions.
+//!
+//! Branches and jumps take a PC-relative offset, like the formatter prints them. `la`, `lla`,
+//! `call`, `tail` and the global load/store forms take an absolute address and are expanded into
+//! an `auipc` pair relative to the `pc` they are placed at.
+
+use riscv_encoding::rv32i::Rv32IInstruction;
+
+use crate::encode::{
+    b_type, branch_offset, check_range, encode, expect_count, i_type, imm, j_type, jump_offset,
+    r_type, reg, s_type, u_type, OP, OP_32, OP_AUIPC, OP_BRANCH, OP_IMM, OP_IMM_32, OP_JAL,
+    OP_JALR, OP_LOAD, OP_LUI, OP_STORE, ZERO,
+};
+use crate::parse::ParseError;
+use crate::{AsmArgument, AsmRelocation, Xlen};
+
+const RA: u8 = 1;
+const T1: u8 = 6;
+
+/// Split a 32-bit value into the upper 20 bits and the sign-extended lower 12 bits, such that
+/// `(hi << 12) + lo == value`.
+///
+/// The upper part is rounded by `0x800` to compensate for the sign of the lower part.
+#[inline]
+pub(crate) fn split_hi_lo(value: u32) -> (i64, i64) {
+    (
+        AsmRelocation::Hi.apply(value),
+        AsmRelocation::Lo.apply(value),
+    )
+}
+
+/// Load a constant into `rd`, any 32-bit one on RV32 and any 64-bit one on RV64.
+fn li(rd: u8, value: i64, xlen: Xlen) -> Result<Vec<u32>, ParseError> {
+    if xlen == Xlen::Rv64 {
+        return Ok(li64(rd, value));
+    }
+    let value = check_range(value, -0x8000_0000, 0xFFFF_FFFF)?;
+
+    if (-0x800..0x800).contains(&value) {
+        return Ok(vec![i_type(OP_IMM, 0b000, rd, ZERO, value)]);
+    }
+
+    let (hi, lo) = split_hi_lo(value as u32);
+    let mut words = vec![u_type(OP_LUI, rd, hi)];
+    if lo != 0 {
+        words.push(i_type(OP_IMM, 0b000, rd, rd, lo));
+    }
+
+    Ok(words)
+}
+
+/// `li` on RV64, where `lui` sign-extends its result and the lower part is added with `addiw`.
+///
+/// Values beyond 32 bits are built like LLVM does: the upper bits are loaded recursively and
+/// shifted into place, then the sign-extended lower 12 bits are added.
+fn li64(rd: u8, value: i64) -> Vec<u32> {
+    if i32::try_from(value).is_ok() {
+        let (hi, lo) = split_hi_lo(value as u32);
+        if hi == 0 {
+            return vec![i_type(OP_IMM, 0b000, rd, ZERO, lo)];
+        }
+
+        let mut words = vec![u_type(OP_LUI, rd, hi)];
+        if lo != 0 {
+            words.push(i_type(OP_IMM_32, 0b000, rd, rd, lo));
+        }
+        return words;
+    }
+
+    let lo = (value << 52) >> 52;
+    let hi = (value as u64).wrapping_add(0x800) >> 12;
+    let shift = 12 + hi.trailing_zeros();
+    let hi = ((hi >> (shift - 12)) << shift) as i64 >> shift;
+
+    let mut words = li64(rd, hi);
+    words.push(i_type(OP_IMM, 0b001, rd, rd, i64::from(shift)));
+    if lo != 0 {
+        words.push(i_type(OP_IMM, 0b000, rd, rd, lo));
+    }
+    words
+}
+
+/// `li` as a fixed `lui`/`addi` pair, for values that are not yet known when the program is
+/// being laid out.
+pub(crate) fn li_long(rd: u8, value: i64) -> Result<Vec<u32>, ParseError> {
+    let value = check_range(value, -0x8000_0000, 0xFFFF_FFFF)?;
+    let (hi, lo) = split_hi_lo(value as u32);
+    Ok(vec![
+        u_type(OP_LUI, rd, hi),
+        i_type(OP_IMM, 0b000, rd, rd, lo),
+    ])
+}
+
+/// `auipc` followed by an instruction using the low 12 bits, addressing `target` from `pc`.
+#[inline]
+fn pcrel(rd: u8, target: i64, pc: u32, lo_instr: impl FnOnce(i64) -> u32) -> Vec<u32> {
+    let (hi, lo) = split_hi_lo((target as u32).wrapping_sub(pc));
+    vec![u_type(OP_AUIPC, rd, hi), lo_instr(lo)]
+}
+
+#[inline]
+fn load_funct3(verb: &str, xlen: Xlen) -> Option<u32> {
+    Some(match verb {
+        "lb" => 0b000,
+        "lh" => 0b001,
+        "lw" => 0b010,
+        "lbu" => 0b100,
+        "lhu" => 0b101,
+        "ld" if xlen == Xlen::Rv64 => 0b011,
+        "lwu" if xlen == Xlen::Rv64 => 0b110,
+        _ => return None,
+    })
+}
+
+#[inline]
+fn store_funct3(verb: &str, xlen: Xlen) -> Option<u32> {
+    Some(match verb {
+        "sb" => 0b000,
+        "sh" => 0b001,
+        "sw" => 0b010,
+        "sd" if xlen == Xlen::Rv64 => 0b011,
+        _ => return None,
+    })
+}
+
+#[inline]
+fn is_address(arg: Option<&AsmArgument>) -> bool {
+    matches!(arg, Some(AsmArgument::Immediate(_) | AsmArgument::Label(_)))
+}
+
+/// Number of instruction words `verb` expands to.
+///
+/// Label operands are not resolved yet, so they are assumed to need the longest form.
+pub(crate) fn size(verb: &str, args: &[AsmArgument], xlen: Xlen) -> usize {
+    match verb {
+        "la" | "lla" | "call" | "tail" => 2,
+        "li" => match args.get(1) {
+            Some(AsmArgument::Immediate(value)) => {
+                li(ZERO, *value, xlen).map_or(1, |words| words.len())
+            }
+            _ => 2,
+        },
+        _ if load_funct3(verb, xlen).is_some() && args.len() == 2 && is_address(args.get(1)) => 2,
+        _ if store_funct3(verb, xlen).is_some() && args.len() == 3 && is_address(args.get(1)) => 2,
+        _ => 1,
+    }
+}
+
+/// Expand `verb` with `args`, placed at address `pc`, into instruction words.
+///
+/// Base instructions are passed through as a single word.
+pub(crate) fn expand_words(
+    verb: &str,
+    args: &[AsmArgument],
+    pc: u32,
+    xlen: Xlen,
+) -> Result<Vec<u32>, ParseError> {
+    let words = match verb {
+        "nop" => {
+            expect_count(verb, args, 0)?;
+            vec![i_type(OP_IMM, 0b000, ZERO, ZERO, 0)]
+        }
+        "li" => {
+            expect_count(verb, args, 2)?;
+            li(reg(args, 0)?, imm(args, 1)?, xlen)?
+        }
+        "la" | "lla" => {
+            expect_count(verb, args, 2)?;
+            let rd = reg(args, 0)?;
+            pcrel(rd, imm(args, 1)?, pc, |lo| {
+                i_type(OP_IMM, 0b000, rd, rd, lo)
+            })
+        }
+        "mv" => {
+            expect_count(verb, args, 2)?;
+            vec![i_type(OP_IMM, 0b000, reg(args, 0)?, reg(args, 1)?, 0)]
+        }
+        "not" => {
+            expect_count(verb, args, 2)?;
+            vec![i_type(OP_IMM, 0b100, reg(args, 0)?, reg(args, 1)?, -1)]
+        }
+        "neg" => {
+            expect_count(verb, args, 2)?;
+            vec![r_type(
+                OP,
+                0b000,
+                0b0100000,
+                reg(args, 0)?,
+                ZERO,
+                reg(args, 1)?,
+            )]
+        }
+        "negw" if xlen == Xlen::Rv64 => {
+            expect_count(verb, args, 2)?;
+            vec![r_type(
+                OP_32,
+                0b000,
+                0b0100000,
+                reg(args, 0)?,
+                ZERO,
+                reg(args, 1)?,
+            )]
+        }
+        "sext.w" if xlen == Xlen::Rv64 => {
+            expect_count(verb, args, 2)?;
+            vec![i_type(OP_IMM_32, 0b000, reg(args, 0)?, reg(args, 1)?, 0)]
+        }
+        "seqz" => {
+            expect_count(verb, args, 2)?;
+            vec![i_type(OP_IMM, 0b011, reg(args, 0)?, reg(args, 1)?, 1)]
+        }
+        "snez" => {
+            expect_count(verb, args, 2)?;
+            vec![r_type(OP, 0b011, 0, reg(args, 0)?, ZERO, reg(args, 1)?)]
+        }
+        "sltz" => {
+            expect_count(verb, args, 2)?;
+            vec![r_type(OP, 0b010, 0, reg(args, 0)?, reg(args, 1)?, ZERO)]
+        }
+        "sgtz" => {
+            expect_count(verb, args, 2)?;
+            vec![r_type(OP, 0b010, 0, reg(args, 0)?, ZERO, reg(args, 1)?)]
+        }
+        "beqz" | "bnez" | "bltz" | "bgez" | "bgtz" | "blez" => {
+            expect_count(verb, args, 2)?;
+            let rs = reg(args, 0)?;
+            let imm = branch_offset(imm(args, 1)?)?;
+            vec![match verb {
+                "beqz" => b_type(OP_BRANCH, 0b000, rs, ZERO, imm),
+                "bnez" => b_type(OP_BRANCH, 0b001, rs, ZERO, imm),
+                "bltz" => b_type(OP_BRANCH, 0b100, rs, ZERO, imm),
+                "bgez" => b_type(OP_BRANCH, 0b101, rs, ZERO, imm),
+                "bgtz" => b_type(OP_BRANCH, 0b100, ZERO, rs, imm),
+                _ => b_type(OP_BRANCH, 0b101, ZERO, rs, imm),
+            }]
+        }
+        "bgt" | "ble" | "bgtu" | "bleu" => {
+            expect_count(verb, args, 3)?;
+            let funct3 = match verb {
+                "bgt" => 0b100,
+                "ble" => 0b101,
+                "bgtu" => 0b110,
+                _ => 0b111,
+            };
+            let imm = branch_offset(imm(args, 2)?)?;
+            vec![b_type(OP_BRANCH, funct3, reg(args, 1)?, reg(args, 0)?, imm)]
+        }
+        "j" => {
+            expect_count(verb, args, 1)?;
+            vec![j_type(OP_JAL, ZERO, jump_offset(imm(args, 0)?)?)]
+        }
+        "jal" if args.len() == 1 => vec![j_type(OP_JAL, RA, jump_offset(imm(args, 0)?)?)],
+        "jr" => {
+            expect_count(verb, args, 1)?;
+            vec![i_type(OP_JALR, 0b000, ZERO, reg(args, 0)?, 0)]
+        }
+        "jalr" if args.len() == 1 => vec![i_type(OP_JALR, 0b000, RA, reg(args, 0)?, 0)],
+        "ret" => {
+            expect_count(verb, args, 0)?;
+            vec![i_type(OP_JALR, 0b000, ZERO, RA, 0)]
+        }
+        "call" => {
+            let (rd, target) = match args.len() {
+                1 => (RA, imm(args, 0)?),
+                _ => {
+                    expect_count(verb, args, 2)?;
+                    (reg(args, 0)?, imm(args, 1)?)
+                }
+            };
+            pcrel(rd, target, pc, |lo| i_type(OP_JALR, 0b000, rd, rd, lo))
+        }
+        "tail" => {
+            expect_count(verb, args, 1)?;
+            pcrel(T1, imm(args, 0)?, pc, |lo| {
+                i_type(OP_JALR, 0b000, ZERO, T1, lo)
+            })
+        }
+        _ => match (load_funct3(verb, xlen), store_funct3(verb, xlen)) {
+            (Some(funct3), _) if args.len() == 2 && is_address(args.get(1)) => {
+                let rd = reg(args, 0)?;
+                pcrel(rd, imm(args, 1)?, pc, |lo| {
+                    i_type(OP_LOAD, funct3, rd, rd, lo)
+                })
+            }
+            (_, Some(funct3)) if args.len() == 3 && is_address(args.get(1)) => {
+                let (rs, rt) = (reg(args, 0)?, reg(args, 2)?);
+                pcrel(rt, imm(args, 1)?, pc, |lo| {
+                    s_type(OP_STORE, funct3, rt, rs, lo)
+                })
+            }
+            _ => vec![encode(verb, args, xlen)?],
+        },
+    };
+
+    Ok(words)
+}
+
+/// Expand a (pseudo-)instruction placed at address `pc` into the base instructions it stands
+/// for.
+pub fn expand(
+    verb: &str,
+    args: &[AsmArgument],
+    pc: u32,
+) -> Result<Vec<Rv32IInstruction>, ParseError> {
+    expand_words(verb, args, pc, Xlen::Rv32)?
+        .into_iter()
+        .map(|word| Rv32IInstruction::try_from(word).map_err(|_| ParseError::Undecodable(word)))
+        .collect()
+}
+
+#[test]
+fn li_sign_correction() {
+    let li = |value| {
+        expand_words(
+            "li",
+            &[AsmArgument::reg(10).unwrap(), AsmArgument::Immediate(value)],
+            0,
+            Xlen::Rv32,
+        )
+    };
+
+    assert_eq!(li(-5), Ok(vec![0xffb00513]));
+    assert_eq!(li(0x1000), Ok(vec![0x00001537]));
+    // lui a0, 0xdeadc; addi a0, a0, -0x111
+    assert_eq!(li(0xdeadbeef), Ok(vec![0xdeadc537, 0xeef50513]));
+    assert!(li(0x1_0000_0000).is_err());
+}
+
+#[test]
+fn li_rv64() {
+    let li = |value| {
+        expand_words(
+            "li",
+            &[AsmArgument::reg(10).unwrap(), AsmArgument::Immediate(value)],
+            0,
+            Xlen::Rv64,
+        )
+        .unwrap()
+    };
+
+    // Same sequence as LLVM
+    assert_eq!(
+        li(0x1234_5678_9abc_def0),
+        [
+            0x00247537, 0x8ad5051b, 0x00e51513, 0xc4d50513, 0x00c51513, 0x5e750513, 0x00d51513,
+            0xef050513,
+        ]
+    );
+    // lui a0, 0x80000; addiw a0, a0, -0x800
+    assert_eq!(li(0x7fff_f800), [0x80000537, 0x8005051b]);
+
+    for value in [
+        0xffff_ffff,
+        i64::MAX,
+        i64::MIN,
+        -0x1234_5678_9abc,
+        0x8000_0000,
+    ] {
+        let mut a0 = 0i64;
+        for word in li(value) {
+            let imm = i64::from((word as i32) >> 20);
+            a0 = match (word & 0x7f, (word >> 12) & 0b111) {
+                (OP_LUI, _) => i64::from((word & 0xffff_f000) as i32),
+                (OP_IMM, 0b000) if (word >> 15) & 0x1f == 0 => imm,
+                (OP_IMM, 0b000) => a0.wrapping_add(imm),
+                (OP_IMM, 0b001) => a0 << (imm & 0x3f),
+                (OP_IMM_32, 0b000) => i64::from(a0.wrapping_add(imm) as i32),
+                _ => unreachable!("{word:08x}"),
+            };
+        }
+        assert_eq!(a0, value, "{value:x}");
+    }
+}
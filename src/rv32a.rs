@@ -0,0 +1,385 @@
+//! The A extension: load-reserved/store-conditional and atomic memory operations on words.
+
+use crate::encode::OP_AMO;
+use crate::fields::instruction;
+use crate::{AsmArgument, AsmInstruction, AsmRegister};
+
+/// The verb `base` followed by the `.aq`, `.rl` or `.aqrl` suffix for the ordering bits of
+/// `instruction`.
+///
+/// [`AsmInstruction::verb`] returns a `&'static str`, so every combination is spelled out with
+/// `concat!` rather than formatted.
+macro_rules! ordered {
+    ($instruction:expr, $base:literal) => {
+        match ($instruction.aq(), $instruction.rl()) {
+            (false, false) => $base,
+            (true, false) => concat!($base, ".aq"),
+            (false, true) => concat!($base, ".rl"),
+            (true, true) => concat!($base, ".aqrl"),
+        }
+    };
+}
+
+pub(crate) use ordered;
+
+// lr.w{.aq,.rl,.aqrl}          REG,(REG)
+// sc.w{.aq,.rl,.aqrl}          REG,REG,(REG)
+// amoswap.w{.aq,.rl,.aqrl}     REG,REG,(REG)
+// amoadd.w{.aq,.rl,.aqrl}      REG,REG,(REG)
+// amoxor.w{.aq,.rl,.aqrl}      REG,REG,(REG)
+// amoand.w{.aq,.rl,.aqrl}      REG,REG,(REG)
+// amoor.w{.aq,.rl,.aqrl}       REG,REG,(REG)
+// amomin.w{.aq,.rl,.aqrl}      REG,REG,(REG)
+// amomax.w{.aq,.rl,.aqrl}      REG,REG,(REG)
+// amominu.w{.aq,.rl,.aqrl}     REG,REG,(REG)
+// amomaxu.w{.aq,.rl,.aqrl}     REG,REG,(REG)
+
+instruction!(LrW { rd, rs1, aq, rl });
+instruction!(ScW {
+    rd,
+    rs1,
+    rs2,
+    aq,
+    rl
+});
+instruction!(AmoswapW {
+    rd,
+    rs1,
+    rs2,
+    aq,
+    rl
+});
+instruction!(AmoaddW {
+    rd,
+    rs1,
+    rs2,
+    aq,
+    rl
+});
+instruction!(AmoxorW {
+    rd,
+    rs1,
+    rs2,
+    aq,
+    rl
+});
+instruction!(AmoandW {
+    rd,
+    rs1,
+    rs2,
+    aq,
+    rl
+});
+instruction!(AmoorW {
+    rd,
+    rs1,
+    rs2,
+    aq,
+    rl
+});
+instruction!(AmominW {
+    rd,
+    rs1,
+    rs2,
+    aq,
+    rl
+});
+instruction!(AmomaxW {
+    rd,
+    rs1,
+    rs2,
+    aq,
+    rl
+});
+instruction!(AmominuW {
+    rd,
+    rs1,
+    rs2,
+    aq,
+    rl
+});
+instruction!(AmomaxuW {
+    rd,
+    rs1,
+    rs2,
+    aq,
+    rl
+});
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rv32AInstruction {
+    LrW(LrW),
+    ScW(ScW),
+    AmoswapW(AmoswapW),
+    AmoaddW(AmoaddW),
+    AmoxorW(AmoxorW),
+    AmoandW(AmoandW),
+    AmoorW(AmoorW),
+    AmominW(AmominW),
+    AmomaxW(AmomaxW),
+    AmominuW(AmominuW),
+    AmomaxuW(AmomaxuW),
+}
+
+impl TryFrom<u32> for Rv32AInstruction {
+    type Error = ();
+
+    fn try_from(word: u32) -> Result<Self, Self::Error> {
+        if word & 0x7f != OP_AMO || (word >> 12) & 0b111 != 0b010 {
+            return Err(());
+        }
+
+        let rs2 = (word >> 20) & 0x1f;
+        Ok(match word >> 27 {
+            0b00010 if rs2 == 0 => Self::LrW(LrW(word)),
+            0b00011 => Self::ScW(ScW(word)),
+            0b00001 => Self::AmoswapW(AmoswapW(word)),
+            0b00000 => Self::AmoaddW(AmoaddW(word)),
+            0b00100 => Self::AmoxorW(AmoxorW(word)),
+            0b01100 => Self::AmoandW(AmoandW(word)),
+            0b01000 => Self::AmoorW(AmoorW(word)),
+            0b10000 => Self::AmominW(AmominW(word)),
+            0b10100 => Self::AmomaxW(AmomaxW(word)),
+            0b11000 => Self::AmominuW(AmominuW(word)),
+            0b11100 => Self::AmomaxuW(AmomaxuW(word)),
+            _ => return Err(()),
+        })
+    }
+}
+
+impl AsmInstruction for Rv32AInstruction {
+    fn verb(&self) -> &'static str {
+        match self {
+            Self::LrW(i) => i.verb(),
+            Self::ScW(i) => i.verb(),
+            Self::AmoswapW(i) => i.verb(),
+            Self::AmoaddW(i) => i.verb(),
+            Self::AmoxorW(i) => i.verb(),
+            Self::AmoandW(i) => i.verb(),
+            Self::AmoorW(i) => i.verb(),
+            Self::AmominW(i) => i.verb(),
+            Self::AmomaxW(i) => i.verb(),
+            Self::AmominuW(i) => i.verb(),
+            Self::AmomaxuW(i) => i.verb(),
+        }
+    }
+
+    fn arguments(&self) -> Vec<AsmArgument> {
+        match self {
+            Self::LrW(i) => i.arguments(),
+            Self::ScW(i) => i.arguments(),
+            Self::AmoswapW(i) => i.arguments(),
+            Self::AmoaddW(i) => i.arguments(),
+            Self::AmoxorW(i) => i.arguments(),
+            Self::AmoandW(i) => i.arguments(),
+            Self::AmoorW(i) => i.arguments(),
+            Self::AmominW(i) => i.arguments(),
+            Self::AmomaxW(i) => i.arguments(),
+            Self::AmominuW(i) => i.arguments(),
+            Self::AmomaxuW(i) => i.arguments(),
+        }
+    }
+}
+
+impl AsmInstruction for LrW {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        ordered!(self, "lr.w")
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::Address(AsmRegister::try_from(self.rs1()).unwrap()),
+        ]
+    }
+}
+
+impl AsmInstruction for ScW {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        ordered!(self, "sc.w")
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::reg(self.rs2()).unwrap(),
+            AsmArgument::Address(AsmRegister::try_from(self.rs1()).unwrap()),
+        ]
+    }
+}
+
+impl AsmInstruction for AmoswapW {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        ordered!(self, "amoswap.w")
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::reg(self.rs2()).unwrap(),
+            AsmArgument::Address(AsmRegister::try_from(self.rs1()).unwrap()),
+        ]
+    }
+}
+
+impl AsmInstruction for AmoaddW {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        ordered!(self, "amoadd.w")
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::reg(self.rs2()).unwrap(),
+            AsmArgument::Address(AsmRegister::try_from(self.rs1()).unwrap()),
+        ]
+    }
+}
+
+impl AsmInstruction for AmoxorW {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        ordered!(self, "amoxor.w")
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::reg(self.rs2()).unwrap(),
+            AsmArgument::Address(AsmRegister::try_from(self.rs1()).unwrap()),
+        ]
+    }
+}
+
+impl AsmInstruction for AmoandW {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        ordered!(self, "amoand.w")
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::reg(self.rs2()).unwrap(),
+            AsmArgument::Address(AsmRegister::try_from(self.rs1()).unwrap()),
+        ]
+    }
+}
+
+impl AsmInstruction for AmoorW {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        ordered!(self, "amoor.w")
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::reg(self.rs2()).unwrap(),
+            AsmArgument::Address(AsmRegister::try_from(self.rs1()).unwrap()),
+        ]
+    }
+}
+
+impl AsmInstruction for AmominW {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        ordered!(self, "amomin.w")
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::reg(self.rs2()).unwrap(),
+            AsmArgument::Address(AsmRegister::try_from(self.rs1()).unwrap()),
+        ]
+    }
+}
+
+impl AsmInstruction for AmomaxW {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        ordered!(self, "amomax.w")
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::reg(self.rs2()).unwrap(),
+            AsmArgument::Address(AsmRegister::try_from(self.rs1()).unwrap()),
+        ]
+    }
+}
+
+impl AsmInstruction for AmominuW {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        ordered!(self, "amominu.w")
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::reg(self.rs2()).unwrap(),
+            AsmArgument::Address(AsmRegister::try_from(self.rs1()).unwrap()),
+        ]
+    }
+}
+
+impl AsmInstruction for AmomaxuW {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        ordered!(self, "amomaxu.w")
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::reg(self.rs2()).unwrap(),
+            AsmArgument::Address(AsmRegister::try_from(self.rs1()).unwrap()),
+        ]
+    }
+}
+
+#[test]
+fn ordering() {
+    use crate::instruction::Instruction;
+    use crate::parse::parse_word_xlen;
+    use crate::{AsmFormatOptions, Xlen};
+
+    for (text, word) in [
+        ("lr.w.aq\ta0, (a1)", 0x1405a52f),
+        ("sc.w.rl\ta0, a2, (a1)", 0x1ac5a52f),
+        ("amoadd.w.aqrl\ta0, a2, (a1)", 0x06c5a52f),
+        ("amoswap.w\tt0, t1, (sp)", 0x086122af),
+        ("lr.d\ta0, (a1)", 0x1005b52f),
+        ("amomaxu.d.aq\ta0, a2, (a1)", 0xe4c5b52f),
+    ] {
+        let instruction = Instruction::decode(word, Xlen::Rv64).unwrap();
+        let options = AsmFormatOptions::default().arg_spacing(" ");
+        assert_eq!(instruction.display(options).to_string(), text);
+        assert_eq!(parse_word_xlen(text, Xlen::Rv64), Ok(word));
+    }
+
+    assert_eq!(
+        crate::parse::parse_word("amoor.w a0, a2, 0(a1)"),
+        Ok(0x40c5a52f)
+    );
+    assert!(crate::parse::parse_word("amoor.w a0, a2, 4(a1)").is_err());
+    assert!(crate::parse::parse_word("lr.d a0, (a1)").is_err());
+}
@@ -0,0 +1,760 @@
+//! The bit-manipulation extensions Zba, Zbb, Zbc and Zbs on RV32.
+//!
+//! `zext.h` is the only form of `pack` in Zbb and is decoded as such.
+
+use crate::encode::{OP, OP_IMM};
+use crate::fields::instruction;
+use crate::{AsmArgument, AsmInstruction};
+
+// sh1add               REG,REG,REG
+// sh2add               REG,REG,REG
+// sh3add               REG,REG,REG
+// andn                 REG,REG,REG
+// orn                  REG,REG,REG
+// xnor                 REG,REG,REG
+// clz                  REG,REG
+// ctz                  REG,REG
+// cpop                 REG,REG
+// max                  REG,REG,REG
+// maxu                 REG,REG,REG
+// min                  REG,REG,REG
+// minu                 REG,REG,REG
+// sext.b               REG,REG
+// sext.h               REG,REG
+// zext.h               REG,REG
+// rol                  REG,REG,REG
+// ror                  REG,REG,REG
+// rori                 REG,REG,IMM
+// orc.b                REG,REG
+// rev8                 REG,REG
+// clmul                REG,REG,REG
+// clmulr               REG,REG,REG
+// clmulh               REG,REG,REG
+// bclr                 REG,REG,REG
+// bclri                REG,REG,IMM
+// bext                 REG,REG,REG
+// bexti                REG,REG,IMM
+// binv                 REG,REG,REG
+// binvi                REG,REG,IMM
+// bset                 REG,REG,REG
+// bseti                REG,REG,IMM
+
+instruction!(Sh1add { rd, rs1, rs2 });
+instruction!(Sh2add { rd, rs1, rs2 });
+instruction!(Sh3add { rd, rs1, rs2 });
+instruction!(Andn { rd, rs1, rs2 });
+instruction!(Orn { rd, rs1, rs2 });
+instruction!(Xnor { rd, rs1, rs2 });
+instruction!(Clz { rd, rs1 });
+instruction!(Ctz { rd, rs1 });
+instruction!(Cpop { rd, rs1 });
+instruction!(Max { rd, rs1, rs2 });
+instruction!(Maxu { rd, rs1, rs2 });
+instruction!(Min { rd, rs1, rs2 });
+instruction!(Minu { rd, rs1, rs2 });
+instruction!(SextB { rd, rs1 });
+instruction!(SextH { rd, rs1 });
+instruction!(ZextH { rd, rs1 });
+instruction!(Rol { rd, rs1, rs2 });
+instruction!(Ror { rd, rs1, rs2 });
+instruction!(Rori { rd, rs1, shamtw });
+instruction!(OrcB { rd, rs1 });
+instruction!(Rev8 { rd, rs1 });
+instruction!(Clmul { rd, rs1, rs2 });
+instruction!(Clmulr { rd, rs1, rs2 });
+instruction!(Clmulh { rd, rs1, rs2 });
+instruction!(Bclr { rd, rs1, rs2 });
+instruction!(Bclri { rd, rs1, shamtw });
+instruction!(Bext { rd, rs1, rs2 });
+instruction!(Bexti { rd, rs1, shamtw });
+instruction!(Binv { rd, rs1, rs2 });
+instruction!(Binvi { rd, rs1, shamtw });
+instruction!(Bset { rd, rs1, rs2 });
+instruction!(Bseti { rd, rs1, shamtw });
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rv32BInstruction {
+    Sh1add(Sh1add),
+    Sh2add(Sh2add),
+    Sh3add(Sh3add),
+    Andn(Andn),
+    Orn(Orn),
+    Xnor(Xnor),
+    Clz(Clz),
+    Ctz(Ctz),
+    Cpop(Cpop),
+    Max(Max),
+    Maxu(Maxu),
+    Min(Min),
+    Minu(Minu),
+    SextB(SextB),
+    SextH(SextH),
+    ZextH(ZextH),
+    Rol(Rol),
+    Ror(Ror),
+    Rori(Rori),
+    OrcB(OrcB),
+    Rev8(Rev8),
+    Clmul(Clmul),
+    Clmulr(Clmulr),
+    Clmulh(Clmulh),
+    Bclr(Bclr),
+    Bclri(Bclri),
+    Bext(Bext),
+    Bexti(Bexti),
+    Binv(Binv),
+    Binvi(Binvi),
+    Bset(Bset),
+    Bseti(Bseti),
+}
+
+impl TryFrom<u32> for Rv32BInstruction {
+    type Error = ();
+
+    fn try_from(word: u32) -> Result<Self, Self::Error> {
+        let funct3 = (word >> 12) & 0b111;
+        let funct7 = word >> 25;
+        let rs2 = (word >> 20) & 0x1f;
+
+        Ok(match (word & 0x7f, funct3, funct7) {
+            (OP, 0b010, 0b0010000) => Self::Sh1add(Sh1add(word)),
+            (OP, 0b100, 0b0010000) => Self::Sh2add(Sh2add(word)),
+            (OP, 0b110, 0b0010000) => Self::Sh3add(Sh3add(word)),
+            (OP, 0b111, 0b0100000) => Self::Andn(Andn(word)),
+            (OP, 0b110, 0b0100000) => Self::Orn(Orn(word)),
+            (OP, 0b100, 0b0100000) => Self::Xnor(Xnor(word)),
+            (OP_IMM, 0b001, 0b0110000) if rs2 == 0b00000 => Self::Clz(Clz(word)),
+            (OP_IMM, 0b001, 0b0110000) if rs2 == 0b00001 => Self::Ctz(Ctz(word)),
+            (OP_IMM, 0b001, 0b0110000) if rs2 == 0b00010 => Self::Cpop(Cpop(word)),
+            (OP, 0b110, 0b0000101) => Self::Max(Max(word)),
+            (OP, 0b111, 0b0000101) => Self::Maxu(Maxu(word)),
+            (OP, 0b100, 0b0000101) => Self::Min(Min(word)),
+            (OP, 0b101, 0b0000101) => Self::Minu(Minu(word)),
+            (OP_IMM, 0b001, 0b0110000) if rs2 == 0b00100 => Self::SextB(SextB(word)),
+            (OP_IMM, 0b001, 0b0110000) if rs2 == 0b00101 => Self::SextH(SextH(word)),
+            (OP, 0b100, 0b0000100) if rs2 == 0b00000 => Self::ZextH(ZextH(word)),
+            (OP, 0b001, 0b0110000) => Self::Rol(Rol(word)),
+            (OP, 0b101, 0b0110000) => Self::Ror(Ror(word)),
+            (OP_IMM, 0b101, 0b0110000) => Self::Rori(Rori(word)),
+            (OP_IMM, 0b101, 0b0010100) if rs2 == 0b00111 => Self::OrcB(OrcB(word)),
+            (OP_IMM, 0b101, 0b0110100) if rs2 == 0b11000 => Self::Rev8(Rev8(word)),
+            (OP, 0b001, 0b0000101) => Self::Clmul(Clmul(word)),
+            (OP, 0b010, 0b0000101) => Self::Clmulr(Clmulr(word)),
+            (OP, 0b011, 0b0000101) => Self::Clmulh(Clmulh(word)),
+            (OP, 0b001, 0b0100100) => Self::Bclr(Bclr(word)),
+            (OP_IMM, 0b001, 0b0100100) => Self::Bclri(Bclri(word)),
+            (OP, 0b101, 0b0100100) => Self::Bext(Bext(word)),
+            (OP_IMM, 0b101, 0b0100100) => Self::Bexti(Bexti(word)),
+            (OP, 0b001, 0b0110100) => Self::Binv(Binv(word)),
+            (OP_IMM, 0b001, 0b0110100) => Self::Binvi(Binvi(word)),
+            (OP, 0b001, 0b0010100) => Self::Bset(Bset(word)),
+            (OP_IMM, 0b001, 0b0010100) => Self::Bseti(Bseti(word)),
+            _ => return Err(()),
+        })
+    }
+}
+
+impl AsmInstruction for Rv32BInstruction {
+    fn verb(&self) -> &'static str {
+        match self {
+            Self::Sh1add(i) => i.verb(),
+            Self::Sh2add(i) => i.verb(),
+            Self::Sh3add(i) => i.verb(),
+            Self::Andn(i) => i.verb(),
+            Self::Orn(i) => i.verb(),
+            Self::Xnor(i) => i.verb(),
+            Self::Clz(i) => i.verb(),
+            Self::Ctz(i) => i.verb(),
+            Self::Cpop(i) => i.verb(),
+            Self::Max(i) => i.verb(),
+            Self::Maxu(i) => i.verb(),
+            Self::Min(i) => i.verb(),
+            Self::Minu(i) => i.verb(),
+            Self::SextB(i) => i.verb(),
+            Self::SextH(i) => i.verb(),
+            Self::ZextH(i) => i.verb(),
+            Self::Rol(i) => i.verb(),
+            Self::Ror(i) => i.verb(),
+            Self::Rori(i) => i.verb(),
+            Self::OrcB(i) => i.verb(),
+            Self::Rev8(i) => i.verb(),
+            Self::Clmul(i) => i.verb(),
+            Self::Clmulr(i) => i.verb(),
+            Self::Clmulh(i) => i.verb(),
+            Self::Bclr(i) => i.verb(),
+            Self::Bclri(i) => i.verb(),
+            Self::Bext(i) => i.verb(),
+            Self::Bexti(i) => i.verb(),
+            Self::Binv(i) => i.verb(),
+            Self::Binvi(i) => i.verb(),
+            Self::Bset(i) => i.verb(),
+            Self::Bseti(i) => i.verb(),
+        }
+    }
+
+    fn arguments(&self) -> Vec<AsmArgument> {
+        match self {
+            Self::Sh1add(i) => i.arguments(),
+            Self::Sh2add(i) => i.arguments(),
+            Self::Sh3add(i) => i.arguments(),
+            Self::Andn(i) => i.arguments(),
+            Self::Orn(i) => i.arguments(),
+            Self::Xnor(i) => i.arguments(),
+            Self::Clz(i) => i.arguments(),
+            Self::Ctz(i) => i.arguments(),
+            Self::Cpop(i) => i.arguments(),
+            Self::Max(i) => i.arguments(),
+            Self::Maxu(i) => i.arguments(),
+            Self::Min(i) => i.arguments(),
+            Self::Minu(i) => i.arguments(),
+            Self::SextB(i) => i.arguments(),
+            Self::SextH(i) => i.arguments(),
+            Self::ZextH(i) => i.arguments(),
+            Self::Rol(i) => i.arguments(),
+            Self::Ror(i) => i.arguments(),
+            Self::Rori(i) => i.arguments(),
+            Self::OrcB(i) => i.arguments(),
+            Self::Rev8(i) => i.arguments(),
+            Self::Clmul(i) => i.arguments(),
+            Self::Clmulr(i) => i.arguments(),
+            Self::Clmulh(i) => i.arguments(),
+            Self::Bclr(i) => i.arguments(),
+            Self::Bclri(i) => i.arguments(),
+            Self::Bext(i) => i.arguments(),
+            Self::Bexti(i) => i.arguments(),
+            Self::Binv(i) => i.arguments(),
+            Self::Binvi(i) => i.arguments(),
+            Self::Bset(i) => i.arguments(),
+            Self::Bseti(i) => i.arguments(),
+        }
+    }
+}
+
+impl AsmInstruction for Sh1add {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "sh1add"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::reg(self.rs1()).unwrap(),
+            AsmArgument::reg(self.rs2()).unwrap(),
+        ]
+    }
+}
+
+impl AsmInstruction for Sh2add {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "sh2add"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::reg(self.rs1()).unwrap(),
+            AsmArgument::reg(self.rs2()).unwrap(),
+        ]
+    }
+}
+
+impl AsmInstruction for Sh3add {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "sh3add"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::reg(self.rs1()).unwrap(),
+            AsmArgument::reg(self.rs2()).unwrap(),
+        ]
+    }
+}
+
+impl AsmInstruction for Andn {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "andn"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::reg(self.rs1()).unwrap(),
+            AsmArgument::reg(self.rs2()).unwrap(),
+        ]
+    }
+}
+
+impl AsmInstruction for Orn {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "orn"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::reg(self.rs1()).unwrap(),
+            AsmArgument::reg(self.rs2()).unwrap(),
+        ]
+    }
+}
+
+impl AsmInstruction for Xnor {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "xnor"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::reg(self.rs1()).unwrap(),
+            AsmArgument::reg(self.rs2()).unwrap(),
+        ]
+    }
+}
+
+impl AsmInstruction for Clz {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "clz"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::reg(self.rs1()).unwrap(),
+        ]
+    }
+}
+
+impl AsmInstruction for Ctz {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "ctz"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::reg(self.rs1()).unwrap(),
+        ]
+    }
+}
+
+impl AsmInstruction for Cpop {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "cpop"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::reg(self.rs1()).unwrap(),
+        ]
+    }
+}
+
+impl AsmInstruction for Max {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "max"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::reg(self.rs1()).unwrap(),
+            AsmArgument::reg(self.rs2()).unwrap(),
+        ]
+    }
+}
+
+impl AsmInstruction for Maxu {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "maxu"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::reg(self.rs1()).unwrap(),
+            AsmArgument::reg(self.rs2()).unwrap(),
+        ]
+    }
+}
+
+impl AsmInstruction for Min {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "min"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::reg(self.rs1()).unwrap(),
+            AsmArgument::reg(self.rs2()).unwrap(),
+        ]
+    }
+}
+
+impl AsmInstruction for Minu {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "minu"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::reg(self.rs1()).unwrap(),
+            AsmArgument::reg(self.rs2()).unwrap(),
+        ]
+    }
+}
+
+impl AsmInstruction for SextB {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "sext.b"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::reg(self.rs1()).unwrap(),
+        ]
+    }
+}
+
+impl AsmInstruction for SextH {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "sext.h"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::reg(self.rs1()).unwrap(),
+        ]
+    }
+}
+
+impl AsmInstruction for ZextH {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "zext.h"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::reg(self.rs1()).unwrap(),
+        ]
+    }
+}
+
+impl AsmInstruction for Rol {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "rol"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::reg(self.rs1()).unwrap(),
+            AsmArgument::reg(self.rs2()).unwrap(),
+        ]
+    }
+}
+
+impl AsmInstruction for Ror {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "ror"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::reg(self.rs1()).unwrap(),
+            AsmArgument::reg(self.rs2()).unwrap(),
+        ]
+    }
+}
+
+impl AsmInstruction for Rori {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "rori"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::reg(self.rs1()).unwrap(),
+            AsmArgument::Immediate(i64::from(self.shamt())),
+        ]
+    }
+}
+
+impl AsmInstruction for OrcB {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "orc.b"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::reg(self.rs1()).unwrap(),
+        ]
+    }
+}
+
+impl AsmInstruction for Rev8 {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "rev8"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::reg(self.rs1()).unwrap(),
+        ]
+    }
+}
+
+impl AsmInstruction for Clmul {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "clmul"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::reg(self.rs1()).unwrap(),
+            AsmArgument::reg(self.rs2()).unwrap(),
+        ]
+    }
+}
+
+impl AsmInstruction for Clmulr {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "clmulr"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::reg(self.rs1()).unwrap(),
+            AsmArgument::reg(self.rs2()).unwrap(),
+        ]
+    }
+}
+
+impl AsmInstruction for Clmulh {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "clmulh"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::reg(self.rs1()).unwrap(),
+            AsmArgument::reg(self.rs2()).unwrap(),
+        ]
+    }
+}
+
+impl AsmInstruction for Bclr {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "bclr"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::reg(self.rs1()).unwrap(),
+            AsmArgument::reg(self.rs2()).unwrap(),
+        ]
+    }
+}
+
+impl AsmInstruction for Bclri {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "bclri"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::reg(self.rs1()).unwrap(),
+            AsmArgument::Immediate(i64::from(self.shamt())),
+        ]
+    }
+}
+
+impl AsmInstruction for Bext {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "bext"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::reg(self.rs1()).unwrap(),
+            AsmArgument::reg(self.rs2()).unwrap(),
+        ]
+    }
+}
+
+impl AsmInstruction for Bexti {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "bexti"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::reg(self.rs1()).unwrap(),
+            AsmArgument::Immediate(i64::from(self.shamt())),
+        ]
+    }
+}
+
+impl AsmInstruction for Binv {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "binv"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::reg(self.rs1()).unwrap(),
+            AsmArgument::reg(self.rs2()).unwrap(),
+        ]
+    }
+}
+
+impl AsmInstruction for Binvi {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "binvi"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::reg(self.rs1()).unwrap(),
+            AsmArgument::Immediate(i64::from(self.shamt())),
+        ]
+    }
+}
+
+impl AsmInstruction for Bset {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "bset"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::reg(self.rs1()).unwrap(),
+            AsmArgument::reg(self.rs2()).unwrap(),
+        ]
+    }
+}
+
+impl AsmInstruction for Bseti {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "bseti"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::reg(self.rs1()).unwrap(),
+            AsmArgument::Immediate(i64::from(self.shamt())),
+        ]
+    }
+}
+
+#[test]
+fn display() {
+    use crate::parse::parse_word;
+    use crate::AsmFormatOptions;
+
+    let display = |word| {
+        Rv32BInstruction::try_from(word)
+            .unwrap()
+            .display(AsmFormatOptions::default().arg_spacing(" "))
+            .to_string()
+    };
+
+    assert_eq!(display(0x20c5a533), "sh1add\ta0, a1, a2");
+    assert_eq!(display(0x60259513), "cpop\ta0, a1");
+    assert_eq!(display(0x0805c533), "zext.h\ta0, a1");
+    assert_eq!(display(0x2875d513), "orc.b\ta0, a1");
+    assert_eq!(display(0x49f5d513), "bexti\ta0, a1, 0x1f");
+    // `pack` with a second source is not part of Zbb
+    assert!(Rv32BInstruction::try_from(0x08c5c533).is_err());
+
+    assert_eq!(parse_word("rev8 a0, a1"), Ok(0x6985d513));
+    assert_eq!(parse_word("bseti a0, a1, 17"), Ok(0x29159513));
+    assert!(parse_word("bseti a0, a1, 32").is_err());
+}
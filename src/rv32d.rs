@@ -0,0 +1,721 @@
+//! The D extension: double-precision floating point.
+
+use crate::encode::{OP_FP, OP_LOAD_FP, OP_MADD, OP_MSUB, OP_NMADD, OP_NMSUB, OP_STORE_FP};
+use crate::fields::instruction;
+use crate::{AsmArgument, AsmInstruction, AsmRegister, AsmRoundingMode};
+
+// fld                  REG,IMM(REG)
+// fsd                  REG,IMM(REG)
+// fmadd.d              REG,REG,REG,REG{,RM}
+// fmsub.d              REG,REG,REG,REG{,RM}
+// fnmsub.d             REG,REG,REG,REG{,RM}
+// fnmadd.d             REG,REG,REG,REG{,RM}
+// fadd.d               REG,REG,REG{,RM}
+// fsub.d               REG,REG,REG{,RM}
+// fmul.d               REG,REG,REG{,RM}
+// fdiv.d               REG,REG,REG{,RM}
+// fsqrt.d              REG,REG{,RM}
+// fsgnj.d              REG,REG,REG
+// fsgnjn.d             REG,REG,REG
+// fsgnjx.d             REG,REG,REG
+// fmin.d               REG,REG,REG
+// fmax.d               REG,REG,REG
+// feq.d                REG,REG,REG
+// flt.d                REG,REG,REG
+// fle.d                REG,REG,REG
+// fclass.d             REG,REG
+// fcvt.w.d             REG,REG{,RM}
+// fcvt.wu.d            REG,REG{,RM}
+// fcvt.s.d             REG,REG{,RM}
+// fcvt.d.s             REG,REG{,RM}
+// fcvt.d.w             REG,REG{,RM}
+// fcvt.d.wu            REG,REG{,RM}
+
+instruction!(Fld { rd, rs1, imm });
+instruction!(Fsd { rs1, rs2, s_imm });
+instruction!(FmaddD {
+    rd,
+    rs1,
+    rs2,
+    rs3,
+    rm
+});
+instruction!(FmsubD {
+    rd,
+    rs1,
+    rs2,
+    rs3,
+    rm
+});
+instruction!(FnmsubD {
+    rd,
+    rs1,
+    rs2,
+    rs3,
+    rm
+});
+instruction!(FnmaddD {
+    rd,
+    rs1,
+    rs2,
+    rs3,
+    rm
+});
+instruction!(FaddD { rd, rs1, rs2, rm });
+instruction!(FsubD { rd, rs1, rs2, rm });
+instruction!(FmulD { rd, rs1, rs2, rm });
+instruction!(FdivD { rd, rs1, rs2, rm });
+instruction!(FsqrtD { rd, rs1, rm });
+instruction!(FsgnjD { rd, rs1, rs2 });
+instruction!(FsgnjnD { rd, rs1, rs2 });
+instruction!(FsgnjxD { rd, rs1, rs2 });
+instruction!(FminD { rd, rs1, rs2 });
+instruction!(FmaxD { rd, rs1, rs2 });
+instruction!(FeqD { rd, rs1, rs2 });
+instruction!(FltD { rd, rs1, rs2 });
+instruction!(FleD { rd, rs1, rs2 });
+instruction!(FclassD { rd, rs1 });
+instruction!(FcvtWD { rd, rs1, rm });
+instruction!(FcvtWuD { rd, rs1, rm });
+instruction!(FcvtSD { rd, rs1, rm });
+instruction!(FcvtDS { rd, rs1, rm });
+instruction!(FcvtDW { rd, rs1, rm });
+instruction!(FcvtDWu { rd, rs1, rm });
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rv32DInstruction {
+    Fld(Fld),
+    Fsd(Fsd),
+    FmaddD(FmaddD),
+    FmsubD(FmsubD),
+    FnmsubD(FnmsubD),
+    FnmaddD(FnmaddD),
+    FaddD(FaddD),
+    FsubD(FsubD),
+    FmulD(FmulD),
+    FdivD(FdivD),
+    FsqrtD(FsqrtD),
+    FsgnjD(FsgnjD),
+    FsgnjnD(FsgnjnD),
+    FsgnjxD(FsgnjxD),
+    FminD(FminD),
+    FmaxD(FmaxD),
+    FeqD(FeqD),
+    FltD(FltD),
+    FleD(FleD),
+    FclassD(FclassD),
+    FcvtWD(FcvtWD),
+    FcvtWuD(FcvtWuD),
+    FcvtSD(FcvtSD),
+    FcvtDS(FcvtDS),
+    FcvtDW(FcvtDW),
+    FcvtDWu(FcvtDWu),
+}
+
+impl TryFrom<u32> for Rv32DInstruction {
+    type Error = ();
+
+    fn try_from(word: u32) -> Result<Self, Self::Error> {
+        let funct3 = (word >> 12) & 0b111;
+        let rs2 = (word >> 20) & 0x1f;
+        // Rounding modes 0b101 and 0b110 are reserved
+        let rm = AsmRoundingMode::try_from(funct3 as u8).is_ok();
+
+        Ok(match (word & 0x7f, word >> 25) {
+            (OP_LOAD_FP, _) if funct3 == 0b011 => Self::Fld(Fld(word)),
+            (OP_STORE_FP, _) if funct3 == 0b011 => Self::Fsd(Fsd(word)),
+            (OP_MADD, funct7) if funct7 & 0b11 == 0b01 && rm => Self::FmaddD(FmaddD(word)),
+            (OP_MSUB, funct7) if funct7 & 0b11 == 0b01 && rm => Self::FmsubD(FmsubD(word)),
+            (OP_NMSUB, funct7) if funct7 & 0b11 == 0b01 && rm => Self::FnmsubD(FnmsubD(word)),
+            (OP_NMADD, funct7) if funct7 & 0b11 == 0b01 && rm => Self::FnmaddD(FnmaddD(word)),
+            (OP_FP, 0b0000001) if rm => Self::FaddD(FaddD(word)),
+            (OP_FP, 0b0000101) if rm => Self::FsubD(FsubD(word)),
+            (OP_FP, 0b0001001) if rm => Self::FmulD(FmulD(word)),
+            (OP_FP, 0b0001101) if rm => Self::FdivD(FdivD(word)),
+            (OP_FP, 0b0101101) if rs2 == 0 && rm => Self::FsqrtD(FsqrtD(word)),
+            (OP_FP, 0b0010001) if funct3 == 0b000 => Self::FsgnjD(FsgnjD(word)),
+            (OP_FP, 0b0010001) if funct3 == 0b001 => Self::FsgnjnD(FsgnjnD(word)),
+            (OP_FP, 0b0010001) if funct3 == 0b010 => Self::FsgnjxD(FsgnjxD(word)),
+            (OP_FP, 0b0010101) if funct3 == 0b000 => Self::FminD(FminD(word)),
+            (OP_FP, 0b0010101) if funct3 == 0b001 => Self::FmaxD(FmaxD(word)),
+            (OP_FP, 0b1010001) if funct3 == 0b010 => Self::FeqD(FeqD(word)),
+            (OP_FP, 0b1010001) if funct3 == 0b001 => Self::FltD(FltD(word)),
+            (OP_FP, 0b1010001) if funct3 == 0b000 => Self::FleD(FleD(word)),
+            (OP_FP, 0b1110001) if rs2 == 0 && funct3 == 0b001 => Self::FclassD(FclassD(word)),
+            (OP_FP, 0b1100001) if rs2 == 0 && rm => Self::FcvtWD(FcvtWD(word)),
+            (OP_FP, 0b1100001) if rs2 == 1 && rm => Self::FcvtWuD(FcvtWuD(word)),
+            (OP_FP, 0b0100000) if rs2 == 1 && rm => Self::FcvtSD(FcvtSD(word)),
+            (OP_FP, 0b0100001) if rs2 == 0 && rm => Self::FcvtDS(FcvtDS(word)),
+            (OP_FP, 0b1101001) if rs2 == 0 && rm => Self::FcvtDW(FcvtDW(word)),
+            (OP_FP, 0b1101001) if rs2 == 1 && rm => Self::FcvtDWu(FcvtDWu(word)),
+            _ => return Err(()),
+        })
+    }
+}
+
+impl AsmInstruction for Rv32DInstruction {
+    fn verb(&self) -> &'static str {
+        match self {
+            Self::Fld(i) => i.verb(),
+            Self::Fsd(i) => i.verb(),
+            Self::FmaddD(i) => i.verb(),
+            Self::FmsubD(i) => i.verb(),
+            Self::FnmsubD(i) => i.verb(),
+            Self::FnmaddD(i) => i.verb(),
+            Self::FaddD(i) => i.verb(),
+            Self::FsubD(i) => i.verb(),
+            Self::FmulD(i) => i.verb(),
+            Self::FdivD(i) => i.verb(),
+            Self::FsqrtD(i) => i.verb(),
+            Self::FsgnjD(i) => i.verb(),
+            Self::FsgnjnD(i) => i.verb(),
+            Self::FsgnjxD(i) => i.verb(),
+            Self::FminD(i) => i.verb(),
+            Self::FmaxD(i) => i.verb(),
+            Self::FeqD(i) => i.verb(),
+            Self::FltD(i) => i.verb(),
+            Self::FleD(i) => i.verb(),
+            Self::FclassD(i) => i.verb(),
+            Self::FcvtWD(i) => i.verb(),
+            Self::FcvtWuD(i) => i.verb(),
+            Self::FcvtSD(i) => i.verb(),
+            Self::FcvtDS(i) => i.verb(),
+            Self::FcvtDW(i) => i.verb(),
+            Self::FcvtDWu(i) => i.verb(),
+        }
+    }
+
+    fn arguments(&self) -> Vec<AsmArgument> {
+        match self {
+            Self::Fld(i) => i.arguments(),
+            Self::Fsd(i) => i.arguments(),
+            Self::FmaddD(i) => i.arguments(),
+            Self::FmsubD(i) => i.arguments(),
+            Self::FnmsubD(i) => i.arguments(),
+            Self::FnmaddD(i) => i.arguments(),
+            Self::FaddD(i) => i.arguments(),
+            Self::FsubD(i) => i.arguments(),
+            Self::FmulD(i) => i.arguments(),
+            Self::FdivD(i) => i.arguments(),
+            Self::FsqrtD(i) => i.arguments(),
+            Self::FsgnjD(i) => i.arguments(),
+            Self::FsgnjnD(i) => i.arguments(),
+            Self::FsgnjxD(i) => i.arguments(),
+            Self::FminD(i) => i.arguments(),
+            Self::FmaxD(i) => i.arguments(),
+            Self::FeqD(i) => i.arguments(),
+            Self::FltD(i) => i.arguments(),
+            Self::FleD(i) => i.arguments(),
+            Self::FclassD(i) => i.arguments(),
+            Self::FcvtWD(i) => i.arguments(),
+            Self::FcvtWuD(i) => i.arguments(),
+            Self::FcvtSD(i) => i.arguments(),
+            Self::FcvtDS(i) => i.arguments(),
+            Self::FcvtDW(i) => i.arguments(),
+            Self::FcvtDWu(i) => i.arguments(),
+        }
+    }
+}
+
+impl AsmInstruction for Fld {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "fld"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::freg(self.rd()).unwrap(),
+            AsmArgument::OffsetImmediate(
+                i64::from(self.imm()),
+                AsmRegister::try_from(self.rs1()).unwrap(),
+            ),
+        ]
+    }
+}
+
+impl AsmInstruction for Fsd {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "fsd"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::freg(self.rs2()).unwrap(),
+            AsmArgument::OffsetImmediate(
+                i64::from(self.imm()),
+                AsmRegister::try_from(self.rs1()).unwrap(),
+            ),
+        ]
+    }
+}
+
+impl AsmInstruction for FmaddD {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "fmadd.d"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        let mut arguments = vec![
+            AsmArgument::freg(self.rd()).unwrap(),
+            AsmArgument::freg(self.rs1()).unwrap(),
+            AsmArgument::freg(self.rs2()).unwrap(),
+            AsmArgument::freg(self.rs3()).unwrap(),
+        ];
+        arguments.extend(AsmRoundingMode::operand(self.rm(), AsmRoundingMode::Dyn));
+        arguments
+    }
+}
+
+impl AsmInstruction for FmsubD {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "fmsub.d"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        let mut arguments = vec![
+            AsmArgument::freg(self.rd()).unwrap(),
+            AsmArgument::freg(self.rs1()).unwrap(),
+            AsmArgument::freg(self.rs2()).unwrap(),
+            AsmArgument::freg(self.rs3()).unwrap(),
+        ];
+        arguments.extend(AsmRoundingMode::operand(self.rm(), AsmRoundingMode::Dyn));
+        arguments
+    }
+}
+
+impl AsmInstruction for FnmsubD {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "fnmsub.d"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        let mut arguments = vec![
+            AsmArgument::freg(self.rd()).unwrap(),
+            AsmArgument::freg(self.rs1()).unwrap(),
+            AsmArgument::freg(self.rs2()).unwrap(),
+            AsmArgument::freg(self.rs3()).unwrap(),
+        ];
+        arguments.extend(AsmRoundingMode::operand(self.rm(), AsmRoundingMode::Dyn));
+        arguments
+    }
+}
+
+impl AsmInstruction for FnmaddD {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "fnmadd.d"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        let mut arguments = vec![
+            AsmArgument::freg(self.rd()).unwrap(),
+            AsmArgument::freg(self.rs1()).unwrap(),
+            AsmArgument::freg(self.rs2()).unwrap(),
+            AsmArgument::freg(self.rs3()).unwrap(),
+        ];
+        arguments.extend(AsmRoundingMode::operand(self.rm(), AsmRoundingMode::Dyn));
+        arguments
+    }
+}
+
+impl AsmInstruction for FaddD {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "fadd.d"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        let mut arguments = vec![
+            AsmArgument::freg(self.rd()).unwrap(),
+            AsmArgument::freg(self.rs1()).unwrap(),
+            AsmArgument::freg(self.rs2()).unwrap(),
+        ];
+        arguments.extend(AsmRoundingMode::operand(self.rm(), AsmRoundingMode::Dyn));
+        arguments
+    }
+}
+
+impl AsmInstruction for FsubD {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "fsub.d"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        let mut arguments = vec![
+            AsmArgument::freg(self.rd()).unwrap(),
+            AsmArgument::freg(self.rs1()).unwrap(),
+            AsmArgument::freg(self.rs2()).unwrap(),
+        ];
+        arguments.extend(AsmRoundingMode::operand(self.rm(), AsmRoundingMode::Dyn));
+        arguments
+    }
+}
+
+impl AsmInstruction for FmulD {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "fmul.d"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        let mut arguments = vec![
+            AsmArgument::freg(self.rd()).unwrap(),
+            AsmArgument::freg(self.rs1()).unwrap(),
+            AsmArgument::freg(self.rs2()).unwrap(),
+        ];
+        arguments.extend(AsmRoundingMode::operand(self.rm(), AsmRoundingMode::Dyn));
+        arguments
+    }
+}
+
+impl AsmInstruction for FdivD {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "fdiv.d"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        let mut arguments = vec![
+            AsmArgument::freg(self.rd()).unwrap(),
+            AsmArgument::freg(self.rs1()).unwrap(),
+            AsmArgument::freg(self.rs2()).unwrap(),
+        ];
+        arguments.extend(AsmRoundingMode::operand(self.rm(), AsmRoundingMode::Dyn));
+        arguments
+    }
+}
+
+impl AsmInstruction for FsqrtD {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "fsqrt.d"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        let mut arguments = vec![
+            AsmArgument::freg(self.rd()).unwrap(),
+            AsmArgument::freg(self.rs1()).unwrap(),
+        ];
+        arguments.extend(AsmRoundingMode::operand(self.rm(), AsmRoundingMode::Dyn));
+        arguments
+    }
+}
+
+impl AsmInstruction for FsgnjD {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        match self.rs1() == self.rs2() {
+            true => "fmv.d",
+            false => "fsgnj.d",
+        }
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        match self.rs1() == self.rs2() {
+            true => vec![
+                AsmArgument::freg(self.rd()).unwrap(),
+                AsmArgument::freg(self.rs1()).unwrap(),
+            ],
+            false => vec![
+                AsmArgument::freg(self.rd()).unwrap(),
+                AsmArgument::freg(self.rs1()).unwrap(),
+                AsmArgument::freg(self.rs2()).unwrap(),
+            ],
+        }
+    }
+}
+
+impl AsmInstruction for FsgnjnD {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        match self.rs1() == self.rs2() {
+            true => "fneg.d",
+            false => "fsgnjn.d",
+        }
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        match self.rs1() == self.rs2() {
+            true => vec![
+                AsmArgument::freg(self.rd()).unwrap(),
+                AsmArgument::freg(self.rs1()).unwrap(),
+            ],
+            false => vec![
+                AsmArgument::freg(self.rd()).unwrap(),
+                AsmArgument::freg(self.rs1()).unwrap(),
+                AsmArgument::freg(self.rs2()).unwrap(),
+            ],
+        }
+    }
+}
+
+impl AsmInstruction for FsgnjxD {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        match self.rs1() == self.rs2() {
+            true => "fabs.d",
+            false => "fsgnjx.d",
+        }
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        match self.rs1() == self.rs2() {
+            true => vec![
+                AsmArgument::freg(self.rd()).unwrap(),
+                AsmArgument::freg(self.rs1()).unwrap(),
+            ],
+            false => vec![
+                AsmArgument::freg(self.rd()).unwrap(),
+                AsmArgument::freg(self.rs1()).unwrap(),
+                AsmArgument::freg(self.rs2()).unwrap(),
+            ],
+        }
+    }
+}
+
+impl AsmInstruction for FminD {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "fmin.d"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::freg(self.rd()).unwrap(),
+            AsmArgument::freg(self.rs1()).unwrap(),
+            AsmArgument::freg(self.rs2()).unwrap(),
+        ]
+    }
+}
+
+impl AsmInstruction for FmaxD {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "fmax.d"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::freg(self.rd()).unwrap(),
+            AsmArgument::freg(self.rs1()).unwrap(),
+            AsmArgument::freg(self.rs2()).unwrap(),
+        ]
+    }
+}
+
+impl AsmInstruction for FeqD {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "feq.d"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::freg(self.rs1()).unwrap(),
+            AsmArgument::freg(self.rs2()).unwrap(),
+        ]
+    }
+}
+
+impl AsmInstruction for FltD {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "flt.d"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::freg(self.rs1()).unwrap(),
+            AsmArgument::freg(self.rs2()).unwrap(),
+        ]
+    }
+}
+
+impl AsmInstruction for FleD {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "fle.d"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::freg(self.rs1()).unwrap(),
+            AsmArgument::freg(self.rs2()).unwrap(),
+        ]
+    }
+}
+
+impl AsmInstruction for FclassD {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "fclass.d"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::freg(self.rs1()).unwrap(),
+        ]
+    }
+}
+
+impl AsmInstruction for FcvtWD {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "fcvt.w.d"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        let mut arguments = vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::freg(self.rs1()).unwrap(),
+        ];
+        arguments.extend(AsmRoundingMode::operand(self.rm(), AsmRoundingMode::Dyn));
+        arguments
+    }
+}
+
+impl AsmInstruction for FcvtWuD {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "fcvt.wu.d"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        let mut arguments = vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::freg(self.rs1()).unwrap(),
+        ];
+        arguments.extend(AsmRoundingMode::operand(self.rm(), AsmRoundingMode::Dyn));
+        arguments
+    }
+}
+
+impl AsmInstruction for FcvtSD {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "fcvt.s.d"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        let mut arguments = vec![
+            AsmArgument::freg(self.rd()).unwrap(),
+            AsmArgument::freg(self.rs1()).unwrap(),
+        ];
+        arguments.extend(AsmRoundingMode::operand(self.rm(), AsmRoundingMode::Dyn));
+        arguments
+    }
+}
+
+impl AsmInstruction for FcvtDS {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "fcvt.d.s"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        let mut arguments = vec![
+            AsmArgument::freg(self.rd()).unwrap(),
+            AsmArgument::freg(self.rs1()).unwrap(),
+        ];
+        arguments.extend(AsmRoundingMode::operand(self.rm(), AsmRoundingMode::Rne));
+        arguments
+    }
+}
+
+impl AsmInstruction for FcvtDW {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "fcvt.d.w"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        let mut arguments = vec![
+            AsmArgument::freg(self.rd()).unwrap(),
+            AsmArgument::reg(self.rs1()).unwrap(),
+        ];
+        arguments.extend(AsmRoundingMode::operand(self.rm(), AsmRoundingMode::Rne));
+        arguments
+    }
+}
+
+impl AsmInstruction for FcvtDWu {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "fcvt.d.wu"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        let mut arguments = vec![
+            AsmArgument::freg(self.rd()).unwrap(),
+            AsmArgument::reg(self.rs1()).unwrap(),
+        ];
+        arguments.extend(AsmRoundingMode::operand(self.rm(), AsmRoundingMode::Rne));
+        arguments
+    }
+}
+
+#[test]
+fn display() {
+    use crate::instruction::Instruction;
+    use crate::parse::parse_word_xlen;
+    use crate::{AsmFormatOptions, Xlen};
+
+    for (text, word) in [
+        ("fld\tfa0, 8(a1)", 0x0085b507),
+        ("fsd\tfs1, -16(sp)", 0xfe913827),
+        ("fmadd.d\tfa0, fa1, fa2, fa3", 0x6ac5f543),
+        ("fnmsub.d\tfa0, fa1, fa2, fa3, rne", 0x6ac5854b),
+        ("fadd.d\tfa0, fa1, fa2", 0x02c5f553),
+        ("fdiv.d\tft0, ft1, ft2, rtz", 0x1a209053),
+        ("fsqrt.d\tfa0, fa1", 0x5a05f553),
+        ("fsgnjx.d\tfa0, fa1, fa2", 0x22c5a553),
+        ("fmax.d\tfa0, fa1, fa2", 0x2ac59553),
+        ("fcvt.s.d\tfa0, fa1", 0x4015f553),
+        ("fcvt.d.s\tfa0, fa1", 0x42058553),
+        ("fle.d\ta0, fa1, fa2", 0xa2c58553),
+        ("fclass.d\ta0, fa1", 0xe2059553),
+        ("fcvt.w.d\ta0, fa1, rtz", 0xc2059553),
+        ("fcvt.d.wu\tfa0, a1", 0xd2158553),
+    ] {
+        let instruction = Instruction::decode(word, Xlen::Rv32).unwrap();
+        let options = AsmFormatOptions::default().arg_spacing(" ");
+        assert_eq!(instruction.display(options).to_string(), text);
+        assert_eq!(parse_word_xlen(text, Xlen::Rv32), Ok(word));
+    }
+}
@@ -0,0 +1,720 @@
+//! The F extension: single-precision floating point.
+
+use crate::encode::{OP_FP, OP_LOAD_FP, OP_MADD, OP_MSUB, OP_NMADD, OP_NMSUB, OP_STORE_FP};
+use crate::fields::instruction;
+use crate::{AsmArgument, AsmInstruction, AsmRegister, AsmRoundingMode};
+
+// flw                  REG,IMM(REG)
+// fsw                  REG,IMM(REG)
+// fmadd.s              REG,REG,REG,REG{,RM}
+// fmsub.s              REG,REG,REG,REG{,RM}
+// fnmsub.s             REG,REG,REG,REG{,RM}
+// fnmadd.s             REG,REG,REG,REG{,RM}
+// fadd.s               REG,REG,REG{,RM}
+// fsub.s               REG,REG,REG{,RM}
+// fmul.s               REG,REG,REG{,RM}
+// fdiv.s               REG,REG,REG{,RM}
+// fsqrt.s              REG,REG{,RM}
+// fsgnj.s              REG,REG,REG
+// fsgnjn.s             REG,REG,REG
+// fsgnjx.s             REG,REG,REG
+// fmin.s               REG,REG,REG
+// fmax.s               REG,REG,REG
+// feq.s                REG,REG,REG
+// flt.s                REG,REG,REG
+// fle.s                REG,REG,REG
+// fclass.s             REG,REG
+// fcvt.w.s             REG,REG{,RM}
+// fcvt.wu.s            REG,REG{,RM}
+// fmv.x.w              REG,REG
+// fcvt.s.w             REG,REG{,RM}
+// fcvt.s.wu            REG,REG{,RM}
+// fmv.w.x              REG,REG
+
+instruction!(Flw { rd, rs1, imm });
+instruction!(Fsw { rs1, rs2, s_imm });
+instruction!(FmaddS {
+    rd,
+    rs1,
+    rs2,
+    rs3,
+    rm
+});
+instruction!(FmsubS {
+    rd,
+    rs1,
+    rs2,
+    rs3,
+    rm
+});
+instruction!(FnmsubS {
+    rd,
+    rs1,
+    rs2,
+    rs3,
+    rm
+});
+instruction!(FnmaddS {
+    rd,
+    rs1,
+    rs2,
+    rs3,
+    rm
+});
+instruction!(FaddS { rd, rs1, rs2, rm });
+instruction!(FsubS { rd, rs1, rs2, rm });
+instruction!(FmulS { rd, rs1, rs2, rm });
+instruction!(FdivS { rd, rs1, rs2, rm });
+instruction!(FsqrtS { rd, rs1, rm });
+instruction!(FsgnjS { rd, rs1, rs2 });
+instruction!(FsgnjnS { rd, rs1, rs2 });
+instruction!(FsgnjxS { rd, rs1, rs2 });
+instruction!(FminS { rd, rs1, rs2 });
+instruction!(FmaxS { rd, rs1, rs2 });
+instruction!(FeqS { rd, rs1, rs2 });
+instruction!(FltS { rd, rs1, rs2 });
+instruction!(FleS { rd, rs1, rs2 });
+instruction!(FclassS { rd, rs1 });
+instruction!(FcvtWS { rd, rs1, rm });
+instruction!(FcvtWuS { rd, rs1, rm });
+instruction!(FmvXW { rd, rs1 });
+instruction!(FcvtSW { rd, rs1, rm });
+instruction!(FcvtSWu { rd, rs1, rm });
+instruction!(FmvWX { rd, rs1 });
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rv32FInstruction {
+    Flw(Flw),
+    Fsw(Fsw),
+    FmaddS(FmaddS),
+    FmsubS(FmsubS),
+    FnmsubS(FnmsubS),
+    FnmaddS(FnmaddS),
+    FaddS(FaddS),
+    FsubS(FsubS),
+    FmulS(FmulS),
+    FdivS(FdivS),
+    FsqrtS(FsqrtS),
+    FsgnjS(FsgnjS),
+    FsgnjnS(FsgnjnS),
+    FsgnjxS(FsgnjxS),
+    FminS(FminS),
+    FmaxS(FmaxS),
+    FeqS(FeqS),
+    FltS(FltS),
+    FleS(FleS),
+    FclassS(FclassS),
+    FcvtWS(FcvtWS),
+    FcvtWuS(FcvtWuS),
+    FmvXW(FmvXW),
+    FcvtSW(FcvtSW),
+    FcvtSWu(FcvtSWu),
+    FmvWX(FmvWX),
+}
+
+impl TryFrom<u32> for Rv32FInstruction {
+    type Error = ();
+
+    fn try_from(word: u32) -> Result<Self, Self::Error> {
+        let funct3 = (word >> 12) & 0b111;
+        let rs2 = (word >> 20) & 0x1f;
+        // Rounding modes 0b101 and 0b110 are reserved
+        let rm = AsmRoundingMode::try_from(funct3 as u8).is_ok();
+
+        Ok(match (word & 0x7f, word >> 25) {
+            (OP_LOAD_FP, _) if funct3 == 0b010 => Self::Flw(Flw(word)),
+            (OP_STORE_FP, _) if funct3 == 0b010 => Self::Fsw(Fsw(word)),
+            (OP_MADD, funct7) if funct7 & 0b11 == 0b00 && rm => Self::FmaddS(FmaddS(word)),
+            (OP_MSUB, funct7) if funct7 & 0b11 == 0b00 && rm => Self::FmsubS(FmsubS(word)),
+            (OP_NMSUB, funct7) if funct7 & 0b11 == 0b00 && rm => Self::FnmsubS(FnmsubS(word)),
+            (OP_NMADD, funct7) if funct7 & 0b11 == 0b00 && rm => Self::FnmaddS(FnmaddS(word)),
+            (OP_FP, 0b0000000) if rm => Self::FaddS(FaddS(word)),
+            (OP_FP, 0b0000100) if rm => Self::FsubS(FsubS(word)),
+            (OP_FP, 0b0001000) if rm => Self::FmulS(FmulS(word)),
+            (OP_FP, 0b0001100) if rm => Self::FdivS(FdivS(word)),
+            (OP_FP, 0b0101100) if rs2 == 0 && rm => Self::FsqrtS(FsqrtS(word)),
+            (OP_FP, 0b0010000) if funct3 == 0b000 => Self::FsgnjS(FsgnjS(word)),
+            (OP_FP, 0b0010000) if funct3 == 0b001 => Self::FsgnjnS(FsgnjnS(word)),
+            (OP_FP, 0b0010000) if funct3 == 0b010 => Self::FsgnjxS(FsgnjxS(word)),
+            (OP_FP, 0b0010100) if funct3 == 0b000 => Self::FminS(FminS(word)),
+            (OP_FP, 0b0010100) if funct3 == 0b001 => Self::FmaxS(FmaxS(word)),
+            (OP_FP, 0b1010000) if funct3 == 0b010 => Self::FeqS(FeqS(word)),
+            (OP_FP, 0b1010000) if funct3 == 0b001 => Self::FltS(FltS(word)),
+            (OP_FP, 0b1010000) if funct3 == 0b000 => Self::FleS(FleS(word)),
+            (OP_FP, 0b1110000) if rs2 == 0 && funct3 == 0b001 => Self::FclassS(FclassS(word)),
+            (OP_FP, 0b1100000) if rs2 == 0 && rm => Self::FcvtWS(FcvtWS(word)),
+            (OP_FP, 0b1100000) if rs2 == 1 && rm => Self::FcvtWuS(FcvtWuS(word)),
+            (OP_FP, 0b1110000) if rs2 == 0 && funct3 == 0b000 => Self::FmvXW(FmvXW(word)),
+            (OP_FP, 0b1101000) if rs2 == 0 && rm => Self::FcvtSW(FcvtSW(word)),
+            (OP_FP, 0b1101000) if rs2 == 1 && rm => Self::FcvtSWu(FcvtSWu(word)),
+            (OP_FP, 0b1111000) if rs2 == 0 && funct3 == 0b000 => Self::FmvWX(FmvWX(word)),
+            _ => return Err(()),
+        })
+    }
+}
+
+impl AsmInstruction for Rv32FInstruction {
+    fn verb(&self) -> &'static str {
+        match self {
+            Self::Flw(i) => i.verb(),
+            Self::Fsw(i) => i.verb(),
+            Self::FmaddS(i) => i.verb(),
+            Self::FmsubS(i) => i.verb(),
+            Self::FnmsubS(i) => i.verb(),
+            Self::FnmaddS(i) => i.verb(),
+            Self::FaddS(i) => i.verb(),
+            Self::FsubS(i) => i.verb(),
+            Self::FmulS(i) => i.verb(),
+            Self::FdivS(i) => i.verb(),
+            Self::FsqrtS(i) => i.verb(),
+            Self::FsgnjS(i) => i.verb(),
+            Self::FsgnjnS(i) => i.verb(),
+            Self::FsgnjxS(i) => i.verb(),
+            Self::FminS(i) => i.verb(),
+            Self::FmaxS(i) => i.verb(),
+            Self::FeqS(i) => i.verb(),
+            Self::FltS(i) => i.verb(),
+            Self::FleS(i) => i.verb(),
+            Self::FclassS(i) => i.verb(),
+            Self::FcvtWS(i) => i.verb(),
+            Self::FcvtWuS(i) => i.verb(),
+            Self::FmvXW(i) => i.verb(),
+            Self::FcvtSW(i) => i.verb(),
+            Self::FcvtSWu(i) => i.verb(),
+            Self::FmvWX(i) => i.verb(),
+        }
+    }
+
+    fn arguments(&self) -> Vec<AsmArgument> {
+        match self {
+            Self::Flw(i) => i.arguments(),
+            Self::Fsw(i) => i.arguments(),
+            Self::FmaddS(i) => i.arguments(),
+            Self::FmsubS(i) => i.arguments(),
+            Self::FnmsubS(i) => i.arguments(),
+            Self::FnmaddS(i) => i.arguments(),
+            Self::FaddS(i) => i.arguments(),
+            Self::FsubS(i) => i.arguments(),
+            Self::FmulS(i) => i.arguments(),
+            Self::FdivS(i) => i.arguments(),
+            Self::FsqrtS(i) => i.arguments(),
+            Self::FsgnjS(i) => i.arguments(),
+            Self::FsgnjnS(i) => i.arguments(),
+            Self::FsgnjxS(i) => i.arguments(),
+            Self::FminS(i) => i.arguments(),
+            Self::FmaxS(i) => i.arguments(),
+            Self::FeqS(i) => i.arguments(),
+            Self::FltS(i) => i.arguments(),
+            Self::FleS(i) => i.arguments(),
+            Self::FclassS(i) => i.arguments(),
+            Self::FcvtWS(i) => i.arguments(),
+            Self::FcvtWuS(i) => i.arguments(),
+            Self::FmvXW(i) => i.arguments(),
+            Self::FcvtSW(i) => i.arguments(),
+            Self::FcvtSWu(i) => i.arguments(),
+            Self::FmvWX(i) => i.arguments(),
+        }
+    }
+}
+
+impl AsmInstruction for Flw {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "flw"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::freg(self.rd()).unwrap(),
+            AsmArgument::OffsetImmediate(
+                i64::from(self.imm()),
+                AsmRegister::try_from(self.rs1()).unwrap(),
+            ),
+        ]
+    }
+}
+
+impl AsmInstruction for Fsw {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "fsw"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::freg(self.rs2()).unwrap(),
+            AsmArgument::OffsetImmediate(
+                i64::from(self.imm()),
+                AsmRegister::try_from(self.rs1()).unwrap(),
+            ),
+        ]
+    }
+}
+
+impl AsmInstruction for FmaddS {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "fmadd.s"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        let mut arguments = vec![
+            AsmArgument::freg(self.rd()).unwrap(),
+            AsmArgument::freg(self.rs1()).unwrap(),
+            AsmArgument::freg(self.rs2()).unwrap(),
+            AsmArgument::freg(self.rs3()).unwrap(),
+        ];
+        arguments.extend(AsmRoundingMode::operand(self.rm(), AsmRoundingMode::Dyn));
+        arguments
+    }
+}
+
+impl AsmInstruction for FmsubS {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "fmsub.s"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        let mut arguments = vec![
+            AsmArgument::freg(self.rd()).unwrap(),
+            AsmArgument::freg(self.rs1()).unwrap(),
+            AsmArgument::freg(self.rs2()).unwrap(),
+            AsmArgument::freg(self.rs3()).unwrap(),
+        ];
+        arguments.extend(AsmRoundingMode::operand(self.rm(), AsmRoundingMode::Dyn));
+        arguments
+    }
+}
+
+impl AsmInstruction for FnmsubS {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "fnmsub.s"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        let mut arguments = vec![
+            AsmArgument::freg(self.rd()).unwrap(),
+            AsmArgument::freg(self.rs1()).unwrap(),
+            AsmArgument::freg(self.rs2()).unwrap(),
+            AsmArgument::freg(self.rs3()).unwrap(),
+        ];
+        arguments.extend(AsmRoundingMode::operand(self.rm(), AsmRoundingMode::Dyn));
+        arguments
+    }
+}
+
+impl AsmInstruction for FnmaddS {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "fnmadd.s"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        let mut arguments = vec![
+            AsmArgument::freg(self.rd()).unwrap(),
+            AsmArgument::freg(self.rs1()).unwrap(),
+            AsmArgument::freg(self.rs2()).unwrap(),
+            AsmArgument::freg(self.rs3()).unwrap(),
+        ];
+        arguments.extend(AsmRoundingMode::operand(self.rm(), AsmRoundingMode::Dyn));
+        arguments
+    }
+}
+
+impl AsmInstruction for FaddS {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "fadd.s"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        let mut arguments = vec![
+            AsmArgument::freg(self.rd()).unwrap(),
+            AsmArgument::freg(self.rs1()).unwrap(),
+            AsmArgument::freg(self.rs2()).unwrap(),
+        ];
+        arguments.extend(AsmRoundingMode::operand(self.rm(), AsmRoundingMode::Dyn));
+        arguments
+    }
+}
+
+impl AsmInstruction for FsubS {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "fsub.s"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        let mut arguments = vec![
+            AsmArgument::freg(self.rd()).unwrap(),
+            AsmArgument::freg(self.rs1()).unwrap(),
+            AsmArgument::freg(self.rs2()).unwrap(),
+        ];
+        arguments.extend(AsmRoundingMode::operand(self.rm(), AsmRoundingMode::Dyn));
+        arguments
+    }
+}
+
+impl AsmInstruction for FmulS {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "fmul.s"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        let mut arguments = vec![
+            AsmArgument::freg(self.rd()).unwrap(),
+            AsmArgument::freg(self.rs1()).unwrap(),
+            AsmArgument::freg(self.rs2()).unwrap(),
+        ];
+        arguments.extend(AsmRoundingMode::operand(self.rm(), AsmRoundingMode::Dyn));
+        arguments
+    }
+}
+
+impl AsmInstruction for FdivS {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "fdiv.s"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        let mut arguments = vec![
+            AsmArgument::freg(self.rd()).unwrap(),
+            AsmArgument::freg(self.rs1()).unwrap(),
+            AsmArgument::freg(self.rs2()).unwrap(),
+        ];
+        arguments.extend(AsmRoundingMode::operand(self.rm(), AsmRoundingMode::Dyn));
+        arguments
+    }
+}
+
+impl AsmInstruction for FsqrtS {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "fsqrt.s"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        let mut arguments = vec![
+            AsmArgument::freg(self.rd()).unwrap(),
+            AsmArgument::freg(self.rs1()).unwrap(),
+        ];
+        arguments.extend(AsmRoundingMode::operand(self.rm(), AsmRoundingMode::Dyn));
+        arguments
+    }
+}
+
+impl AsmInstruction for FsgnjS {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        match self.rs1() == self.rs2() {
+            true => "fmv.s",
+            false => "fsgnj.s",
+        }
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        match self.rs1() == self.rs2() {
+            true => vec![
+                AsmArgument::freg(self.rd()).unwrap(),
+                AsmArgument::freg(self.rs1()).unwrap(),
+            ],
+            false => vec![
+                AsmArgument::freg(self.rd()).unwrap(),
+                AsmArgument::freg(self.rs1()).unwrap(),
+                AsmArgument::freg(self.rs2()).unwrap(),
+            ],
+        }
+    }
+}
+
+impl AsmInstruction for FsgnjnS {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        match self.rs1() == self.rs2() {
+            true => "fneg.s",
+            false => "fsgnjn.s",
+        }
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        match self.rs1() == self.rs2() {
+            true => vec![
+                AsmArgument::freg(self.rd()).unwrap(),
+                AsmArgument::freg(self.rs1()).unwrap(),
+            ],
+            false => vec![
+                AsmArgument::freg(self.rd()).unwrap(),
+                AsmArgument::freg(self.rs1()).unwrap(),
+                AsmArgument::freg(self.rs2()).unwrap(),
+            ],
+        }
+    }
+}
+
+impl AsmInstruction for FsgnjxS {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        match self.rs1() == self.rs2() {
+            true => "fabs.s",
+            false => "fsgnjx.s",
+        }
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        match self.rs1() == self.rs2() {
+            true => vec![
+                AsmArgument::freg(self.rd()).unwrap(),
+                AsmArgument::freg(self.rs1()).unwrap(),
+            ],
+            false => vec![
+                AsmArgument::freg(self.rd()).unwrap(),
+                AsmArgument::freg(self.rs1()).unwrap(),
+                AsmArgument::freg(self.rs2()).unwrap(),
+            ],
+        }
+    }
+}
+
+impl AsmInstruction for FminS {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "fmin.s"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::freg(self.rd()).unwrap(),
+            AsmArgument::freg(self.rs1()).unwrap(),
+            AsmArgument::freg(self.rs2()).unwrap(),
+        ]
+    }
+}
+
+impl AsmInstruction for FmaxS {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "fmax.s"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::freg(self.rd()).unwrap(),
+            AsmArgument::freg(self.rs1()).unwrap(),
+            AsmArgument::freg(self.rs2()).unwrap(),
+        ]
+    }
+}
+
+impl AsmInstruction for FeqS {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "feq.s"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::freg(self.rs1()).unwrap(),
+            AsmArgument::freg(self.rs2()).unwrap(),
+        ]
+    }
+}
+
+impl AsmInstruction for FltS {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "flt.s"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::freg(self.rs1()).unwrap(),
+            AsmArgument::freg(self.rs2()).unwrap(),
+        ]
+    }
+}
+
+impl AsmInstruction for FleS {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "fle.s"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::freg(self.rs1()).unwrap(),
+            AsmArgument::freg(self.rs2()).unwrap(),
+        ]
+    }
+}
+
+impl AsmInstruction for FclassS {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "fclass.s"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::freg(self.rs1()).unwrap(),
+        ]
+    }
+}
+
+impl AsmInstruction for FcvtWS {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "fcvt.w.s"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        let mut arguments = vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::freg(self.rs1()).unwrap(),
+        ];
+        arguments.extend(AsmRoundingMode::operand(self.rm(), AsmRoundingMode::Dyn));
+        arguments
+    }
+}
+
+impl AsmInstruction for FcvtWuS {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "fcvt.wu.s"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        let mut arguments = vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::freg(self.rs1()).unwrap(),
+        ];
+        arguments.extend(AsmRoundingMode::operand(self.rm(), AsmRoundingMode::Dyn));
+        arguments
+    }
+}
+
+impl AsmInstruction for FmvXW {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "fmv.x.w"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::freg(self.rs1()).unwrap(),
+        ]
+    }
+}
+
+impl AsmInstruction for FcvtSW {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "fcvt.s.w"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        let mut arguments = vec![
+            AsmArgument::freg(self.rd()).unwrap(),
+            AsmArgument::reg(self.rs1()).unwrap(),
+        ];
+        arguments.extend(AsmRoundingMode::operand(self.rm(), AsmRoundingMode::Dyn));
+        arguments
+    }
+}
+
+impl AsmInstruction for FcvtSWu {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "fcvt.s.wu"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        let mut arguments = vec![
+            AsmArgument::freg(self.rd()).unwrap(),
+            AsmArgument::reg(self.rs1()).unwrap(),
+        ];
+        arguments.extend(AsmRoundingMode::operand(self.rm(), AsmRoundingMode::Dyn));
+        arguments
+    }
+}
+
+impl AsmInstruction for FmvWX {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "fmv.w.x"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::freg(self.rd()).unwrap(),
+            AsmArgument::reg(self.rs1()).unwrap(),
+        ]
+    }
+}
+
+#[test]
+fn rounding_modes() {
+    use crate::assembler::Assembler;
+    use crate::AsmFormatOptions;
+
+    let display = |word, options: AsmFormatOptions| {
+        Rv32FInstruction::try_from(word)
+            .unwrap()
+            .display(options.arg_spacing(" "))
+            .to_string()
+    };
+
+    assert_eq!(
+        display(0x00c5f553, AsmFormatOptions::default()),
+        "fadd.s\tfa0, fa1, fa2"
+    );
+    assert_eq!(
+        display(0xc0059553, AsmFormatOptions::default()),
+        "fcvt.w.s\ta0, fa1, rtz"
+    );
+    assert_eq!(
+        display(0x20b58553, AsmFormatOptions::default().raw_reg_names(true)),
+        "fmv.s\tf10, f11"
+    );
+    // Rounding mode 0b101 is reserved
+    assert!(Rv32FInstruction::try_from(0x00c5d553).is_err());
+
+    let program = Assembler::new(0)
+        .assemble("fcvt.w.s a0, fa1, rtz\nfmadd.s ft0, ft1, ft2, ft11, rtz\nflw ft0, 4(a0)")
+        .unwrap();
+    assert_eq!(program.words(), [0xc0059553, 0xf8209043, 0x00452007]);
+}
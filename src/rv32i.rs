@@ -137,6 +137,19 @@ impl AsmInstruction for Rv32IInstruction {
             Self::EBreak(i) => i.arguments(),
         }
     }
+
+    fn is_pc_relative(&self) -> bool {
+        match self {
+            Self::Jal(i) => i.is_pc_relative(),
+            Self::Beq(i) => i.is_pc_relative(),
+            Self::Bne(i) => i.is_pc_relative(),
+            Self::Blt(i) => i.is_pc_relative(),
+            Self::Bge(i) => i.is_pc_relative(),
+            Self::Bltu(i) => i.is_pc_relative(),
+            Self::Bgeu(i) => i.is_pc_relative(),
+            _ => false,
+        }
+    }
 }
 
 impl AsmInstruction for Lui {
@@ -185,13 +198,17 @@ impl AsmInstruction for Jal {
             ],
         }
     }
+    #[inline]
+    fn is_pc_relative(&self) -> bool {
+        true
+    }
 }
 
 impl AsmInstruction for JalR {
     #[inline]
     fn verb(&self) -> &'static str {
         match (self.imm(), self.rd(), self.rs1()) {
-            (0, 1, 1) => "ret",
+            (0, 0, 1) => "ret",
             (0, 0, _) => "jr",
             _ => "jalr",
         }
@@ -199,7 +216,7 @@ impl AsmInstruction for JalR {
     #[inline]
     fn arguments(&self) -> Vec<AsmArgument> {
         match (self.imm(), self.rd(), self.rs1()) {
-            (0, 1, 1) => vec![],
+            (0, 0, 1) => vec![],
             (0, 0 | 1, _) => vec![AsmArgument::reg(self.rs1()).unwrap()],
             _ => vec![
                 AsmArgument::reg(self.rd()).unwrap(),
@@ -237,6 +254,11 @@ impl AsmInstruction for Beq {
             ],
         }
     }
+
+    #[inline]
+    fn is_pc_relative(&self) -> bool {
+        true
+    }
 }
 
 impl AsmInstruction for Bne {
@@ -266,6 +288,11 @@ impl AsmInstruction for Bne {
             ],
         }
     }
+
+    #[inline]
+    fn is_pc_relative(&self) -> bool {
+        true
+    }
 }
 
 impl AsmInstruction for Blt {
@@ -296,6 +323,11 @@ impl AsmInstruction for Blt {
             ],
         }
     }
+
+    #[inline]
+    fn is_pc_relative(&self) -> bool {
+        true
+    }
 }
 
 impl AsmInstruction for Bge {
@@ -326,6 +358,11 @@ impl AsmInstruction for Bge {
             ],
         }
     }
+
+    #[inline]
+    fn is_pc_relative(&self) -> bool {
+        true
+    }
 }
 
 impl AsmInstruction for Bltu {
@@ -342,6 +379,11 @@ impl AsmInstruction for Bltu {
             AsmArgument::Immediate(i64::from(self.imm())),
         ]
     }
+
+    #[inline]
+    fn is_pc_relative(&self) -> bool {
+        true
+    }
 }
 
 impl AsmInstruction for Bgeu {
@@ -358,6 +400,11 @@ impl AsmInstruction for Bgeu {
             AsmArgument::Immediate(i64::from(self.imm())),
         ]
     }
+
+    #[inline]
+    fn is_pc_relative(&self) -> bool {
+        true
+    }
 }
 
 impl AsmInstruction for Lb {
@@ -557,7 +604,7 @@ impl AsmInstruction for Sltiu {
     fn verb(&self) -> &'static str {
         match self.imm() {
             1 => "seqz",
-            _ => "slti",
+            _ => "sltiu",
         }
     }
 
@@ -829,7 +876,7 @@ impl AsmInstruction for Srl {
 impl AsmInstruction for Sra {
     #[inline]
     fn verb(&self) -> &'static str {
-        "add"
+        "sra"
     }
 
     #[inline]
@@ -0,0 +1,212 @@
+//! The M extension: integer multiplication and division.
+
+use crate::encode::{MULDIV, OP};
+use crate::fields::instruction;
+use crate::{AsmArgument, AsmInstruction};
+
+// mul                  REG,REG,REG
+// mulh                 REG,REG,REG
+// mulhsu               REG,REG,REG
+// mulhu                REG,REG,REG
+// div                  REG,REG,REG
+// divu                 REG,REG,REG
+// rem                  REG,REG,REG
+// remu                 REG,REG,REG
+
+instruction!(Mul { rd, rs1, rs2 });
+instruction!(Mulh { rd, rs1, rs2 });
+instruction!(Mulhsu { rd, rs1, rs2 });
+instruction!(Mulhu { rd, rs1, rs2 });
+instruction!(Div { rd, rs1, rs2 });
+instruction!(Divu { rd, rs1, rs2 });
+instruction!(Rem { rd, rs1, rs2 });
+instruction!(Remu { rd, rs1, rs2 });
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rv32MInstruction {
+    Mul(Mul),
+    Mulh(Mulh),
+    Mulhsu(Mulhsu),
+    Mulhu(Mulhu),
+    Div(Div),
+    Divu(Divu),
+    Rem(Rem),
+    Remu(Remu),
+}
+
+impl TryFrom<u32> for Rv32MInstruction {
+    type Error = ();
+
+    fn try_from(word: u32) -> Result<Self, Self::Error> {
+        if word & 0x7f != OP || word >> 25 != MULDIV {
+            return Err(());
+        }
+
+        Ok(match (word >> 12) & 0b111 {
+            0b000 => Self::Mul(Mul(word)),
+            0b001 => Self::Mulh(Mulh(word)),
+            0b010 => Self::Mulhsu(Mulhsu(word)),
+            0b011 => Self::Mulhu(Mulhu(word)),
+            0b100 => Self::Div(Div(word)),
+            0b101 => Self::Divu(Divu(word)),
+            0b110 => Self::Rem(Rem(word)),
+            _ => Self::Remu(Remu(word)),
+        })
+    }
+}
+
+impl AsmInstruction for Rv32MInstruction {
+    fn verb(&self) -> &'static str {
+        match self {
+            Self::Mul(i) => i.verb(),
+            Self::Mulh(i) => i.verb(),
+            Self::Mulhsu(i) => i.verb(),
+            Self::Mulhu(i) => i.verb(),
+            Self::Div(i) => i.verb(),
+            Self::Divu(i) => i.verb(),
+            Self::Rem(i) => i.verb(),
+            Self::Remu(i) => i.verb(),
+        }
+    }
+
+    fn arguments(&self) -> Vec<AsmArgument> {
+        match self {
+            Self::Mul(i) => i.arguments(),
+            Self::Mulh(i) => i.arguments(),
+            Self::Mulhsu(i) => i.arguments(),
+            Self::Mulhu(i) => i.arguments(),
+            Self::Div(i) => i.arguments(),
+            Self::Divu(i) => i.arguments(),
+            Self::Rem(i) => i.arguments(),
+            Self::Remu(i) => i.arguments(),
+        }
+    }
+}
+
+impl AsmInstruction for Mul {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "mul"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::reg(self.rs1()).unwrap(),
+            AsmArgument::reg(self.rs2()).unwrap(),
+        ]
+    }
+}
+
+impl AsmInstruction for Mulh {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "mulh"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::reg(self.rs1()).unwrap(),
+            AsmArgument::reg(self.rs2()).unwrap(),
+        ]
+    }
+}
+
+impl AsmInstruction for Mulhsu {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "mulhsu"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::reg(self.rs1()).unwrap(),
+            AsmArgument::reg(self.rs2()).unwrap(),
+        ]
+    }
+}
+
+impl AsmInstruction for Mulhu {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "mulhu"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::reg(self.rs1()).unwrap(),
+            AsmArgument::reg(self.rs2()).unwrap(),
+        ]
+    }
+}
+
+impl AsmInstruction for Div {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "div"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::reg(self.rs1()).unwrap(),
+            AsmArgument::reg(self.rs2()).unwrap(),
+        ]
+    }
+}
+
+impl AsmInstruction for Divu {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "divu"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::reg(self.rs1()).unwrap(),
+            AsmArgument::reg(self.rs2()).unwrap(),
+        ]
+    }
+}
+
+impl AsmInstruction for Rem {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "rem"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::reg(self.rs1()).unwrap(),
+            AsmArgument::reg(self.rs2()).unwrap(),
+        ]
+    }
+}
+
+impl AsmInstruction for Remu {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "remu"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::reg(self.rs1()).unwrap(),
+            AsmArgument::reg(self.rs2()).unwrap(),
+        ]
+    }
+}
@@ -0,0 +1,340 @@
+//! The doubleword atomics the A extension adds on RV64.
+
+use crate::encode::OP_AMO;
+use crate::fields::instruction;
+use crate::rv32a::ordered;
+use crate::{AsmArgument, AsmInstruction, AsmRegister};
+
+// lr.d{.aq,.rl,.aqrl}          REG,(REG)
+// sc.d{.aq,.rl,.aqrl}          REG,REG,(REG)
+// amoswap.d{.aq,.rl,.aqrl}     REG,REG,(REG)
+// amoadd.d{.aq,.rl,.aqrl}      REG,REG,(REG)
+// amoxor.d{.aq,.rl,.aqrl}      REG,REG,(REG)
+// amoand.d{.aq,.rl,.aqrl}      REG,REG,(REG)
+// amoor.d{.aq,.rl,.aqrl}       REG,REG,(REG)
+// amomin.d{.aq,.rl,.aqrl}      REG,REG,(REG)
+// amomax.d{.aq,.rl,.aqrl}      REG,REG,(REG)
+// amominu.d{.aq,.rl,.aqrl}     REG,REG,(REG)
+// amomaxu.d{.aq,.rl,.aqrl}     REG,REG,(REG)
+
+instruction!(LrD { rd, rs1, aq, rl });
+instruction!(ScD {
+    rd,
+    rs1,
+    rs2,
+    aq,
+    rl
+});
+instruction!(AmoswapD {
+    rd,
+    rs1,
+    rs2,
+    aq,
+    rl
+});
+instruction!(AmoaddD {
+    rd,
+    rs1,
+    rs2,
+    aq,
+    rl
+});
+instruction!(AmoxorD {
+    rd,
+    rs1,
+    rs2,
+    aq,
+    rl
+});
+instruction!(AmoandD {
+    rd,
+    rs1,
+    rs2,
+    aq,
+    rl
+});
+instruction!(AmoorD {
+    rd,
+    rs1,
+    rs2,
+    aq,
+    rl
+});
+instruction!(AmominD {
+    rd,
+    rs1,
+    rs2,
+    aq,
+    rl
+});
+instruction!(AmomaxD {
+    rd,
+    rs1,
+    rs2,
+    aq,
+    rl
+});
+instruction!(AmominuD {
+    rd,
+    rs1,
+    rs2,
+    aq,
+    rl
+});
+instruction!(AmomaxuD {
+    rd,
+    rs1,
+    rs2,
+    aq,
+    rl
+});
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rv64AInstruction {
+    LrD(LrD),
+    ScD(ScD),
+    AmoswapD(AmoswapD),
+    AmoaddD(AmoaddD),
+    AmoxorD(AmoxorD),
+    AmoandD(AmoandD),
+    AmoorD(AmoorD),
+    AmominD(AmominD),
+    AmomaxD(AmomaxD),
+    AmominuD(AmominuD),
+    AmomaxuD(AmomaxuD),
+}
+
+impl TryFrom<u32> for Rv64AInstruction {
+    type Error = ();
+
+    fn try_from(word: u32) -> Result<Self, Self::Error> {
+        if word & 0x7f != OP_AMO || (word >> 12) & 0b111 != 0b011 {
+            return Err(());
+        }
+
+        let rs2 = (word >> 20) & 0x1f;
+        Ok(match word >> 27 {
+            0b00010 if rs2 == 0 => Self::LrD(LrD(word)),
+            0b00011 => Self::ScD(ScD(word)),
+            0b00001 => Self::AmoswapD(AmoswapD(word)),
+            0b00000 => Self::AmoaddD(AmoaddD(word)),
+            0b00100 => Self::AmoxorD(AmoxorD(word)),
+            0b01100 => Self::AmoandD(AmoandD(word)),
+            0b01000 => Self::AmoorD(AmoorD(word)),
+            0b10000 => Self::AmominD(AmominD(word)),
+            0b10100 => Self::AmomaxD(AmomaxD(word)),
+            0b11000 => Self::AmominuD(AmominuD(word)),
+            0b11100 => Self::AmomaxuD(AmomaxuD(word)),
+            _ => return Err(()),
+        })
+    }
+}
+
+impl AsmInstruction for Rv64AInstruction {
+    fn verb(&self) -> &'static str {
+        match self {
+            Self::LrD(i) => i.verb(),
+            Self::ScD(i) => i.verb(),
+            Self::AmoswapD(i) => i.verb(),
+            Self::AmoaddD(i) => i.verb(),
+            Self::AmoxorD(i) => i.verb(),
+            Self::AmoandD(i) => i.verb(),
+            Self::AmoorD(i) => i.verb(),
+            Self::AmominD(i) => i.verb(),
+            Self::AmomaxD(i) => i.verb(),
+            Self::AmominuD(i) => i.verb(),
+            Self::AmomaxuD(i) => i.verb(),
+        }
+    }
+
+    fn arguments(&self) -> Vec<AsmArgument> {
+        match self {
+            Self::LrD(i) => i.arguments(),
+            Self::ScD(i) => i.arguments(),
+            Self::AmoswapD(i) => i.arguments(),
+            Self::AmoaddD(i) => i.arguments(),
+            Self::AmoxorD(i) => i.arguments(),
+            Self::AmoandD(i) => i.arguments(),
+            Self::AmoorD(i) => i.arguments(),
+            Self::AmominD(i) => i.arguments(),
+            Self::AmomaxD(i) => i.arguments(),
+            Self::AmominuD(i) => i.arguments(),
+            Self::AmomaxuD(i) => i.arguments(),
+        }
+    }
+}
+
+impl AsmInstruction for LrD {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        ordered!(self, "lr.d")
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::Address(AsmRegister::try_from(self.rs1()).unwrap()),
+        ]
+    }
+}
+
+impl AsmInstruction for ScD {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        ordered!(self, "sc.d")
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::reg(self.rs2()).unwrap(),
+            AsmArgument::Address(AsmRegister::try_from(self.rs1()).unwrap()),
+        ]
+    }
+}
+
+impl AsmInstruction for AmoswapD {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        ordered!(self, "amoswap.d")
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::reg(self.rs2()).unwrap(),
+            AsmArgument::Address(AsmRegister::try_from(self.rs1()).unwrap()),
+        ]
+    }
+}
+
+impl AsmInstruction for AmoaddD {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        ordered!(self, "amoadd.d")
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::reg(self.rs2()).unwrap(),
+            AsmArgument::Address(AsmRegister::try_from(self.rs1()).unwrap()),
+        ]
+    }
+}
+
+impl AsmInstruction for AmoxorD {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        ordered!(self, "amoxor.d")
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::reg(self.rs2()).unwrap(),
+            AsmArgument::Address(AsmRegister::try_from(self.rs1()).unwrap()),
+        ]
+    }
+}
+
+impl AsmInstruction for AmoandD {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        ordered!(self, "amoand.d")
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::reg(self.rs2()).unwrap(),
+            AsmArgument::Address(AsmRegister::try_from(self.rs1()).unwrap()),
+        ]
+    }
+}
+
+impl AsmInstruction for AmoorD {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        ordered!(self, "amoor.d")
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::reg(self.rs2()).unwrap(),
+            AsmArgument::Address(AsmRegister::try_from(self.rs1()).unwrap()),
+        ]
+    }
+}
+
+impl AsmInstruction for AmominD {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        ordered!(self, "amomin.d")
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::reg(self.rs2()).unwrap(),
+            AsmArgument::Address(AsmRegister::try_from(self.rs1()).unwrap()),
+        ]
+    }
+}
+
+impl AsmInstruction for AmomaxD {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        ordered!(self, "amomax.d")
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::reg(self.rs2()).unwrap(),
+            AsmArgument::Address(AsmRegister::try_from(self.rs1()).unwrap()),
+        ]
+    }
+}
+
+impl AsmInstruction for AmominuD {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        ordered!(self, "amominu.d")
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::reg(self.rs2()).unwrap(),
+            AsmArgument::Address(AsmRegister::try_from(self.rs1()).unwrap()),
+        ]
+    }
+}
+
+impl AsmInstruction for AmomaxuD {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        ordered!(self, "amomaxu.d")
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::reg(self.rs2()).unwrap(),
+            AsmArgument::Address(AsmRegister::try_from(self.rs1()).unwrap()),
+        ]
+    }
+}
@@ -0,0 +1,488 @@
+//! The instructions the bit-manipulation extensions add on RV64, and those encoded differently
+//! there.
+//!
+//! The immediate shifts and bit operations take a 6-bit amount, and `rev8` and `zext.h` have
+//! their own encodings on RV64, so they are redefined here. `add.uw` with `zero` as its second
+//! source is shown as `zext.w`.
+
+use crate::encode::{OP_32, OP_IMM, OP_IMM_32};
+use crate::fields::instruction;
+use crate::{AsmArgument, AsmInstruction};
+
+// add.uw               REG,REG,REG
+// sh1add.uw            REG,REG,REG
+// sh2add.uw            REG,REG,REG
+// sh3add.uw            REG,REG,REG
+// slli.uw              REG,REG,IMM
+// clzw                 REG,REG
+// ctzw                 REG,REG
+// cpopw                REG,REG
+// zext.h               REG,REG
+// rolw                 REG,REG,REG
+// rorw                 REG,REG,REG
+// rori                 REG,REG,IMM
+// roriw                REG,REG,IMM
+// rev8                 REG,REG
+// bclri                REG,REG,IMM
+// bexti                REG,REG,IMM
+// binvi                REG,REG,IMM
+// bseti                REG,REG,IMM
+
+instruction!(AddUw { rd, rs1, rs2 });
+instruction!(Sh1addUw { rd, rs1, rs2 });
+instruction!(Sh2addUw { rd, rs1, rs2 });
+instruction!(Sh3addUw { rd, rs1, rs2 });
+instruction!(SlliUw { rd, rs1, shamt });
+instruction!(Clzw { rd, rs1 });
+instruction!(Ctzw { rd, rs1 });
+instruction!(Cpopw { rd, rs1 });
+instruction!(ZextH { rd, rs1 });
+instruction!(Rolw { rd, rs1, rs2 });
+instruction!(Rorw { rd, rs1, rs2 });
+instruction!(Rori { rd, rs1, shamt });
+instruction!(Roriw { rd, rs1, shamtw });
+instruction!(Rev8 { rd, rs1 });
+instruction!(Bclri { rd, rs1, shamt });
+instruction!(Bexti { rd, rs1, shamt });
+instruction!(Binvi { rd, rs1, shamt });
+instruction!(Bseti { rd, rs1, shamt });
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rv64BInstruction {
+    AddUw(AddUw),
+    Sh1addUw(Sh1addUw),
+    Sh2addUw(Sh2addUw),
+    Sh3addUw(Sh3addUw),
+    SlliUw(SlliUw),
+    Clzw(Clzw),
+    Ctzw(Ctzw),
+    Cpopw(Cpopw),
+    ZextH(ZextH),
+    Rolw(Rolw),
+    Rorw(Rorw),
+    Rori(Rori),
+    Roriw(Roriw),
+    Rev8(Rev8),
+    Bclri(Bclri),
+    Bexti(Bexti),
+    Binvi(Binvi),
+    Bseti(Bseti),
+}
+
+impl TryFrom<u32> for Rv64BInstruction {
+    type Error = ();
+
+    fn try_from(word: u32) -> Result<Self, Self::Error> {
+        let funct3 = (word >> 12) & 0b111;
+        let funct7 = word >> 25;
+        let rs2 = (word >> 20) & 0x1f;
+
+        Ok(match (word & 0x7f, funct3, funct7) {
+            (OP_32, 0b000, 0b0000100) => Self::AddUw(AddUw(word)),
+            (OP_32, 0b010, 0b0010000) => Self::Sh1addUw(Sh1addUw(word)),
+            (OP_32, 0b100, 0b0010000) => Self::Sh2addUw(Sh2addUw(word)),
+            (OP_32, 0b110, 0b0010000) => Self::Sh3addUw(Sh3addUw(word)),
+            (OP_IMM_32, 0b001, 0b0110000) if rs2 == 0b00000 => Self::Clzw(Clzw(word)),
+            (OP_IMM_32, 0b001, 0b0110000) if rs2 == 0b00001 => Self::Ctzw(Ctzw(word)),
+            (OP_IMM_32, 0b001, 0b0110000) if rs2 == 0b00010 => Self::Cpopw(Cpopw(word)),
+            (OP_32, 0b100, 0b0000100) if rs2 == 0b00000 => Self::ZextH(ZextH(word)),
+            (OP_32, 0b001, 0b0110000) => Self::Rolw(Rolw(word)),
+            (OP_32, 0b101, 0b0110000) => Self::Rorw(Rorw(word)),
+            (OP_IMM_32, 0b101, 0b0110000) => Self::Roriw(Roriw(word)),
+            (OP_IMM, 0b101, 0b0110101) if rs2 == 0b11000 => Self::Rev8(Rev8(word)),
+            // The lowest bit of funct7 is bit 5 of the shift amount
+            (OP_IMM_32, 0b001, _) if funct7 >> 1 == 0b000010 => Self::SlliUw(SlliUw(word)),
+            (OP_IMM, 0b101, _) if funct7 >> 1 == 0b011000 => Self::Rori(Rori(word)),
+            (OP_IMM, 0b001, _) if funct7 >> 1 == 0b010010 => Self::Bclri(Bclri(word)),
+            (OP_IMM, 0b101, _) if funct7 >> 1 == 0b010010 => Self::Bexti(Bexti(word)),
+            (OP_IMM, 0b001, _) if funct7 >> 1 == 0b011010 => Self::Binvi(Binvi(word)),
+            (OP_IMM, 0b001, _) if funct7 >> 1 == 0b001010 => Self::Bseti(Bseti(word)),
+            _ => return Err(()),
+        })
+    }
+}
+
+impl AsmInstruction for Rv64BInstruction {
+    fn verb(&self) -> &'static str {
+        match self {
+            Self::AddUw(i) => i.verb(),
+            Self::Sh1addUw(i) => i.verb(),
+            Self::Sh2addUw(i) => i.verb(),
+            Self::Sh3addUw(i) => i.verb(),
+            Self::SlliUw(i) => i.verb(),
+            Self::Clzw(i) => i.verb(),
+            Self::Ctzw(i) => i.verb(),
+            Self::Cpopw(i) => i.verb(),
+            Self::ZextH(i) => i.verb(),
+            Self::Rolw(i) => i.verb(),
+            Self::Rorw(i) => i.verb(),
+            Self::Rori(i) => i.verb(),
+            Self::Roriw(i) => i.verb(),
+            Self::Rev8(i) => i.verb(),
+            Self::Bclri(i) => i.verb(),
+            Self::Bexti(i) => i.verb(),
+            Self::Binvi(i) => i.verb(),
+            Self::Bseti(i) => i.verb(),
+        }
+    }
+
+    fn arguments(&self) -> Vec<AsmArgument> {
+        match self {
+            Self::AddUw(i) => i.arguments(),
+            Self::Sh1addUw(i) => i.arguments(),
+            Self::Sh2addUw(i) => i.arguments(),
+            Self::Sh3addUw(i) => i.arguments(),
+            Self::SlliUw(i) => i.arguments(),
+            Self::Clzw(i) => i.arguments(),
+            Self::Ctzw(i) => i.arguments(),
+            Self::Cpopw(i) => i.arguments(),
+            Self::ZextH(i) => i.arguments(),
+            Self::Rolw(i) => i.arguments(),
+            Self::Rorw(i) => i.arguments(),
+            Self::Rori(i) => i.arguments(),
+            Self::Roriw(i) => i.arguments(),
+            Self::Rev8(i) => i.arguments(),
+            Self::Bclri(i) => i.arguments(),
+            Self::Bexti(i) => i.arguments(),
+            Self::Binvi(i) => i.arguments(),
+            Self::Bseti(i) => i.arguments(),
+        }
+    }
+}
+
+impl AsmInstruction for AddUw {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        match self.rs2() {
+            0 => "zext.w",
+            _ => "add.uw",
+        }
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        match self.rs2() {
+            0 => vec![
+                AsmArgument::reg(self.rd()).unwrap(),
+                AsmArgument::reg(self.rs1()).unwrap(),
+            ],
+            _ => vec![
+                AsmArgument::reg(self.rd()).unwrap(),
+                AsmArgument::reg(self.rs1()).unwrap(),
+                AsmArgument::reg(self.rs2()).unwrap(),
+            ],
+        }
+    }
+}
+
+impl AsmInstruction for Sh1addUw {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "sh1add.uw"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::reg(self.rs1()).unwrap(),
+            AsmArgument::reg(self.rs2()).unwrap(),
+        ]
+    }
+}
+
+impl AsmInstruction for Sh2addUw {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "sh2add.uw"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::reg(self.rs1()).unwrap(),
+            AsmArgument::reg(self.rs2()).unwrap(),
+        ]
+    }
+}
+
+impl AsmInstruction for Sh3addUw {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "sh3add.uw"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::reg(self.rs1()).unwrap(),
+            AsmArgument::reg(self.rs2()).unwrap(),
+        ]
+    }
+}
+
+impl AsmInstruction for SlliUw {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "slli.uw"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::reg(self.rs1()).unwrap(),
+            AsmArgument::Immediate(i64::from(self.shamt())),
+        ]
+    }
+}
+
+impl AsmInstruction for Clzw {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "clzw"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::reg(self.rs1()).unwrap(),
+        ]
+    }
+}
+
+impl AsmInstruction for Ctzw {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "ctzw"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::reg(self.rs1()).unwrap(),
+        ]
+    }
+}
+
+impl AsmInstruction for Cpopw {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "cpopw"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::reg(self.rs1()).unwrap(),
+        ]
+    }
+}
+
+impl AsmInstruction for ZextH {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "zext.h"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::reg(self.rs1()).unwrap(),
+        ]
+    }
+}
+
+impl AsmInstruction for Rolw {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "rolw"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::reg(self.rs1()).unwrap(),
+            AsmArgument::reg(self.rs2()).unwrap(),
+        ]
+    }
+}
+
+impl AsmInstruction for Rorw {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "rorw"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::reg(self.rs1()).unwrap(),
+            AsmArgument::reg(self.rs2()).unwrap(),
+        ]
+    }
+}
+
+impl AsmInstruction for Rori {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "rori"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::reg(self.rs1()).unwrap(),
+            AsmArgument::Immediate(i64::from(self.shamt())),
+        ]
+    }
+}
+
+impl AsmInstruction for Roriw {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "roriw"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::reg(self.rs1()).unwrap(),
+            AsmArgument::Immediate(i64::from(self.shamt())),
+        ]
+    }
+}
+
+impl AsmInstruction for Rev8 {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "rev8"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::reg(self.rs1()).unwrap(),
+        ]
+    }
+}
+
+impl AsmInstruction for Bclri {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "bclri"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::reg(self.rs1()).unwrap(),
+            AsmArgument::Immediate(i64::from(self.shamt())),
+        ]
+    }
+}
+
+impl AsmInstruction for Bexti {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "bexti"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::reg(self.rs1()).unwrap(),
+            AsmArgument::Immediate(i64::from(self.shamt())),
+        ]
+    }
+}
+
+impl AsmInstruction for Binvi {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "binvi"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::reg(self.rs1()).unwrap(),
+            AsmArgument::Immediate(i64::from(self.shamt())),
+        ]
+    }
+}
+
+impl AsmInstruction for Bseti {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "bseti"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::reg(self.rs1()).unwrap(),
+            AsmArgument::Immediate(i64::from(self.shamt())),
+        ]
+    }
+}
+
+#[test]
+fn encodings() {
+    use crate::instruction::Instruction;
+    use crate::parse::parse_word_xlen;
+    use crate::{AsmFormatOptions, Xlen};
+
+    let display = |word, xlen| {
+        Instruction::decode(word, xlen).map(|instruction| {
+            instruction
+                .display(AsmFormatOptions::default().arg_spacing(" "))
+                .to_string()
+        })
+    };
+
+    assert_eq!(
+        display(0x0805853b, Xlen::Rv64).as_deref(),
+        Some("zext.w\ta0, a1")
+    );
+    assert_eq!(
+        display(0x0bf5951b, Xlen::Rv64).as_deref(),
+        Some("slli.uw\ta0, a1, 0x3f")
+    );
+    // `rev8` and `zext.h` are encoded differently on RV32 and RV64
+    assert_eq!(
+        display(0x6b85d513, Xlen::Rv64).as_deref(),
+        Some("rev8\ta0, a1")
+    );
+    assert_eq!(display(0x6985d513, Xlen::Rv64), None);
+    assert_eq!(
+        display(0x0805c53b, Xlen::Rv64).as_deref(),
+        Some("zext.h\ta0, a1")
+    );
+    assert_eq!(display(0x0805c533, Xlen::Rv64), None);
+
+    assert_eq!(
+        parse_word_xlen("bclri a0, a1, 40", Xlen::Rv64),
+        Ok(0x4a859513)
+    );
+    assert_eq!(
+        parse_word_xlen("roriw a0, a1, 31", Xlen::Rv64),
+        Ok(0x61f5d51b)
+    );
+    assert!(parse_word_xlen("roriw a0, a1, 32", Xlen::Rv64).is_err());
+}
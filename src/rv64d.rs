@@ -0,0 +1,197 @@
+//! The conversions and moves between double precision and 64-bit integers the D extension adds
+//! on RV64.
+
+use crate::encode::OP_FP;
+use crate::fields::instruction;
+use crate::{AsmArgument, AsmInstruction, AsmRoundingMode};
+
+// fcvt.l.d             REG,REG{,RM}
+// fcvt.lu.d            REG,REG{,RM}
+// fmv.x.d              REG,REG
+// fcvt.d.l             REG,REG{,RM}
+// fcvt.d.lu            REG,REG{,RM}
+// fmv.d.x              REG,REG
+
+instruction!(FcvtLD { rd, rs1, rm });
+instruction!(FcvtLuD { rd, rs1, rm });
+instruction!(FmvXD { rd, rs1 });
+instruction!(FcvtDL { rd, rs1, rm });
+instruction!(FcvtDLu { rd, rs1, rm });
+instruction!(FmvDX { rd, rs1 });
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rv64DInstruction {
+    FcvtLD(FcvtLD),
+    FcvtLuD(FcvtLuD),
+    FmvXD(FmvXD),
+    FcvtDL(FcvtDL),
+    FcvtDLu(FcvtDLu),
+    FmvDX(FmvDX),
+}
+
+impl TryFrom<u32> for Rv64DInstruction {
+    type Error = ();
+
+    fn try_from(word: u32) -> Result<Self, Self::Error> {
+        let funct3 = (word >> 12) & 0b111;
+        let rs2 = (word >> 20) & 0x1f;
+        // Rounding modes 0b101 and 0b110 are reserved
+        let rm = AsmRoundingMode::try_from(funct3 as u8).is_ok();
+
+        Ok(match (word & 0x7f, word >> 25) {
+            (OP_FP, 0b1100001) if rs2 == 2 && rm => Self::FcvtLD(FcvtLD(word)),
+            (OP_FP, 0b1100001) if rs2 == 3 && rm => Self::FcvtLuD(FcvtLuD(word)),
+            (OP_FP, 0b1110001) if rs2 == 0 && funct3 == 0b000 => Self::FmvXD(FmvXD(word)),
+            (OP_FP, 0b1101001) if rs2 == 2 && rm => Self::FcvtDL(FcvtDL(word)),
+            (OP_FP, 0b1101001) if rs2 == 3 && rm => Self::FcvtDLu(FcvtDLu(word)),
+            (OP_FP, 0b1111001) if rs2 == 0 && funct3 == 0b000 => Self::FmvDX(FmvDX(word)),
+            _ => return Err(()),
+        })
+    }
+}
+
+impl AsmInstruction for Rv64DInstruction {
+    fn verb(&self) -> &'static str {
+        match self {
+            Self::FcvtLD(i) => i.verb(),
+            Self::FcvtLuD(i) => i.verb(),
+            Self::FmvXD(i) => i.verb(),
+            Self::FcvtDL(i) => i.verb(),
+            Self::FcvtDLu(i) => i.verb(),
+            Self::FmvDX(i) => i.verb(),
+        }
+    }
+
+    fn arguments(&self) -> Vec<AsmArgument> {
+        match self {
+            Self::FcvtLD(i) => i.arguments(),
+            Self::FcvtLuD(i) => i.arguments(),
+            Self::FmvXD(i) => i.arguments(),
+            Self::FcvtDL(i) => i.arguments(),
+            Self::FcvtDLu(i) => i.arguments(),
+            Self::FmvDX(i) => i.arguments(),
+        }
+    }
+}
+
+impl AsmInstruction for FcvtLD {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "fcvt.l.d"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        let mut arguments = vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::freg(self.rs1()).unwrap(),
+        ];
+        arguments.extend(AsmRoundingMode::operand(self.rm(), AsmRoundingMode::Dyn));
+        arguments
+    }
+}
+
+impl AsmInstruction for FcvtLuD {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "fcvt.lu.d"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        let mut arguments = vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::freg(self.rs1()).unwrap(),
+        ];
+        arguments.extend(AsmRoundingMode::operand(self.rm(), AsmRoundingMode::Dyn));
+        arguments
+    }
+}
+
+impl AsmInstruction for FmvXD {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "fmv.x.d"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::freg(self.rs1()).unwrap(),
+        ]
+    }
+}
+
+impl AsmInstruction for FcvtDL {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "fcvt.d.l"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        let mut arguments = vec![
+            AsmArgument::freg(self.rd()).unwrap(),
+            AsmArgument::reg(self.rs1()).unwrap(),
+        ];
+        arguments.extend(AsmRoundingMode::operand(self.rm(), AsmRoundingMode::Dyn));
+        arguments
+    }
+}
+
+impl AsmInstruction for FcvtDLu {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "fcvt.d.lu"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        let mut arguments = vec![
+            AsmArgument::freg(self.rd()).unwrap(),
+            AsmArgument::reg(self.rs1()).unwrap(),
+        ];
+        arguments.extend(AsmRoundingMode::operand(self.rm(), AsmRoundingMode::Dyn));
+        arguments
+    }
+}
+
+impl AsmInstruction for FmvDX {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "fmv.d.x"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::freg(self.rd()).unwrap(),
+            AsmArgument::reg(self.rs1()).unwrap(),
+        ]
+    }
+}
+
+#[test]
+fn conversions() {
+    use crate::instruction::Instruction;
+    use crate::parse::parse_word_xlen;
+    use crate::{AsmFormatOptions, Xlen};
+
+    for (text, word) in [
+        ("fcvt.l.d\ta0, fa1, rtz", 0xc2259553),
+        ("fcvt.lu.d\ta0, fa1", 0xc235f553),
+        ("fcvt.d.l\tfa0, a1", 0xd225f553),
+        ("fcvt.d.lu\tfa0, a1, rup", 0xd235b553),
+        ("fmv.x.d\ta0, fa1", 0xe2058553),
+        ("fmv.d.x\tfa0, a1", 0xf2058553),
+    ] {
+        let instruction = Instruction::decode(word, Xlen::Rv64).unwrap();
+        let options = AsmFormatOptions::default().arg_spacing(" ");
+        assert_eq!(instruction.display(options).to_string(), text);
+        assert_eq!(parse_word_xlen(text, Xlen::Rv64), Ok(word));
+    }
+
+    assert!(Instruction::decode(0xe2058553, Xlen::Rv32).is_none());
+    assert!(crate::parse::parse_word("fmv.x.d a0, fa1").is_err());
+}
@@ -0,0 +1,152 @@
+//! The conversions between single precision and 64-bit integers the F extension adds on RV64.
+
+use crate::encode::OP_FP;
+use crate::fields::instruction;
+use crate::{AsmArgument, AsmInstruction, AsmRoundingMode};
+
+// fcvt.l.s             REG,REG{,RM}
+// fcvt.lu.s            REG,REG{,RM}
+// fcvt.s.l             REG,REG{,RM}
+// fcvt.s.lu            REG,REG{,RM}
+
+instruction!(FcvtLS { rd, rs1, rm });
+instruction!(FcvtLuS { rd, rs1, rm });
+instruction!(FcvtSL { rd, rs1, rm });
+instruction!(FcvtSLu { rd, rs1, rm });
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rv64FInstruction {
+    FcvtLS(FcvtLS),
+    FcvtLuS(FcvtLuS),
+    FcvtSL(FcvtSL),
+    FcvtSLu(FcvtSLu),
+}
+
+impl TryFrom<u32> for Rv64FInstruction {
+    type Error = ();
+
+    fn try_from(word: u32) -> Result<Self, Self::Error> {
+        let funct3 = (word >> 12) & 0b111;
+        let rs2 = (word >> 20) & 0x1f;
+        // Rounding modes 0b101 and 0b110 are reserved
+        let rm = AsmRoundingMode::try_from(funct3 as u8).is_ok();
+
+        Ok(match (word & 0x7f, word >> 25) {
+            (OP_FP, 0b1100000) if rs2 == 2 && rm => Self::FcvtLS(FcvtLS(word)),
+            (OP_FP, 0b1100000) if rs2 == 3 && rm => Self::FcvtLuS(FcvtLuS(word)),
+            (OP_FP, 0b1101000) if rs2 == 2 && rm => Self::FcvtSL(FcvtSL(word)),
+            (OP_FP, 0b1101000) if rs2 == 3 && rm => Self::FcvtSLu(FcvtSLu(word)),
+            _ => return Err(()),
+        })
+    }
+}
+
+impl AsmInstruction for Rv64FInstruction {
+    fn verb(&self) -> &'static str {
+        match self {
+            Self::FcvtLS(i) => i.verb(),
+            Self::FcvtLuS(i) => i.verb(),
+            Self::FcvtSL(i) => i.verb(),
+            Self::FcvtSLu(i) => i.verb(),
+        }
+    }
+
+    fn arguments(&self) -> Vec<AsmArgument> {
+        match self {
+            Self::FcvtLS(i) => i.arguments(),
+            Self::FcvtLuS(i) => i.arguments(),
+            Self::FcvtSL(i) => i.arguments(),
+            Self::FcvtSLu(i) => i.arguments(),
+        }
+    }
+}
+
+impl AsmInstruction for FcvtLS {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "fcvt.l.s"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        let mut arguments = vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::freg(self.rs1()).unwrap(),
+        ];
+        arguments.extend(AsmRoundingMode::operand(self.rm(), AsmRoundingMode::Dyn));
+        arguments
+    }
+}
+
+impl AsmInstruction for FcvtLuS {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "fcvt.lu.s"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        let mut arguments = vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::freg(self.rs1()).unwrap(),
+        ];
+        arguments.extend(AsmRoundingMode::operand(self.rm(), AsmRoundingMode::Dyn));
+        arguments
+    }
+}
+
+impl AsmInstruction for FcvtSL {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "fcvt.s.l"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        let mut arguments = vec![
+            AsmArgument::freg(self.rd()).unwrap(),
+            AsmArgument::reg(self.rs1()).unwrap(),
+        ];
+        arguments.extend(AsmRoundingMode::operand(self.rm(), AsmRoundingMode::Dyn));
+        arguments
+    }
+}
+
+impl AsmInstruction for FcvtSLu {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "fcvt.s.lu"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        let mut arguments = vec![
+            AsmArgument::freg(self.rd()).unwrap(),
+            AsmArgument::reg(self.rs1()).unwrap(),
+        ];
+        arguments.extend(AsmRoundingMode::operand(self.rm(), AsmRoundingMode::Dyn));
+        arguments
+    }
+}
+
+#[test]
+fn conversions() {
+    use crate::instruction::Instruction;
+    use crate::parse::parse_word_xlen;
+    use crate::{AsmFormatOptions, Xlen};
+
+    for (text, word) in [
+        ("fcvt.l.s\ta0, fa1", 0xc025f553),
+        ("fcvt.lu.s\ta0, fa1, rtz", 0xc0359553),
+        ("fcvt.s.l\tfa0, a1", 0xd025f553),
+        ("fcvt.s.lu\tfa0, a1", 0xd035f553),
+    ] {
+        let instruction = Instruction::decode(word, Xlen::Rv64).unwrap();
+        let options = AsmFormatOptions::default().arg_spacing(" ");
+        assert_eq!(instruction.display(options).to_string(), text);
+        assert_eq!(parse_word_xlen(text, Xlen::Rv64), Ok(word));
+    }
+
+    assert!(Instruction::decode(0xc025f553, Xlen::Rv32).is_none());
+    assert!(crate::parse::parse_word("fcvt.l.s a0, fa1").is_err());
+}
@@ -0,0 +1,429 @@
+//! The instructions RV64I adds to RV32I.
+//!
+//! The immediate shifts take a 6-bit shift amount on RV64, so they are redefined here; every other
+//! RV32I encoding means the same on RV64 and is decoded as an
+//! [`Rv32IInstruction`](riscv_encoding::rv32i::Rv32IInstruction).
+
+use crate::encode::{OP_32, OP_IMM, OP_IMM_32, OP_LOAD, OP_STORE};
+use crate::fields::instruction;
+use crate::{AsmArgument, AsmInstruction, AsmRegister};
+
+// ld                   REG,IMM(REG)
+// lwu                  REG,IMM(REG)
+// sd                   REG,IMM(REG)
+// slli                 REG,REG,IMM
+// srli                 REG,REG,IMM
+// srai                 REG,REG,IMM
+// addiw                REG,REG,IMM
+// slliw                REG,REG,IMM
+// srliw                REG,REG,IMM
+// sraiw                REG,REG,IMM
+// addw                 REG,REG,REG
+// subw                 REG,REG,REG
+// sllw                 REG,REG,REG
+// srlw                 REG,REG,REG
+// sraw                 REG,REG,REG
+
+instruction!(Ld { rd, rs1, imm });
+instruction!(Lwu { rd, rs1, imm });
+instruction!(Sd { rs1, rs2, s_imm });
+instruction!(Slli { rd, rs1, shamt });
+instruction!(Srli { rd, rs1, shamt });
+instruction!(Srai { rd, rs1, shamt });
+instruction!(Addiw { rd, rs1, imm });
+instruction!(Slliw { rd, rs1, shamtw });
+instruction!(Srliw { rd, rs1, shamtw });
+instruction!(Sraiw { rd, rs1, shamtw });
+instruction!(Addw { rd, rs1, rs2 });
+instruction!(Subw { rd, rs1, rs2 });
+instruction!(Sllw { rd, rs1, rs2 });
+instruction!(Srlw { rd, rs1, rs2 });
+instruction!(Sraw { rd, rs1, rs2 });
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rv64IInstruction {
+    Ld(Ld),
+    Lwu(Lwu),
+    Sd(Sd),
+    Slli(Slli),
+    Srli(Srli),
+    Srai(Srai),
+    Addiw(Addiw),
+    Slliw(Slliw),
+    Srliw(Srliw),
+    Sraiw(Sraiw),
+    Addw(Addw),
+    Subw(Subw),
+    Sllw(Sllw),
+    Srlw(Srlw),
+    Sraw(Sraw),
+}
+
+impl TryFrom<u32> for Rv64IInstruction {
+    type Error = ();
+
+    fn try_from(word: u32) -> Result<Self, Self::Error> {
+        let funct3 = (word >> 12) & 0b111;
+        let funct7 = word >> 25;
+
+        Ok(match (word & 0x7f, funct3) {
+            (OP_LOAD, 0b011) => Self::Ld(Ld(word)),
+            (OP_LOAD, 0b110) => Self::Lwu(Lwu(word)),
+            (OP_STORE, 0b011) => Self::Sd(Sd(word)),
+            // The upper bit of funct7 is bit 5 of the shift amount
+            (OP_IMM, 0b001) if funct7 >> 1 == 0 => Self::Slli(Slli(word)),
+            (OP_IMM, 0b101) if funct7 >> 1 == 0 => Self::Srli(Srli(word)),
+            (OP_IMM, 0b101) if funct7 >> 1 == 0b010000 => Self::Srai(Srai(word)),
+            (OP_IMM_32, 0b000) => Self::Addiw(Addiw(word)),
+            (OP_IMM_32, 0b001) if funct7 == 0 => Self::Slliw(Slliw(word)),
+            (OP_IMM_32, 0b101) if funct7 == 0 => Self::Srliw(Srliw(word)),
+            (OP_IMM_32, 0b101) if funct7 == 0b0100000 => Self::Sraiw(Sraiw(word)),
+            (OP_32, 0b000) if funct7 == 0 => Self::Addw(Addw(word)),
+            (OP_32, 0b000) if funct7 == 0b0100000 => Self::Subw(Subw(word)),
+            (OP_32, 0b001) if funct7 == 0 => Self::Sllw(Sllw(word)),
+            (OP_32, 0b101) if funct7 == 0 => Self::Srlw(Srlw(word)),
+            (OP_32, 0b101) if funct7 == 0b0100000 => Self::Sraw(Sraw(word)),
+            _ => return Err(()),
+        })
+    }
+}
+
+impl AsmInstruction for Rv64IInstruction {
+    fn verb(&self) -> &'static str {
+        match self {
+            Self::Ld(i) => i.verb(),
+            Self::Lwu(i) => i.verb(),
+            Self::Sd(i) => i.verb(),
+            Self::Slli(i) => i.verb(),
+            Self::Srli(i) => i.verb(),
+            Self::Srai(i) => i.verb(),
+            Self::Addiw(i) => i.verb(),
+            Self::Slliw(i) => i.verb(),
+            Self::Srliw(i) => i.verb(),
+            Self::Sraiw(i) => i.verb(),
+            Self::Addw(i) => i.verb(),
+            Self::Subw(i) => i.verb(),
+            Self::Sllw(i) => i.verb(),
+            Self::Srlw(i) => i.verb(),
+            Self::Sraw(i) => i.verb(),
+        }
+    }
+
+    fn arguments(&self) -> Vec<AsmArgument> {
+        match self {
+            Self::Ld(i) => i.arguments(),
+            Self::Lwu(i) => i.arguments(),
+            Self::Sd(i) => i.arguments(),
+            Self::Slli(i) => i.arguments(),
+            Self::Srli(i) => i.arguments(),
+            Self::Srai(i) => i.arguments(),
+            Self::Addiw(i) => i.arguments(),
+            Self::Slliw(i) => i.arguments(),
+            Self::Srliw(i) => i.arguments(),
+            Self::Sraiw(i) => i.arguments(),
+            Self::Addw(i) => i.arguments(),
+            Self::Subw(i) => i.arguments(),
+            Self::Sllw(i) => i.arguments(),
+            Self::Srlw(i) => i.arguments(),
+            Self::Sraw(i) => i.arguments(),
+        }
+    }
+}
+
+impl AsmInstruction for Ld {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "ld"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::OffsetImmediate(
+                i64::from(self.imm()),
+                AsmRegister::try_from(self.rs1()).unwrap(),
+            ),
+        ]
+    }
+}
+
+impl AsmInstruction for Lwu {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "lwu"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::OffsetImmediate(
+                i64::from(self.imm()),
+                AsmRegister::try_from(self.rs1()).unwrap(),
+            ),
+        ]
+    }
+}
+
+impl AsmInstruction for Sd {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "sd"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rs2()).unwrap(),
+            AsmArgument::OffsetImmediate(
+                i64::from(self.imm()),
+                AsmRegister::try_from(self.rs1()).unwrap(),
+            ),
+        ]
+    }
+}
+
+impl AsmInstruction for Slli {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "slli"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::reg(self.rs1()).unwrap(),
+            AsmArgument::Immediate(i64::from(self.shamt())),
+        ]
+    }
+}
+
+impl AsmInstruction for Srli {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "srli"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::reg(self.rs1()).unwrap(),
+            AsmArgument::Immediate(i64::from(self.shamt())),
+        ]
+    }
+}
+
+impl AsmInstruction for Srai {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "srai"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::reg(self.rs1()).unwrap(),
+            AsmArgument::Immediate(i64::from(self.shamt())),
+        ]
+    }
+}
+
+impl AsmInstruction for Addiw {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        match self.imm() {
+            0 => "sext.w",
+            _ => "addiw",
+        }
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        match self.imm() {
+            0 => vec![
+                AsmArgument::reg(self.rd()).unwrap(),
+                AsmArgument::reg(self.rs1()).unwrap(),
+            ],
+            _ => vec![
+                AsmArgument::reg(self.rd()).unwrap(),
+                AsmArgument::reg(self.rs1()).unwrap(),
+                AsmArgument::Immediate(i64::from(self.imm())),
+            ],
+        }
+    }
+}
+
+impl AsmInstruction for Slliw {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "slliw"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::reg(self.rs1()).unwrap(),
+            AsmArgument::Immediate(i64::from(self.shamt())),
+        ]
+    }
+}
+
+impl AsmInstruction for Srliw {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "srliw"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::reg(self.rs1()).unwrap(),
+            AsmArgument::Immediate(i64::from(self.shamt())),
+        ]
+    }
+}
+
+impl AsmInstruction for Sraiw {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "sraiw"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::reg(self.rs1()).unwrap(),
+            AsmArgument::Immediate(i64::from(self.shamt())),
+        ]
+    }
+}
+
+impl AsmInstruction for Addw {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "addw"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::reg(self.rs1()).unwrap(),
+            AsmArgument::reg(self.rs2()).unwrap(),
+        ]
+    }
+}
+
+impl AsmInstruction for Subw {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        match self.rs1() {
+            0 => "negw",
+            _ => "subw",
+        }
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        match self.rs1() {
+            0 => vec![
+                AsmArgument::reg(self.rd()).unwrap(),
+                AsmArgument::reg(self.rs2()).unwrap(),
+            ],
+            _ => vec![
+                AsmArgument::reg(self.rd()).unwrap(),
+                AsmArgument::reg(self.rs1()).unwrap(),
+                AsmArgument::reg(self.rs2()).unwrap(),
+            ],
+        }
+    }
+}
+
+impl AsmInstruction for Sllw {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "sllw"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::reg(self.rs1()).unwrap(),
+            AsmArgument::reg(self.rs2()).unwrap(),
+        ]
+    }
+}
+
+impl AsmInstruction for Srlw {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "srlw"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::reg(self.rs1()).unwrap(),
+            AsmArgument::reg(self.rs2()).unwrap(),
+        ]
+    }
+}
+
+impl AsmInstruction for Sraw {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "sraw"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::reg(self.rs1()).unwrap(),
+            AsmArgument::reg(self.rs2()).unwrap(),
+        ]
+    }
+}
+
+#[test]
+fn aliases() {
+    use crate::AsmFormatOptions;
+
+    let display = |word| {
+        Rv64IInstruction::try_from(word)
+            .unwrap()
+            .display(AsmFormatOptions::default().arg_spacing(" "))
+            .to_string()
+    };
+
+    assert_eq!(display(0x0005851b), "sext.w\ta0, a1");
+    assert_eq!(display(0x40b0053b), "negw\ta0, a1");
+    assert_eq!(display(0x03f5d513), "srli\ta0, a1, 0x3f");
+    assert_eq!(display(0xff853503), "ld\ta0, -8(a0)");
+    assert!(Rv64IInstruction::try_from(0x0205951b).is_err());
+
+    for (text, word) in [
+        ("sext.w a0, a1", 0x0005851b),
+        ("negw a0, a1", 0x40b0053b),
+        ("slli a0, a1, 63", 0x03f59513),
+        ("sd a1, 16(sp)", 0x00b13823),
+        ("lwu a0, 4(a1)", 0x0045e503),
+        ("sraiw a0, a1, 31", 0x41f5d51b),
+    ] {
+        assert_eq!(
+            crate::parse::parse_word_xlen(text, crate::Xlen::Rv64),
+            Ok(word)
+        );
+    }
+    assert!(crate::parse::parse_word("ld a0, 0(a0)").is_err());
+    assert!(crate::parse::parse_word("slli a0, a1, 63").is_err());
+}
@@ -0,0 +1,171 @@
+//! The 32-bit multiplication and division instructions the M extension adds on RV64.
+
+use crate::encode::{MULDIV, OP_32};
+use crate::fields::instruction;
+use crate::{AsmArgument, AsmInstruction};
+
+// mulw                 REG,REG,REG
+// divw                 REG,REG,REG
+// divuw                REG,REG,REG
+// remw                 REG,REG,REG
+// remuw                REG,REG,REG
+
+instruction!(Mulw { rd, rs1, rs2 });
+instruction!(Divw { rd, rs1, rs2 });
+instruction!(Divuw { rd, rs1, rs2 });
+instruction!(Remw { rd, rs1, rs2 });
+instruction!(Remuw { rd, rs1, rs2 });
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rv64MInstruction {
+    Mulw(Mulw),
+    Divw(Divw),
+    Divuw(Divuw),
+    Remw(Remw),
+    Remuw(Remuw),
+}
+
+impl TryFrom<u32> for Rv64MInstruction {
+    type Error = ();
+
+    fn try_from(word: u32) -> Result<Self, Self::Error> {
+        if word & 0x7f != OP_32 || word >> 25 != MULDIV {
+            return Err(());
+        }
+
+        Ok(match (word >> 12) & 0b111 {
+            0b000 => Self::Mulw(Mulw(word)),
+            0b100 => Self::Divw(Divw(word)),
+            0b101 => Self::Divuw(Divuw(word)),
+            0b110 => Self::Remw(Remw(word)),
+            0b111 => Self::Remuw(Remuw(word)),
+            _ => return Err(()),
+        })
+    }
+}
+
+impl AsmInstruction for Rv64MInstruction {
+    fn verb(&self) -> &'static str {
+        match self {
+            Self::Mulw(i) => i.verb(),
+            Self::Divw(i) => i.verb(),
+            Self::Divuw(i) => i.verb(),
+            Self::Remw(i) => i.verb(),
+            Self::Remuw(i) => i.verb(),
+        }
+    }
+
+    fn arguments(&self) -> Vec<AsmArgument> {
+        match self {
+            Self::Mulw(i) => i.arguments(),
+            Self::Divw(i) => i.arguments(),
+            Self::Divuw(i) => i.arguments(),
+            Self::Remw(i) => i.arguments(),
+            Self::Remuw(i) => i.arguments(),
+        }
+    }
+}
+
+impl AsmInstruction for Mulw {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "mulw"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::reg(self.rs1()).unwrap(),
+            AsmArgument::reg(self.rs2()).unwrap(),
+        ]
+    }
+}
+
+impl AsmInstruction for Divw {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "divw"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::reg(self.rs1()).unwrap(),
+            AsmArgument::reg(self.rs2()).unwrap(),
+        ]
+    }
+}
+
+impl AsmInstruction for Divuw {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "divuw"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::reg(self.rs1()).unwrap(),
+            AsmArgument::reg(self.rs2()).unwrap(),
+        ]
+    }
+}
+
+impl AsmInstruction for Remw {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "remw"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::reg(self.rs1()).unwrap(),
+            AsmArgument::reg(self.rs2()).unwrap(),
+        ]
+    }
+}
+
+impl AsmInstruction for Remuw {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "remuw"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::reg(self.rs1()).unwrap(),
+            AsmArgument::reg(self.rs2()).unwrap(),
+        ]
+    }
+}
+
+#[test]
+fn word_forms() {
+    use crate::instruction::Instruction;
+    use crate::parse::parse_word_xlen;
+    use crate::{AsmFormatOptions, Xlen};
+
+    for (text, word) in [
+        ("mulw\ta0, a1, a2", 0x02c5853b),
+        ("divw\ta0, a1, a2", 0x02c5c53b),
+        ("divuw\tt0, t1, t2", 0x027352bb),
+        ("remw\ta0, a1, a2", 0x02c5e53b),
+        ("remuw\ts0, s1, a5", 0x02f4f43b),
+    ] {
+        let instruction = Instruction::decode(word, Xlen::Rv64).unwrap();
+        let options = AsmFormatOptions::default().arg_spacing(" ");
+        assert_eq!(instruction.display(options).to_string(), text);
+        assert_eq!(parse_word_xlen(text, Xlen::Rv64), Ok(word));
+
+        // The word forms only exist on RV64
+        assert!(Instruction::decode(word, Xlen::Rv32).is_none());
+        assert!(parse_word_xlen(text, Xlen::Rv32).is_err());
+    }
+}
@@ -0,0 +1,1435 @@
+//! The compressed instructions of the C extension.
+//!
+//! Every 16-bit instruction is the short form of a 32-bit one. The types here format as the
+//! `c.*` instruction and keep the expanded word, which
+//! [`AsmFormatOptions::expand_compressed`](crate::AsmFormatOptions::expand_compressed) shows
+//! instead.
+
+use crate::encode::{
+    b_type, i_type, j_type, r_type, s_type, u_type, OP, OP_32, OP_BRANCH, OP_IMM, OP_IMM_32,
+    OP_JAL, OP_JALR, OP_LOAD, OP_LOAD_FP, OP_LUI, OP_STORE, OP_STORE_FP, ZERO,
+};
+use crate::fields::{compressed, sext};
+use crate::{AsmArgument, AsmInstruction, AsmRegister, Xlen};
+
+const RA: u8 = 1;
+const SP: u8 = 2;
+
+// c.addi4spn           REG,REG,IMM
+// c.fld                FREG,IMM(REG)
+// c.lw                 REG,IMM(REG)
+// c.flw                FREG,IMM(REG)
+// c.ld                 REG,IMM(REG)
+// c.fsd                FREG,IMM(REG)
+// c.sw                 REG,IMM(REG)
+// c.fsw                FREG,IMM(REG)
+// c.sd                 REG,IMM(REG)
+// c.nop
+// c.addi               REG,IMM
+// c.jal                IMM
+// c.addiw              REG,IMM
+// c.li                 REG,IMM
+// c.addi16sp           REG,IMM
+// c.lui                REG,IMM
+// c.srli               REG,IMM
+// c.srai               REG,IMM
+// c.andi               REG,IMM
+// c.sub                REG,REG
+// c.xor                REG,REG
+// c.or                 REG,REG
+// c.and                REG,REG
+// c.subw               REG,REG
+// c.addw               REG,REG
+// c.j                  IMM
+// c.beqz               REG,IMM
+// c.bnez               REG,IMM
+// c.slli               REG,IMM
+// c.fldsp              FREG,IMM(REG)
+// c.lwsp               REG,IMM(REG)
+// c.flwsp              FREG,IMM(REG)
+// c.ldsp               REG,IMM(REG)
+// c.jr                 REG
+// c.mv                 REG,REG
+// c.ebreak
+// c.jalr               REG
+// c.add                REG,REG
+// c.fsdsp              FREG,IMM(REG)
+// c.swsp               REG,IMM(REG)
+// c.fswsp              FREG,IMM(REG)
+// c.sdsp               REG,IMM(REG)
+
+compressed!(CAddi4spn { rd, rs1, imm });
+compressed!(CFld { rd, rs1, imm });
+compressed!(CLw { rd, rs1, imm });
+compressed!(CFlw { rd, rs1, imm });
+compressed!(CLd { rd, rs1, imm });
+compressed!(CFsd { rs1, rs2, s_imm });
+compressed!(CSw { rs1, rs2, s_imm });
+compressed!(CFsw { rs1, rs2, s_imm });
+compressed!(CSd { rs1, rs2, s_imm });
+compressed!(CNop { imm });
+compressed!(CAddi { rd, imm });
+compressed!(CJal { j_imm });
+compressed!(CAddiw { rd, imm });
+compressed!(CLi { rd, imm });
+compressed!(CAddi16sp { rd, imm });
+compressed!(CLui { rd, u_imm });
+compressed!(CSrli { rd, shamt });
+compressed!(CSrai { rd, shamt });
+compressed!(CAndi { rd, imm });
+compressed!(CSub { rd, rs2 });
+compressed!(CXor { rd, rs2 });
+compressed!(COr { rd, rs2 });
+compressed!(CAnd { rd, rs2 });
+compressed!(CSubw { rd, rs2 });
+compressed!(CAddw { rd, rs2 });
+compressed!(CJ { j_imm });
+compressed!(CBeqz { rs1, b_imm });
+compressed!(CBnez { rs1, b_imm });
+compressed!(CSlli { rd, shamt });
+compressed!(CFldsp { rd, rs1, imm });
+compressed!(CLwsp { rd, rs1, imm });
+compressed!(CFlwsp { rd, rs1, imm });
+compressed!(CLdsp { rd, rs1, imm });
+compressed!(CJr { rs1 });
+compressed!(CMv { rd, rs2 });
+compressed!(CEbreak {});
+compressed!(CJalr { rs1 });
+compressed!(CAdd { rd, rs2 });
+compressed!(CFsdsp { rs1, rs2, s_imm });
+compressed!(CSwsp { rs1, rs2, s_imm });
+compressed!(CFswsp { rs1, rs2, s_imm });
+compressed!(CSdsp { rs1, rs2, s_imm });
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RvcInstruction {
+    CAddi4spn(CAddi4spn),
+    CFld(CFld),
+    CLw(CLw),
+    CFlw(CFlw),
+    CLd(CLd),
+    CFsd(CFsd),
+    CSw(CSw),
+    CFsw(CFsw),
+    CSd(CSd),
+    CNop(CNop),
+    CAddi(CAddi),
+    CJal(CJal),
+    CAddiw(CAddiw),
+    CLi(CLi),
+    CAddi16sp(CAddi16sp),
+    CLui(CLui),
+    CSrli(CSrli),
+    CSrai(CSrai),
+    CAndi(CAndi),
+    CSub(CSub),
+    CXor(CXor),
+    COr(COr),
+    CAnd(CAnd),
+    CSubw(CSubw),
+    CAddw(CAddw),
+    CJ(CJ),
+    CBeqz(CBeqz),
+    CBnez(CBnez),
+    CSlli(CSlli),
+    CFldsp(CFldsp),
+    CLwsp(CLwsp),
+    CFlwsp(CFlwsp),
+    CLdsp(CLdsp),
+    CJr(CJr),
+    CMv(CMv),
+    CEbreak(CEbreak),
+    CJalr(CJalr),
+    CAdd(CAdd),
+    CFsdsp(CFsdsp),
+    CSwsp(CSwsp),
+    CFswsp(CFswsp),
+    CSdsp(CSdsp),
+}
+
+impl RvcInstruction {
+    /// Decode `half` as a compressed instruction of a core with the register width `xlen`.
+    ///
+    /// `c.jal`, `c.flw` and their stack-pointer forms share their encodings with `c.addiw`,
+    /// `c.ld` and `c.sd` on RV64. Reserved encodings, including the all-zero illegal
+    /// instruction, are not decoded.
+    pub fn decode(half: u16, xlen: Xlen) -> Option<Self> {
+        let h = u32::from(half);
+        let bit = |n: u32| (h >> n) & 1;
+        let bits = |hi: u32, lo: u32| (h >> lo) & ((1 << (hi - lo + 1)) - 1);
+        let rv64 = xlen == Xlen::Rv64;
+
+        let rd = bits(11, 7) as u8;
+        let rs2 = bits(6, 2) as u8;
+        // rd' and rs2' of the 3-bit register fields, which select x8-x15
+        let rd_ = bits(4, 2) as u8 + 8;
+        let rs1_ = bits(9, 7) as u8 + 8;
+
+        let ci = i64::from(sext((bit(12) << 5) | bits(6, 2), 6));
+        let shamt = i64::from((bit(12) << 5) | bits(6, 2));
+        let w_offset = i64::from((bits(12, 10) << 3) | (bit(6) << 2) | (bit(5) << 6));
+        let d_offset = i64::from((bits(12, 10) << 3) | (bits(6, 5) << 6));
+        let lwsp_offset = i64::from((bit(12) << 5) | (bits(6, 4) << 2) | (bits(3, 2) << 6));
+        let ldsp_offset = i64::from((bit(12) << 5) | (bits(6, 5) << 3) | (bits(4, 2) << 6));
+        let swsp_offset = i64::from((bits(12, 9) << 2) | (bits(8, 7) << 6));
+        let sdsp_offset = i64::from((bits(12, 10) << 3) | (bits(9, 7) << 6));
+        let addi4spn =
+            i64::from((bits(12, 11) << 4) | (bits(10, 7) << 6) | (bit(6) << 2) | (bit(5) << 3));
+        let addi16sp = i64::from(sext(
+            (bit(12) << 9) | (bit(6) << 4) | (bit(5) << 6) | (bits(4, 3) << 7) | (bit(2) << 5),
+            10,
+        ));
+        let j_offset = i64::from(sext(
+            (bit(12) << 11)
+                | (bit(11) << 4)
+                | (bits(10, 9) << 8)
+                | (bit(8) << 10)
+                | (bit(7) << 6)
+                | (bit(6) << 7)
+                | (bits(5, 3) << 1)
+                | (bit(2) << 5),
+            12,
+        ));
+        let b_offset = i64::from(sext(
+            (bit(12) << 8)
+                | (bits(11, 10) << 3)
+                | (bits(6, 5) << 6)
+                | (bits(4, 3) << 1)
+                | (bit(2) << 5),
+            9,
+        ));
+
+        Some(match (h & 0b11, bits(15, 13)) {
+            (0b00, 0b000) if addi4spn != 0 => {
+                Self::CAddi4spn(CAddi4spn(i_type(OP_IMM, 0b000, rd_, SP, addi4spn), half))
+            }
+            (0b00, 0b001) => Self::CFld(CFld(i_type(OP_LOAD_FP, 0b011, rd_, rs1_, d_offset), half)),
+            (0b00, 0b010) => Self::CLw(CLw(i_type(OP_LOAD, 0b010, rd_, rs1_, w_offset), half)),
+            (0b00, 0b011) if rv64 => {
+                Self::CLd(CLd(i_type(OP_LOAD, 0b011, rd_, rs1_, d_offset), half))
+            }
+            (0b00, 0b011) => Self::CFlw(CFlw(i_type(OP_LOAD_FP, 0b010, rd_, rs1_, w_offset), half)),
+            (0b00, 0b101) => {
+                Self::CFsd(CFsd(s_type(OP_STORE_FP, 0b011, rs1_, rd_, d_offset), half))
+            }
+            (0b00, 0b110) => Self::CSw(CSw(s_type(OP_STORE, 0b010, rs1_, rd_, w_offset), half)),
+            (0b00, 0b111) if rv64 => {
+                Self::CSd(CSd(s_type(OP_STORE, 0b011, rs1_, rd_, d_offset), half))
+            }
+            (0b00, 0b111) => {
+                Self::CFsw(CFsw(s_type(OP_STORE_FP, 0b010, rs1_, rd_, w_offset), half))
+            }
+
+            (0b01, 0b000) if rd == ZERO => {
+                Self::CNop(CNop(i_type(OP_IMM, 0b000, ZERO, ZERO, ci), half))
+            }
+            (0b01, 0b000) => Self::CAddi(CAddi(i_type(OP_IMM, 0b000, rd, rd, ci), half)),
+            (0b01, 0b001) if rv64 && rd != ZERO => {
+                Self::CAddiw(CAddiw(i_type(OP_IMM_32, 0b000, rd, rd, ci), half))
+            }
+            (0b01, 0b001) if !rv64 => Self::CJal(CJal(j_type(OP_JAL, RA, j_offset), half)),
+            (0b01, 0b010) => Self::CLi(CLi(i_type(OP_IMM, 0b000, rd, ZERO, ci), half)),
+            (0b01, 0b011) if rd == SP && addi16sp != 0 => {
+                Self::CAddi16sp(CAddi16sp(i_type(OP_IMM, 0b000, SP, SP, addi16sp), half))
+            }
+            (0b01, 0b011) if rd != SP && ci != 0 => Self::CLui(CLui(u_type(OP_LUI, rd, ci), half)),
+            (0b01, 0b100) => match (bits(11, 10), bit(12), bits(6, 5)) {
+                // Shift amounts above 31 are reserved on RV32
+                (0b00 | 0b01, 1, _) if !rv64 => return None,
+                (0b00, ..) => Self::CSrli(CSrli(i_type(OP_IMM, 0b101, rs1_, rs1_, shamt), half)),
+                (0b01, ..) => Self::CSrai(CSrai(
+                    i_type(OP_IMM, 0b101, rs1_, rs1_, shamt | 0x400),
+                    half,
+                )),
+                (0b10, ..) => Self::CAndi(CAndi(i_type(OP_IMM, 0b111, rs1_, rs1_, ci), half)),
+                (_, 0, 0b00) => {
+                    Self::CSub(CSub(r_type(OP, 0b000, 0b0100000, rs1_, rs1_, rd_), half))
+                }
+                (_, 0, 0b01) => Self::CXor(CXor(r_type(OP, 0b100, 0, rs1_, rs1_, rd_), half)),
+                (_, 0, 0b10) => Self::COr(COr(r_type(OP, 0b110, 0, rs1_, rs1_, rd_), half)),
+                (_, 0, _) => Self::CAnd(CAnd(r_type(OP, 0b111, 0, rs1_, rs1_, rd_), half)),
+                (_, _, 0b00) if rv64 => Self::CSubw(CSubw(
+                    r_type(OP_32, 0b000, 0b0100000, rs1_, rs1_, rd_),
+                    half,
+                )),
+                (_, _, 0b01) if rv64 => {
+                    Self::CAddw(CAddw(r_type(OP_32, 0b000, 0, rs1_, rs1_, rd_), half))
+                }
+                _ => return None,
+            },
+            (0b01, 0b101) => Self::CJ(CJ(j_type(OP_JAL, ZERO, j_offset), half)),
+            (0b01, 0b110) => {
+                Self::CBeqz(CBeqz(b_type(OP_BRANCH, 0b000, rs1_, ZERO, b_offset), half))
+            }
+            (0b01, 0b111) => {
+                Self::CBnez(CBnez(b_type(OP_BRANCH, 0b001, rs1_, ZERO, b_offset), half))
+            }
+
+            (0b10, 0b000) if rv64 || bit(12) == 0 => {
+                Self::CSlli(CSlli(i_type(OP_IMM, 0b001, rd, rd, shamt), half))
+            }
+            (0b10, 0b001) => {
+                Self::CFldsp(CFldsp(i_type(OP_LOAD_FP, 0b011, rd, SP, ldsp_offset), half))
+            }
+            (0b10, 0b010) if rd != ZERO => {
+                Self::CLwsp(CLwsp(i_type(OP_LOAD, 0b010, rd, SP, lwsp_offset), half))
+            }
+            (0b10, 0b011) if rv64 && rd != ZERO => {
+                Self::CLdsp(CLdsp(i_type(OP_LOAD, 0b011, rd, SP, ldsp_offset), half))
+            }
+            (0b10, 0b011) if !rv64 => {
+                Self::CFlwsp(CFlwsp(i_type(OP_LOAD_FP, 0b010, rd, SP, lwsp_offset), half))
+            }
+            (0b10, 0b100) => match (bit(12), rd, rs2) {
+                (0, ZERO, ZERO) => return None,
+                (0, _, ZERO) => Self::CJr(CJr(i_type(OP_JALR, 0b000, ZERO, rd, 0), half)),
+                (0, _, _) => Self::CMv(CMv(r_type(OP, 0b000, 0, rd, ZERO, rs2), half)),
+                (_, ZERO, ZERO) => Self::CEbreak(CEbreak(0x0010_0073, half)),
+                (_, _, ZERO) => Self::CJalr(CJalr(i_type(OP_JALR, 0b000, RA, rd, 0), half)),
+                _ => Self::CAdd(CAdd(r_type(OP, 0b000, 0, rd, rd, rs2), half)),
+            },
+            (0b10, 0b101) => Self::CFsdsp(CFsdsp(
+                s_type(OP_STORE_FP, 0b011, SP, rs2, sdsp_offset),
+                half,
+            )),
+            (0b10, 0b110) => {
+                Self::CSwsp(CSwsp(s_type(OP_STORE, 0b010, SP, rs2, swsp_offset), half))
+            }
+            (0b10, 0b111) if rv64 => {
+                Self::CSdsp(CSdsp(s_type(OP_STORE, 0b011, SP, rs2, sdsp_offset), half))
+            }
+            (0b10, 0b111) => Self::CFswsp(CFswsp(
+                s_type(OP_STORE_FP, 0b010, SP, rs2, swsp_offset),
+                half,
+            )),
+            _ => return None,
+        })
+    }
+}
+
+impl AsmInstruction for RvcInstruction {
+    fn verb(&self) -> &'static str {
+        match self {
+            Self::CAddi4spn(i) => i.verb(),
+            Self::CFld(i) => i.verb(),
+            Self::CLw(i) => i.verb(),
+            Self::CFlw(i) => i.verb(),
+            Self::CLd(i) => i.verb(),
+            Self::CFsd(i) => i.verb(),
+            Self::CSw(i) => i.verb(),
+            Self::CFsw(i) => i.verb(),
+            Self::CSd(i) => i.verb(),
+            Self::CNop(i) => i.verb(),
+            Self::CAddi(i) => i.verb(),
+            Self::CJal(i) => i.verb(),
+            Self::CAddiw(i) => i.verb(),
+            Self::CLi(i) => i.verb(),
+            Self::CAddi16sp(i) => i.verb(),
+            Self::CLui(i) => i.verb(),
+            Self::CSrli(i) => i.verb(),
+            Self::CSrai(i) => i.verb(),
+            Self::CAndi(i) => i.verb(),
+            Self::CSub(i) => i.verb(),
+            Self::CXor(i) => i.verb(),
+            Self::COr(i) => i.verb(),
+            Self::CAnd(i) => i.verb(),
+            Self::CSubw(i) => i.verb(),
+            Self::CAddw(i) => i.verb(),
+            Self::CJ(i) => i.verb(),
+            Self::CBeqz(i) => i.verb(),
+            Self::CBnez(i) => i.verb(),
+            Self::CSlli(i) => i.verb(),
+            Self::CFldsp(i) => i.verb(),
+            Self::CLwsp(i) => i.verb(),
+            Self::CFlwsp(i) => i.verb(),
+            Self::CLdsp(i) => i.verb(),
+            Self::CJr(i) => i.verb(),
+            Self::CMv(i) => i.verb(),
+            Self::CEbreak(i) => i.verb(),
+            Self::CJalr(i) => i.verb(),
+            Self::CAdd(i) => i.verb(),
+            Self::CFsdsp(i) => i.verb(),
+            Self::CSwsp(i) => i.verb(),
+            Self::CFswsp(i) => i.verb(),
+            Self::CSdsp(i) => i.verb(),
+        }
+    }
+
+    fn arguments(&self) -> Vec<AsmArgument> {
+        match self {
+            Self::CAddi4spn(i) => i.arguments(),
+            Self::CFld(i) => i.arguments(),
+            Self::CLw(i) => i.arguments(),
+            Self::CFlw(i) => i.arguments(),
+            Self::CLd(i) => i.arguments(),
+            Self::CFsd(i) => i.arguments(),
+            Self::CSw(i) => i.arguments(),
+            Self::CFsw(i) => i.arguments(),
+            Self::CSd(i) => i.arguments(),
+            Self::CNop(i) => i.arguments(),
+            Self::CAddi(i) => i.arguments(),
+            Self::CJal(i) => i.arguments(),
+            Self::CAddiw(i) => i.arguments(),
+            Self::CLi(i) => i.arguments(),
+            Self::CAddi16sp(i) => i.arguments(),
+            Self::CLui(i) => i.arguments(),
+            Self::CSrli(i) => i.arguments(),
+            Self::CSrai(i) => i.arguments(),
+            Self::CAndi(i) => i.arguments(),
+            Self::CSub(i) => i.arguments(),
+            Self::CXor(i) => i.arguments(),
+            Self::COr(i) => i.arguments(),
+            Self::CAnd(i) => i.arguments(),
+            Self::CSubw(i) => i.arguments(),
+            Self::CAddw(i) => i.arguments(),
+            Self::CJ(i) => i.arguments(),
+            Self::CBeqz(i) => i.arguments(),
+            Self::CBnez(i) => i.arguments(),
+            Self::CSlli(i) => i.arguments(),
+            Self::CFldsp(i) => i.arguments(),
+            Self::CLwsp(i) => i.arguments(),
+            Self::CFlwsp(i) => i.arguments(),
+            Self::CLdsp(i) => i.arguments(),
+            Self::CJr(i) => i.arguments(),
+            Self::CMv(i) => i.arguments(),
+            Self::CEbreak(i) => i.arguments(),
+            Self::CJalr(i) => i.arguments(),
+            Self::CAdd(i) => i.arguments(),
+            Self::CFsdsp(i) => i.arguments(),
+            Self::CSwsp(i) => i.arguments(),
+            Self::CFswsp(i) => i.arguments(),
+            Self::CSdsp(i) => i.arguments(),
+        }
+    }
+
+    fn is_pc_relative(&self) -> bool {
+        match self {
+            Self::CAddi4spn(i) => i.is_pc_relative(),
+            Self::CFld(i) => i.is_pc_relative(),
+            Self::CLw(i) => i.is_pc_relative(),
+            Self::CFlw(i) => i.is_pc_relative(),
+            Self::CLd(i) => i.is_pc_relative(),
+            Self::CFsd(i) => i.is_pc_relative(),
+            Self::CSw(i) => i.is_pc_relative(),
+            Self::CFsw(i) => i.is_pc_relative(),
+            Self::CSd(i) => i.is_pc_relative(),
+            Self::CNop(i) => i.is_pc_relative(),
+            Self::CAddi(i) => i.is_pc_relative(),
+            Self::CJal(i) => i.is_pc_relative(),
+            Self::CAddiw(i) => i.is_pc_relative(),
+            Self::CLi(i) => i.is_pc_relative(),
+            Self::CAddi16sp(i) => i.is_pc_relative(),
+            Self::CLui(i) => i.is_pc_relative(),
+            Self::CSrli(i) => i.is_pc_relative(),
+            Self::CSrai(i) => i.is_pc_relative(),
+            Self::CAndi(i) => i.is_pc_relative(),
+            Self::CSub(i) => i.is_pc_relative(),
+            Self::CXor(i) => i.is_pc_relative(),
+            Self::COr(i) => i.is_pc_relative(),
+            Self::CAnd(i) => i.is_pc_relative(),
+            Self::CSubw(i) => i.is_pc_relative(),
+            Self::CAddw(i) => i.is_pc_relative(),
+            Self::CJ(i) => i.is_pc_relative(),
+            Self::CBeqz(i) => i.is_pc_relative(),
+            Self::CBnez(i) => i.is_pc_relative(),
+            Self::CSlli(i) => i.is_pc_relative(),
+            Self::CFldsp(i) => i.is_pc_relative(),
+            Self::CLwsp(i) => i.is_pc_relative(),
+            Self::CFlwsp(i) => i.is_pc_relative(),
+            Self::CLdsp(i) => i.is_pc_relative(),
+            Self::CJr(i) => i.is_pc_relative(),
+            Self::CMv(i) => i.is_pc_relative(),
+            Self::CEbreak(i) => i.is_pc_relative(),
+            Self::CJalr(i) => i.is_pc_relative(),
+            Self::CAdd(i) => i.is_pc_relative(),
+            Self::CFsdsp(i) => i.is_pc_relative(),
+            Self::CSwsp(i) => i.is_pc_relative(),
+            Self::CFswsp(i) => i.is_pc_relative(),
+            Self::CSdsp(i) => i.is_pc_relative(),
+        }
+    }
+
+    fn expanded(&self) -> Option<u32> {
+        match self {
+            Self::CAddi4spn(i) => i.expanded(),
+            Self::CFld(i) => i.expanded(),
+            Self::CLw(i) => i.expanded(),
+            Self::CFlw(i) => i.expanded(),
+            Self::CLd(i) => i.expanded(),
+            Self::CFsd(i) => i.expanded(),
+            Self::CSw(i) => i.expanded(),
+            Self::CFsw(i) => i.expanded(),
+            Self::CSd(i) => i.expanded(),
+            Self::CNop(i) => i.expanded(),
+            Self::CAddi(i) => i.expanded(),
+            Self::CJal(i) => i.expanded(),
+            Self::CAddiw(i) => i.expanded(),
+            Self::CLi(i) => i.expanded(),
+            Self::CAddi16sp(i) => i.expanded(),
+            Self::CLui(i) => i.expanded(),
+            Self::CSrli(i) => i.expanded(),
+            Self::CSrai(i) => i.expanded(),
+            Self::CAndi(i) => i.expanded(),
+            Self::CSub(i) => i.expanded(),
+            Self::CXor(i) => i.expanded(),
+            Self::COr(i) => i.expanded(),
+            Self::CAnd(i) => i.expanded(),
+            Self::CSubw(i) => i.expanded(),
+            Self::CAddw(i) => i.expanded(),
+            Self::CJ(i) => i.expanded(),
+            Self::CBeqz(i) => i.expanded(),
+            Self::CBnez(i) => i.expanded(),
+            Self::CSlli(i) => i.expanded(),
+            Self::CFldsp(i) => i.expanded(),
+            Self::CLwsp(i) => i.expanded(),
+            Self::CFlwsp(i) => i.expanded(),
+            Self::CLdsp(i) => i.expanded(),
+            Self::CJr(i) => i.expanded(),
+            Self::CMv(i) => i.expanded(),
+            Self::CEbreak(i) => i.expanded(),
+            Self::CJalr(i) => i.expanded(),
+            Self::CAdd(i) => i.expanded(),
+            Self::CFsdsp(i) => i.expanded(),
+            Self::CSwsp(i) => i.expanded(),
+            Self::CFswsp(i) => i.expanded(),
+            Self::CSdsp(i) => i.expanded(),
+        }
+    }
+}
+
+impl AsmInstruction for CAddi4spn {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "c.addi4spn"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::reg(self.rs1()).unwrap(),
+            AsmArgument::Immediate(i64::from(self.imm())),
+        ]
+    }
+
+    #[inline]
+    fn expanded(&self) -> Option<u32> {
+        Some(self.0)
+    }
+}
+
+impl AsmInstruction for CFld {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "c.fld"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::freg(self.rd()).unwrap(),
+            AsmArgument::OffsetImmediate(
+                i64::from(self.imm()),
+                AsmRegister::try_from(self.rs1()).unwrap(),
+            ),
+        ]
+    }
+
+    #[inline]
+    fn expanded(&self) -> Option<u32> {
+        Some(self.0)
+    }
+}
+
+impl AsmInstruction for CLw {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "c.lw"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::OffsetImmediate(
+                i64::from(self.imm()),
+                AsmRegister::try_from(self.rs1()).unwrap(),
+            ),
+        ]
+    }
+
+    #[inline]
+    fn expanded(&self) -> Option<u32> {
+        Some(self.0)
+    }
+}
+
+impl AsmInstruction for CFlw {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "c.flw"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::freg(self.rd()).unwrap(),
+            AsmArgument::OffsetImmediate(
+                i64::from(self.imm()),
+                AsmRegister::try_from(self.rs1()).unwrap(),
+            ),
+        ]
+    }
+
+    #[inline]
+    fn expanded(&self) -> Option<u32> {
+        Some(self.0)
+    }
+}
+
+impl AsmInstruction for CLd {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "c.ld"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::OffsetImmediate(
+                i64::from(self.imm()),
+                AsmRegister::try_from(self.rs1()).unwrap(),
+            ),
+        ]
+    }
+
+    #[inline]
+    fn expanded(&self) -> Option<u32> {
+        Some(self.0)
+    }
+}
+
+impl AsmInstruction for CFsd {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "c.fsd"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::freg(self.rs2()).unwrap(),
+            AsmArgument::OffsetImmediate(
+                i64::from(self.imm()),
+                AsmRegister::try_from(self.rs1()).unwrap(),
+            ),
+        ]
+    }
+
+    #[inline]
+    fn expanded(&self) -> Option<u32> {
+        Some(self.0)
+    }
+}
+
+impl AsmInstruction for CSw {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "c.sw"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rs2()).unwrap(),
+            AsmArgument::OffsetImmediate(
+                i64::from(self.imm()),
+                AsmRegister::try_from(self.rs1()).unwrap(),
+            ),
+        ]
+    }
+
+    #[inline]
+    fn expanded(&self) -> Option<u32> {
+        Some(self.0)
+    }
+}
+
+impl AsmInstruction for CFsw {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "c.fsw"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::freg(self.rs2()).unwrap(),
+            AsmArgument::OffsetImmediate(
+                i64::from(self.imm()),
+                AsmRegister::try_from(self.rs1()).unwrap(),
+            ),
+        ]
+    }
+
+    #[inline]
+    fn expanded(&self) -> Option<u32> {
+        Some(self.0)
+    }
+}
+
+impl AsmInstruction for CSd {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "c.sd"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rs2()).unwrap(),
+            AsmArgument::OffsetImmediate(
+                i64::from(self.imm()),
+                AsmRegister::try_from(self.rs1()).unwrap(),
+            ),
+        ]
+    }
+
+    #[inline]
+    fn expanded(&self) -> Option<u32> {
+        Some(self.0)
+    }
+}
+
+impl AsmInstruction for CNop {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "c.nop"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        match self.imm() {
+            0 => vec![],
+            imm => vec![AsmArgument::Immediate(i64::from(imm))],
+        }
+    }
+
+    #[inline]
+    fn expanded(&self) -> Option<u32> {
+        Some(self.0)
+    }
+}
+
+impl AsmInstruction for CAddi {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "c.addi"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::Immediate(i64::from(self.imm())),
+        ]
+    }
+
+    #[inline]
+    fn expanded(&self) -> Option<u32> {
+        Some(self.0)
+    }
+}
+
+impl AsmInstruction for CJal {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "c.jal"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![AsmArgument::Immediate(i64::from(self.imm()))]
+    }
+
+    #[inline]
+    fn is_pc_relative(&self) -> bool {
+        true
+    }
+
+    #[inline]
+    fn expanded(&self) -> Option<u32> {
+        Some(self.0)
+    }
+}
+
+impl AsmInstruction for CAddiw {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "c.addiw"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::Immediate(i64::from(self.imm())),
+        ]
+    }
+
+    #[inline]
+    fn expanded(&self) -> Option<u32> {
+        Some(self.0)
+    }
+}
+
+impl AsmInstruction for CLi {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "c.li"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::Immediate(i64::from(self.imm())),
+        ]
+    }
+
+    #[inline]
+    fn expanded(&self) -> Option<u32> {
+        Some(self.0)
+    }
+}
+
+impl AsmInstruction for CAddi16sp {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "c.addi16sp"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::Immediate(i64::from(self.imm())),
+        ]
+    }
+
+    #[inline]
+    fn expanded(&self) -> Option<u32> {
+        Some(self.0)
+    }
+}
+
+impl AsmInstruction for CLui {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "c.lui"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::Immediate(i64::from(self.imm())),
+        ]
+    }
+
+    #[inline]
+    fn expanded(&self) -> Option<u32> {
+        Some(self.0)
+    }
+}
+
+impl AsmInstruction for CSrli {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "c.srli"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::Immediate(i64::from(self.shamt())),
+        ]
+    }
+
+    #[inline]
+    fn expanded(&self) -> Option<u32> {
+        Some(self.0)
+    }
+}
+
+impl AsmInstruction for CSrai {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "c.srai"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::Immediate(i64::from(self.shamt())),
+        ]
+    }
+
+    #[inline]
+    fn expanded(&self) -> Option<u32> {
+        Some(self.0)
+    }
+}
+
+impl AsmInstruction for CAndi {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "c.andi"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::Immediate(i64::from(self.imm())),
+        ]
+    }
+
+    #[inline]
+    fn expanded(&self) -> Option<u32> {
+        Some(self.0)
+    }
+}
+
+impl AsmInstruction for CSub {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "c.sub"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::reg(self.rs2()).unwrap(),
+        ]
+    }
+
+    #[inline]
+    fn expanded(&self) -> Option<u32> {
+        Some(self.0)
+    }
+}
+
+impl AsmInstruction for CXor {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "c.xor"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::reg(self.rs2()).unwrap(),
+        ]
+    }
+
+    #[inline]
+    fn expanded(&self) -> Option<u32> {
+        Some(self.0)
+    }
+}
+
+impl AsmInstruction for COr {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "c.or"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::reg(self.rs2()).unwrap(),
+        ]
+    }
+
+    #[inline]
+    fn expanded(&self) -> Option<u32> {
+        Some(self.0)
+    }
+}
+
+impl AsmInstruction for CAnd {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "c.and"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::reg(self.rs2()).unwrap(),
+        ]
+    }
+
+    #[inline]
+    fn expanded(&self) -> Option<u32> {
+        Some(self.0)
+    }
+}
+
+impl AsmInstruction for CSubw {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "c.subw"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::reg(self.rs2()).unwrap(),
+        ]
+    }
+
+    #[inline]
+    fn expanded(&self) -> Option<u32> {
+        Some(self.0)
+    }
+}
+
+impl AsmInstruction for CAddw {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "c.addw"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::reg(self.rs2()).unwrap(),
+        ]
+    }
+
+    #[inline]
+    fn expanded(&self) -> Option<u32> {
+        Some(self.0)
+    }
+}
+
+impl AsmInstruction for CJ {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "c.j"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![AsmArgument::Immediate(i64::from(self.imm()))]
+    }
+
+    #[inline]
+    fn is_pc_relative(&self) -> bool {
+        true
+    }
+
+    #[inline]
+    fn expanded(&self) -> Option<u32> {
+        Some(self.0)
+    }
+}
+
+impl AsmInstruction for CBeqz {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "c.beqz"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rs1()).unwrap(),
+            AsmArgument::Immediate(i64::from(self.imm())),
+        ]
+    }
+
+    #[inline]
+    fn is_pc_relative(&self) -> bool {
+        true
+    }
+
+    #[inline]
+    fn expanded(&self) -> Option<u32> {
+        Some(self.0)
+    }
+}
+
+impl AsmInstruction for CBnez {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "c.bnez"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rs1()).unwrap(),
+            AsmArgument::Immediate(i64::from(self.imm())),
+        ]
+    }
+
+    #[inline]
+    fn is_pc_relative(&self) -> bool {
+        true
+    }
+
+    #[inline]
+    fn expanded(&self) -> Option<u32> {
+        Some(self.0)
+    }
+}
+
+impl AsmInstruction for CSlli {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "c.slli"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::Immediate(i64::from(self.shamt())),
+        ]
+    }
+
+    #[inline]
+    fn expanded(&self) -> Option<u32> {
+        Some(self.0)
+    }
+}
+
+impl AsmInstruction for CFldsp {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "c.fldsp"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::freg(self.rd()).unwrap(),
+            AsmArgument::OffsetImmediate(
+                i64::from(self.imm()),
+                AsmRegister::try_from(self.rs1()).unwrap(),
+            ),
+        ]
+    }
+
+    #[inline]
+    fn expanded(&self) -> Option<u32> {
+        Some(self.0)
+    }
+}
+
+impl AsmInstruction for CLwsp {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "c.lwsp"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::OffsetImmediate(
+                i64::from(self.imm()),
+                AsmRegister::try_from(self.rs1()).unwrap(),
+            ),
+        ]
+    }
+
+    #[inline]
+    fn expanded(&self) -> Option<u32> {
+        Some(self.0)
+    }
+}
+
+impl AsmInstruction for CFlwsp {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "c.flwsp"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::freg(self.rd()).unwrap(),
+            AsmArgument::OffsetImmediate(
+                i64::from(self.imm()),
+                AsmRegister::try_from(self.rs1()).unwrap(),
+            ),
+        ]
+    }
+
+    #[inline]
+    fn expanded(&self) -> Option<u32> {
+        Some(self.0)
+    }
+}
+
+impl AsmInstruction for CLdsp {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "c.ldsp"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::OffsetImmediate(
+                i64::from(self.imm()),
+                AsmRegister::try_from(self.rs1()).unwrap(),
+            ),
+        ]
+    }
+
+    #[inline]
+    fn expanded(&self) -> Option<u32> {
+        Some(self.0)
+    }
+}
+
+impl AsmInstruction for CJr {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "c.jr"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![AsmArgument::reg(self.rs1()).unwrap()]
+    }
+
+    #[inline]
+    fn expanded(&self) -> Option<u32> {
+        Some(self.0)
+    }
+}
+
+impl AsmInstruction for CMv {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "c.mv"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::reg(self.rs2()).unwrap(),
+        ]
+    }
+
+    #[inline]
+    fn expanded(&self) -> Option<u32> {
+        Some(self.0)
+    }
+}
+
+impl AsmInstruction for CEbreak {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "c.ebreak"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![]
+    }
+
+    #[inline]
+    fn expanded(&self) -> Option<u32> {
+        Some(self.0)
+    }
+}
+
+impl AsmInstruction for CJalr {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "c.jalr"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![AsmArgument::reg(self.rs1()).unwrap()]
+    }
+
+    #[inline]
+    fn expanded(&self) -> Option<u32> {
+        Some(self.0)
+    }
+}
+
+impl AsmInstruction for CAdd {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "c.add"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::reg(self.rs2()).unwrap(),
+        ]
+    }
+
+    #[inline]
+    fn expanded(&self) -> Option<u32> {
+        Some(self.0)
+    }
+}
+
+impl AsmInstruction for CFsdsp {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "c.fsdsp"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::freg(self.rs2()).unwrap(),
+            AsmArgument::OffsetImmediate(
+                i64::from(self.imm()),
+                AsmRegister::try_from(self.rs1()).unwrap(),
+            ),
+        ]
+    }
+
+    #[inline]
+    fn expanded(&self) -> Option<u32> {
+        Some(self.0)
+    }
+}
+
+impl AsmInstruction for CSwsp {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "c.swsp"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rs2()).unwrap(),
+            AsmArgument::OffsetImmediate(
+                i64::from(self.imm()),
+                AsmRegister::try_from(self.rs1()).unwrap(),
+            ),
+        ]
+    }
+
+    #[inline]
+    fn expanded(&self) -> Option<u32> {
+        Some(self.0)
+    }
+}
+
+impl AsmInstruction for CFswsp {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "c.fswsp"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::freg(self.rs2()).unwrap(),
+            AsmArgument::OffsetImmediate(
+                i64::from(self.imm()),
+                AsmRegister::try_from(self.rs1()).unwrap(),
+            ),
+        ]
+    }
+
+    #[inline]
+    fn expanded(&self) -> Option<u32> {
+        Some(self.0)
+    }
+}
+
+impl AsmInstruction for CSdsp {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "c.sdsp"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rs2()).unwrap(),
+            AsmArgument::OffsetImmediate(
+                i64::from(self.imm()),
+                AsmRegister::try_from(self.rs1()).unwrap(),
+            ),
+        ]
+    }
+
+    #[inline]
+    fn expanded(&self) -> Option<u32> {
+        Some(self.0)
+    }
+}
+
+#[test]
+fn expand() {
+    use crate::AsmFormatOptions;
+
+    let options = AsmFormatOptions::default().arg_spacing(" ");
+    let display = |half, xlen, expand| {
+        RvcInstruction::decode(half, xlen).map(|instruction| {
+            instruction
+                .display(options.clone().xlen(xlen).expand_compressed(expand))
+                .to_string()
+        })
+    };
+
+    assert_eq!(
+        display(0x0048, Xlen::Rv32, false).as_deref(),
+        Some("c.addi4spn\ta0, sp, 0x4")
+    );
+    assert_eq!(
+        display(0x0048, Xlen::Rv32, true).as_deref(),
+        Some("addi\ta0, sp, 0x4")
+    );
+    assert_eq!(
+        display(0xbfa6, Xlen::Rv32, true).as_deref(),
+        Some("fsd\tfs1, 504(sp)")
+    );
+    // c.jal on RV32 is c.addiw on RV64
+    assert_eq!(
+        display(0x357d, Xlen::Rv32, false).as_deref(),
+        Some("c.jal\t0xfffffffffffffeae")
+    );
+    assert_eq!(
+        display(0x357d, Xlen::Rv64, false).as_deref(),
+        Some("c.addiw\ta0, 0xffffffffffffffff")
+    );
+    assert_eq!(display(0x0000, Xlen::Rv32, false), None);
+    // Shift amounts above 31 are reserved on RV32
+    assert_eq!(display(0x1502, Xlen::Rv32, false), None);
+    assert_eq!(
+        display(0x1502, Xlen::Rv64, false).as_deref(),
+        Some("c.slli\ta0, 0x20")
+    );
+}
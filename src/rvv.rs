@@ -0,0 +1,1512 @@
+//! The vector instructions of the V extension, RVV 1.0.
+//!
+//! There are several hundred of them, so rather than a type per instruction they are grouped by
+//! encoding: the `vsetvl` family, the loads, the stores and the arithmetic instructions, whose
+//! verbs and operands are looked up in tables.
+
+use crate::encode::{OP_LOAD_FP, OP_STORE_FP, OP_V};
+use crate::fields::{instruction, sext};
+use crate::{AsmArgument, AsmInstruction};
+
+// vsetvli              REG,REG,VTYPE
+// vsetivli             REG,IMM,VTYPE
+// vsetvl               REG,REG,REG
+// vle<eew>.v           VREG,(REG)[,v0.t]
+// vlse<eew>.v          VREG,(REG),REG[,v0.t]
+// vluxei<eew>.v        VREG,(REG),VREG[,v0.t]
+// vloxei<eew>.v        VREG,(REG),VREG[,v0.t]
+// vl<nf>re<eew>.v      VREG,(REG)
+// vl<nf>r.v            VREG,(REG)
+// vlm.v                VREG,(REG)
+// vse<eew>.v           VREG,(REG)[,v0.t]
+// vsse<eew>.v          VREG,(REG),REG[,v0.t]
+// vsuxei<eew>.v        VREG,(REG),VREG[,v0.t]
+// vsoxei<eew>.v        VREG,(REG),VREG[,v0.t]
+// vs<nf>r.v            VREG,(REG)
+// vsm.v                VREG,(REG)
+// <op>.vv              VREG,VREG,VREG[,v0.t]
+// <op>.vx              VREG,VREG,REG[,v0.t]
+// <op>.vi              VREG,VREG,IMM[,v0.t]
+// <op>.vf              VREG,VREG,FREG[,v0.t]
+// <op>.v?m             VREG,VREG,(VREG|REG|IMM|FREG),v0
+
+instruction!(Vsetvli { rd, rs1 });
+instruction!(Vsetivli { rd, uimm });
+instruction!(Vsetvl { rd, rs1, rs2 });
+instruction!(VectorLoad {
+    vd,
+    rs1,
+    rs2,
+    vs2,
+    vm
+});
+instruction!(VectorStore {
+    vd,
+    rs1,
+    rs2,
+    vs2,
+    vm
+});
+instruction!(VectorArithmetic { vd, vs1, vs2, vm });
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RvvInstruction {
+    Vsetvli(Vsetvli),
+    Vsetivli(Vsetivli),
+    Vsetvl(Vsetvl),
+    VectorLoad(VectorLoad),
+    VectorStore(VectorStore),
+    VectorArithmetic(VectorArithmetic),
+}
+
+impl TryFrom<u32> for RvvInstruction {
+    type Error = ();
+
+    fn try_from(word: u32) -> Result<Self, Self::Error> {
+        let funct3 = (word >> 12) & 0b111;
+
+        match word & 0x7f {
+            OP_V if funct3 == OPCFG => match word >> 30 {
+                0b00 | 0b01 => Ok(Self::Vsetvli(Vsetvli(word))),
+                0b11 => Ok(Self::Vsetivli(Vsetivli(word))),
+                _ if (word >> 25) & 0x3f == 0 => Ok(Self::Vsetvl(Vsetvl(word))),
+                _ => Err(()),
+            },
+            OP_V => operation(word)
+                .map(|_| Self::VectorArithmetic(VectorArithmetic(word)))
+                .ok_or(()),
+            OP_LOAD_FP => load_verb(word)
+                .map(|_| Self::VectorLoad(VectorLoad(word)))
+                .ok_or(()),
+            OP_STORE_FP => store_verb(word)
+                .map(|_| Self::VectorStore(VectorStore(word)))
+                .ok_or(()),
+            _ => Err(()),
+        }
+    }
+}
+
+impl AsmInstruction for RvvInstruction {
+    fn verb(&self) -> &'static str {
+        match self {
+            Self::Vsetvli(i) => i.verb(),
+            Self::Vsetivli(i) => i.verb(),
+            Self::Vsetvl(i) => i.verb(),
+            Self::VectorLoad(i) => i.verb(),
+            Self::VectorStore(i) => i.verb(),
+            Self::VectorArithmetic(i) => i.verb(),
+        }
+    }
+
+    fn arguments(&self) -> Vec<AsmArgument> {
+        match self {
+            Self::Vsetvli(i) => i.arguments(),
+            Self::Vsetivli(i) => i.arguments(),
+            Self::Vsetvl(i) => i.arguments(),
+            Self::VectorLoad(i) => i.arguments(),
+            Self::VectorStore(i) => i.arguments(),
+            Self::VectorArithmetic(i) => i.arguments(),
+        }
+    }
+}
+
+// `funct3` of the OP-V major opcode, selecting the kind of operands
+const OPIVV: u32 = 0b000;
+const OPFVV: u32 = 0b001;
+const OPMVV: u32 = 0b010;
+const OPIVI: u32 = 0b011;
+const OPIVX: u32 = 0b100;
+const OPFVF: u32 = 0b101;
+const OPMVX: u32 = 0b110;
+const OPCFG: u32 = 0b111;
+
+// `mop` of the loads and stores
+const UNIT_STRIDE: u32 = 0b00;
+const INDEXED_UNORDERED: u32 = 0b01;
+const STRIDED: u32 = 0b10;
+const INDEXED_ORDERED: u32 = 0b11;
+
+/// Operands of an arithmetic instruction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Form {
+    /// `vd, vs2, vs1`
+    Vv,
+    /// `vd, vs2, rs1`
+    Vx,
+    /// `vd, vs2, fs1`
+    Vf,
+    /// `vd, vs2, simm5`
+    Vi,
+    /// `vd, vs2, uimm5`, the shifts, slides and `vrgather.vi`
+    Vu,
+    /// `vd, vs1, vs2` of the multiply-adds
+    MaccV,
+    /// `vd, rs1, vs2`
+    MaccX,
+    /// `vd, fs1, vs2`
+    MaccF,
+    /// `vd, vs2`, with the operation in the `vs1` field
+    Unary(u32),
+    /// `vd`, with the operation in the `vs1` field and `vs2` zero
+    Nullary(u32),
+    /// `rd, vs2`, with the operation in the `vs1` field
+    ToX(u32),
+    /// `fd, vs2`, with the operation in the `vs1` field
+    ToF(u32),
+    /// `vd, vs1` with `vs2` zero
+    MoveV,
+    /// `vd, rs1` with `vs2` zero
+    MoveX,
+    /// `vd, fs1` with `vs2` zero
+    MoveF,
+    /// `vd, simm5` with `vs2` zero
+    MoveI,
+    /// `vd, vs2` of `vmv<n>r.v`, with `n - 1` in the `vs1` field and both registers multiples of `n`
+    MoveWhole(u32),
+    /// `vd, vs2` of a mask instruction whose two sources are the same register
+    SameV,
+    /// `vd` of a mask instruction whose sources are both `vd`
+    SameVd,
+}
+
+/// How an arithmetic instruction uses the `vm` bit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Mask {
+    /// Masked by `v0.t` when `vm` is clear.
+    Masked,
+    /// Only encoded with `vm` set.
+    Unmasked,
+    /// Only encoded with `vm` clear, taking `v0` as the carry-in or merge mask.
+    CarryIn,
+}
+
+/// The verb, operands and masking of the arithmetic instruction `word`.
+fn operation(word: u32) -> Option<(&'static str, Form, Mask)> {
+    let funct3 = (word >> 12) & 0b111;
+    let funct6 = word >> 26;
+    let vm = (word >> 25) & 1 != 0;
+    let vd = (word >> 7) & 0x1f;
+    let vs1 = (word >> 15) & 0x1f;
+    let vs2 = (word >> 20) & 0x1f;
+
+    ARITHMETIC
+        .iter()
+        .find(|&&(_, f3, f6, form, mask)| {
+            let masking = match mask {
+                Mask::Masked => true,
+                Mask::Unmasked => vm,
+                Mask::CarryIn => !vm,
+            };
+            let fields = match form {
+                Form::Unary(code) | Form::ToX(code) | Form::ToF(code) => vs1 == code,
+                Form::Nullary(code) => vs1 == code && vs2 == 0,
+                Form::MoveV | Form::MoveX | Form::MoveF | Form::MoveI => vs2 == 0,
+                Form::MoveWhole(n) => vs1 == n - 1 && vd.is_multiple_of(n) && vs2.is_multiple_of(n),
+                Form::SameV => vs1 == vs2,
+                Form::SameVd => vs1 == vd && vs2 == vd,
+                _ => true,
+            };
+            f3 == funct3 && f6 == funct6 && masking && fields
+        })
+        .map(|&(verb, _, _, form, mask)| (verb, form, mask))
+}
+
+/// Index of the element width in the `width` field of a load or store into the tables below, or
+/// `None` for the scalar floating-point widths.
+#[inline]
+fn element_width(word: u32) -> Option<usize> {
+    match (word >> 12) & 0b111 {
+        0b000 => Some(0),
+        0b101 => Some(1),
+        0b110 => Some(2),
+        0b111 => Some(3),
+        _ => None,
+    }
+}
+
+/// Index of the register count of a whole-register load or store, whose `nf` field holds one less
+/// than 1, 2, 4 or 8. The data register must be a multiple of the count.
+#[inline]
+fn whole_registers(word: u32) -> Option<usize> {
+    let count = (word >> 29) + 1;
+    let vd = (word >> 7) & 0x1f;
+    (count.is_power_of_two() && vd.is_multiple_of(count)).then_some(count.trailing_zeros() as usize)
+}
+
+fn load_verb(word: u32) -> Option<&'static str> {
+    let eew = element_width(word)?;
+    let nf = (word >> 29) as usize;
+    let mew = (word >> 28) & 1;
+    let mop = (word >> 26) & 0b11;
+    let vm = (word >> 25) & 1 != 0;
+    let lumop = (word >> 20) & 0x1f;
+
+    if mew != 0 {
+        return None;
+    }
+
+    Some(match (mop, lumop) {
+        (UNIT_STRIDE, 0b00000) => UNIT_LOADS[eew][nf],
+        (UNIT_STRIDE, 0b01000) if vm => WHOLE_REGISTER_LOADS[eew][whole_registers(word)?],
+        (UNIT_STRIDE, 0b01011) if vm && eew == 0 && nf == 0 => "vlm.v",
+        (UNIT_STRIDE, 0b10000) => FAULT_ONLY_FIRST_LOADS[eew][nf],
+        (UNIT_STRIDE, _) => return None,
+        (INDEXED_UNORDERED, _) => UNORDERED_LOADS[eew][nf],
+        (STRIDED, _) => STRIDED_LOADS[eew][nf],
+        (INDEXED_ORDERED, _) => ORDERED_LOADS[eew][nf],
+        _ => unreachable!(),
+    })
+}
+
+fn store_verb(word: u32) -> Option<&'static str> {
+    let eew = element_width(word)?;
+    let nf = (word >> 29) as usize;
+    let mew = (word >> 28) & 1;
+    let mop = (word >> 26) & 0b11;
+    let vm = (word >> 25) & 1 != 0;
+    let sumop = (word >> 20) & 0x1f;
+
+    if mew != 0 {
+        return None;
+    }
+
+    Some(match (mop, sumop) {
+        (UNIT_STRIDE, 0b00000) => UNIT_STORES[eew][nf],
+        (UNIT_STRIDE, 0b01000) if vm && eew == 0 => WHOLE_REGISTER_STORES[whole_registers(word)?],
+        (UNIT_STRIDE, 0b01011) if vm && eew == 0 && nf == 0 => "vsm.v",
+        (UNIT_STRIDE, _) => return None,
+        (INDEXED_UNORDERED, _) => UNORDERED_STORES[eew][nf],
+        (STRIDED, _) => STRIDED_STORES[eew][nf],
+        (INDEXED_ORDERED, _) => ORDERED_STORES[eew][nf],
+        _ => unreachable!(),
+    })
+}
+
+/// `v0.t`, the last operand of instructions masked by `v0`, which clear `vm`.
+#[inline]
+fn mask_operand<'a>(vm: bool) -> Option<AsmArgument<'a>> {
+    (!vm).then_some(AsmArgument::Label("v0.t"))
+}
+
+/// `vtype` as its element width, register group multiplier and tail and mask policies, e.g.
+/// `e32, m4, ta, ma`, or as a number if it has reserved bits set.
+fn vtype_operands<'a>(vtype: u16) -> Vec<AsmArgument<'a>> {
+    let sew = match (vtype >> 3) & 0b111 {
+        0b000 => "e8",
+        0b001 => "e16",
+        0b010 => "e32",
+        0b011 => "e64",
+        _ => return vec![AsmArgument::Immediate(vtype.into())],
+    };
+    let lmul = match vtype & 0b111 {
+        0b000 => "m1",
+        0b001 => "m2",
+        0b010 => "m4",
+        0b011 => "m8",
+        0b101 => "mf8",
+        0b110 => "mf4",
+        0b111 => "mf2",
+        _ => return vec![AsmArgument::Immediate(vtype.into())],
+    };
+    if vtype >> 8 != 0 {
+        return vec![AsmArgument::Immediate(vtype.into())];
+    }
+
+    let ta = if vtype & 0x40 != 0 { "ta" } else { "tu" };
+    let ma = if vtype & 0x80 != 0 { "ma" } else { "mu" };
+    vec![
+        AsmArgument::Label(sew),
+        AsmArgument::Label(lmul),
+        AsmArgument::Label(ta),
+        AsmArgument::Label(ma),
+    ]
+}
+
+/// Register operands of a load or store: the data register, the base address and the stride or
+/// index register, followed by the mask.
+fn memory_operands<'a>(word: u32) -> Vec<AsmArgument<'a>> {
+    let vd = ((word >> 7) & 0x1f) as u8;
+    let rs1 = ((word >> 15) & 0x1f) as u8;
+    let rs2 = ((word >> 20) & 0x1f) as u8;
+    let mop = (word >> 26) & 0b11;
+    let vm = (word >> 25) & 1 != 0;
+
+    let mut arguments = vec![
+        AsmArgument::vreg(vd).unwrap(),
+        AsmArgument::Address(rs1.try_into().unwrap()),
+    ];
+    match mop {
+        STRIDED => arguments.push(AsmArgument::reg(rs2).unwrap()),
+        INDEXED_UNORDERED | INDEXED_ORDERED => arguments.push(AsmArgument::vreg(rs2).unwrap()),
+        _ => {}
+    }
+    arguments.extend(mask_operand(vm));
+    arguments
+}
+
+const WHOLE_REGISTER_STORES: [&str; 4] = ["vs1r.v", "vs2r.v", "vs4r.v", "vs8r.v"];
+
+/// Unit-stride loads and their segment forms, by element width and `nf`.
+const UNIT_LOADS: [[&str; 8]; 4] = [
+    [
+        "vle8.v",
+        "vlseg2e8.v",
+        "vlseg3e8.v",
+        "vlseg4e8.v",
+        "vlseg5e8.v",
+        "vlseg6e8.v",
+        "vlseg7e8.v",
+        "vlseg8e8.v",
+    ],
+    [
+        "vle16.v",
+        "vlseg2e16.v",
+        "vlseg3e16.v",
+        "vlseg4e16.v",
+        "vlseg5e16.v",
+        "vlseg6e16.v",
+        "vlseg7e16.v",
+        "vlseg8e16.v",
+    ],
+    [
+        "vle32.v",
+        "vlseg2e32.v",
+        "vlseg3e32.v",
+        "vlseg4e32.v",
+        "vlseg5e32.v",
+        "vlseg6e32.v",
+        "vlseg7e32.v",
+        "vlseg8e32.v",
+    ],
+    [
+        "vle64.v",
+        "vlseg2e64.v",
+        "vlseg3e64.v",
+        "vlseg4e64.v",
+        "vlseg5e64.v",
+        "vlseg6e64.v",
+        "vlseg7e64.v",
+        "vlseg8e64.v",
+    ],
+];
+
+/// Fault-only-first loads.
+const FAULT_ONLY_FIRST_LOADS: [[&str; 8]; 4] = [
+    [
+        "vle8ff.v",
+        "vlseg2e8ff.v",
+        "vlseg3e8ff.v",
+        "vlseg4e8ff.v",
+        "vlseg5e8ff.v",
+        "vlseg6e8ff.v",
+        "vlseg7e8ff.v",
+        "vlseg8e8ff.v",
+    ],
+    [
+        "vle16ff.v",
+        "vlseg2e16ff.v",
+        "vlseg3e16ff.v",
+        "vlseg4e16ff.v",
+        "vlseg5e16ff.v",
+        "vlseg6e16ff.v",
+        "vlseg7e16ff.v",
+        "vlseg8e16ff.v",
+    ],
+    [
+        "vle32ff.v",
+        "vlseg2e32ff.v",
+        "vlseg3e32ff.v",
+        "vlseg4e32ff.v",
+        "vlseg5e32ff.v",
+        "vlseg6e32ff.v",
+        "vlseg7e32ff.v",
+        "vlseg8e32ff.v",
+    ],
+    [
+        "vle64ff.v",
+        "vlseg2e64ff.v",
+        "vlseg3e64ff.v",
+        "vlseg4e64ff.v",
+        "vlseg5e64ff.v",
+        "vlseg6e64ff.v",
+        "vlseg7e64ff.v",
+        "vlseg8e64ff.v",
+    ],
+];
+
+/// Strided loads.
+const STRIDED_LOADS: [[&str; 8]; 4] = [
+    [
+        "vlse8.v",
+        "vlsseg2e8.v",
+        "vlsseg3e8.v",
+        "vlsseg4e8.v",
+        "vlsseg5e8.v",
+        "vlsseg6e8.v",
+        "vlsseg7e8.v",
+        "vlsseg8e8.v",
+    ],
+    [
+        "vlse16.v",
+        "vlsseg2e16.v",
+        "vlsseg3e16.v",
+        "vlsseg4e16.v",
+        "vlsseg5e16.v",
+        "vlsseg6e16.v",
+        "vlsseg7e16.v",
+        "vlsseg8e16.v",
+    ],
+    [
+        "vlse32.v",
+        "vlsseg2e32.v",
+        "vlsseg3e32.v",
+        "vlsseg4e32.v",
+        "vlsseg5e32.v",
+        "vlsseg6e32.v",
+        "vlsseg7e32.v",
+        "vlsseg8e32.v",
+    ],
+    [
+        "vlse64.v",
+        "vlsseg2e64.v",
+        "vlsseg3e64.v",
+        "vlsseg4e64.v",
+        "vlsseg5e64.v",
+        "vlsseg6e64.v",
+        "vlsseg7e64.v",
+        "vlsseg8e64.v",
+    ],
+];
+
+/// Indexed-unordered loads, by index width.
+const UNORDERED_LOADS: [[&str; 8]; 4] = [
+    [
+        "vluxei8.v",
+        "vluxseg2ei8.v",
+        "vluxseg3ei8.v",
+        "vluxseg4ei8.v",
+        "vluxseg5ei8.v",
+        "vluxseg6ei8.v",
+        "vluxseg7ei8.v",
+        "vluxseg8ei8.v",
+    ],
+    [
+        "vluxei16.v",
+        "vluxseg2ei16.v",
+        "vluxseg3ei16.v",
+        "vluxseg4ei16.v",
+        "vluxseg5ei16.v",
+        "vluxseg6ei16.v",
+        "vluxseg7ei16.v",
+        "vluxseg8ei16.v",
+    ],
+    [
+        "vluxei32.v",
+        "vluxseg2ei32.v",
+        "vluxseg3ei32.v",
+        "vluxseg4ei32.v",
+        "vluxseg5ei32.v",
+        "vluxseg6ei32.v",
+        "vluxseg7ei32.v",
+        "vluxseg8ei32.v",
+    ],
+    [
+        "vluxei64.v",
+        "vluxseg2ei64.v",
+        "vluxseg3ei64.v",
+        "vluxseg4ei64.v",
+        "vluxseg5ei64.v",
+        "vluxseg6ei64.v",
+        "vluxseg7ei64.v",
+        "vluxseg8ei64.v",
+    ],
+];
+
+/// Indexed-ordered loads.
+const ORDERED_LOADS: [[&str; 8]; 4] = [
+    [
+        "vloxei8.v",
+        "vloxseg2ei8.v",
+        "vloxseg3ei8.v",
+        "vloxseg4ei8.v",
+        "vloxseg5ei8.v",
+        "vloxseg6ei8.v",
+        "vloxseg7ei8.v",
+        "vloxseg8ei8.v",
+    ],
+    [
+        "vloxei16.v",
+        "vloxseg2ei16.v",
+        "vloxseg3ei16.v",
+        "vloxseg4ei16.v",
+        "vloxseg5ei16.v",
+        "vloxseg6ei16.v",
+        "vloxseg7ei16.v",
+        "vloxseg8ei16.v",
+    ],
+    [
+        "vloxei32.v",
+        "vloxseg2ei32.v",
+        "vloxseg3ei32.v",
+        "vloxseg4ei32.v",
+        "vloxseg5ei32.v",
+        "vloxseg6ei32.v",
+        "vloxseg7ei32.v",
+        "vloxseg8ei32.v",
+    ],
+    [
+        "vloxei64.v",
+        "vloxseg2ei64.v",
+        "vloxseg3ei64.v",
+        "vloxseg4ei64.v",
+        "vloxseg5ei64.v",
+        "vloxseg6ei64.v",
+        "vloxseg7ei64.v",
+        "vloxseg8ei64.v",
+    ],
+];
+
+/// Unit-stride stores.
+const UNIT_STORES: [[&str; 8]; 4] = [
+    [
+        "vse8.v",
+        "vsseg2e8.v",
+        "vsseg3e8.v",
+        "vsseg4e8.v",
+        "vsseg5e8.v",
+        "vsseg6e8.v",
+        "vsseg7e8.v",
+        "vsseg8e8.v",
+    ],
+    [
+        "vse16.v",
+        "vsseg2e16.v",
+        "vsseg3e16.v",
+        "vsseg4e16.v",
+        "vsseg5e16.v",
+        "vsseg6e16.v",
+        "vsseg7e16.v",
+        "vsseg8e16.v",
+    ],
+    [
+        "vse32.v",
+        "vsseg2e32.v",
+        "vsseg3e32.v",
+        "vsseg4e32.v",
+        "vsseg5e32.v",
+        "vsseg6e32.v",
+        "vsseg7e32.v",
+        "vsseg8e32.v",
+    ],
+    [
+        "vse64.v",
+        "vsseg2e64.v",
+        "vsseg3e64.v",
+        "vsseg4e64.v",
+        "vsseg5e64.v",
+        "vsseg6e64.v",
+        "vsseg7e64.v",
+        "vsseg8e64.v",
+    ],
+];
+
+/// Strided stores.
+const STRIDED_STORES: [[&str; 8]; 4] = [
+    [
+        "vsse8.v",
+        "vssseg2e8.v",
+        "vssseg3e8.v",
+        "vssseg4e8.v",
+        "vssseg5e8.v",
+        "vssseg6e8.v",
+        "vssseg7e8.v",
+        "vssseg8e8.v",
+    ],
+    [
+        "vsse16.v",
+        "vssseg2e16.v",
+        "vssseg3e16.v",
+        "vssseg4e16.v",
+        "vssseg5e16.v",
+        "vssseg6e16.v",
+        "vssseg7e16.v",
+        "vssseg8e16.v",
+    ],
+    [
+        "vsse32.v",
+        "vssseg2e32.v",
+        "vssseg3e32.v",
+        "vssseg4e32.v",
+        "vssseg5e32.v",
+        "vssseg6e32.v",
+        "vssseg7e32.v",
+        "vssseg8e32.v",
+    ],
+    [
+        "vsse64.v",
+        "vssseg2e64.v",
+        "vssseg3e64.v",
+        "vssseg4e64.v",
+        "vssseg5e64.v",
+        "vssseg6e64.v",
+        "vssseg7e64.v",
+        "vssseg8e64.v",
+    ],
+];
+
+/// Indexed-unordered stores.
+const UNORDERED_STORES: [[&str; 8]; 4] = [
+    [
+        "vsuxei8.v",
+        "vsuxseg2ei8.v",
+        "vsuxseg3ei8.v",
+        "vsuxseg4ei8.v",
+        "vsuxseg5ei8.v",
+        "vsuxseg6ei8.v",
+        "vsuxseg7ei8.v",
+        "vsuxseg8ei8.v",
+    ],
+    [
+        "vsuxei16.v",
+        "vsuxseg2ei16.v",
+        "vsuxseg3ei16.v",
+        "vsuxseg4ei16.v",
+        "vsuxseg5ei16.v",
+        "vsuxseg6ei16.v",
+        "vsuxseg7ei16.v",
+        "vsuxseg8ei16.v",
+    ],
+    [
+        "vsuxei32.v",
+        "vsuxseg2ei32.v",
+        "vsuxseg3ei32.v",
+        "vsuxseg4ei32.v",
+        "vsuxseg5ei32.v",
+        "vsuxseg6ei32.v",
+        "vsuxseg7ei32.v",
+        "vsuxseg8ei32.v",
+    ],
+    [
+        "vsuxei64.v",
+        "vsuxseg2ei64.v",
+        "vsuxseg3ei64.v",
+        "vsuxseg4ei64.v",
+        "vsuxseg5ei64.v",
+        "vsuxseg6ei64.v",
+        "vsuxseg7ei64.v",
+        "vsuxseg8ei64.v",
+    ],
+];
+
+/// Indexed-ordered stores.
+const ORDERED_STORES: [[&str; 8]; 4] = [
+    [
+        "vsoxei8.v",
+        "vsoxseg2ei8.v",
+        "vsoxseg3ei8.v",
+        "vsoxseg4ei8.v",
+        "vsoxseg5ei8.v",
+        "vsoxseg6ei8.v",
+        "vsoxseg7ei8.v",
+        "vsoxseg8ei8.v",
+    ],
+    [
+        "vsoxei16.v",
+        "vsoxseg2ei16.v",
+        "vsoxseg3ei16.v",
+        "vsoxseg4ei16.v",
+        "vsoxseg5ei16.v",
+        "vsoxseg6ei16.v",
+        "vsoxseg7ei16.v",
+        "vsoxseg8ei16.v",
+    ],
+    [
+        "vsoxei32.v",
+        "vsoxseg2ei32.v",
+        "vsoxseg3ei32.v",
+        "vsoxseg4ei32.v",
+        "vsoxseg5ei32.v",
+        "vsoxseg6ei32.v",
+        "vsoxseg7ei32.v",
+        "vsoxseg8ei32.v",
+    ],
+    [
+        "vsoxei64.v",
+        "vsoxseg2ei64.v",
+        "vsoxseg3ei64.v",
+        "vsoxseg4ei64.v",
+        "vsoxseg5ei64.v",
+        "vsoxseg6ei64.v",
+        "vsoxseg7ei64.v",
+        "vsoxseg8ei64.v",
+    ],
+];
+
+/// Whole-register loads, by element width and register count. Those of bytes are shown as the
+/// `vl<n>r.v` aliases.
+const WHOLE_REGISTER_LOADS: [[&str; 4]; 4] = [
+    ["vl1r.v", "vl2r.v", "vl4r.v", "vl8r.v"],
+    ["vl1re16.v", "vl2re16.v", "vl4re16.v", "vl8re16.v"],
+    ["vl1re32.v", "vl2re32.v", "vl4re32.v", "vl8re32.v"],
+    ["vl1re64.v", "vl2re64.v", "vl4re64.v", "vl8re64.v"],
+];
+
+/// Every arithmetic instruction with its `funct3` category, `funct6`, operands and masking.
+///
+/// Aliases come before the instruction they stand for, the first entry matching a word wins.
+const ARITHMETIC: &[(&str, u32, u32, Form, Mask)] = &[
+    ("vadd.vv", OPIVV, 0b000000, Form::Vv, Mask::Masked),
+    ("vsub.vv", OPIVV, 0b000010, Form::Vv, Mask::Masked),
+    ("vminu.vv", OPIVV, 0b000100, Form::Vv, Mask::Masked),
+    ("vmin.vv", OPIVV, 0b000101, Form::Vv, Mask::Masked),
+    ("vmaxu.vv", OPIVV, 0b000110, Form::Vv, Mask::Masked),
+    ("vmax.vv", OPIVV, 0b000111, Form::Vv, Mask::Masked),
+    ("vand.vv", OPIVV, 0b001001, Form::Vv, Mask::Masked),
+    ("vor.vv", OPIVV, 0b001010, Form::Vv, Mask::Masked),
+    ("vxor.vv", OPIVV, 0b001011, Form::Vv, Mask::Masked),
+    ("vrgather.vv", OPIVV, 0b001100, Form::Vv, Mask::Masked),
+    ("vrgatherei16.vv", OPIVV, 0b001110, Form::Vv, Mask::Masked),
+    ("vadc.vvm", OPIVV, 0b010000, Form::Vv, Mask::CarryIn),
+    ("vmadc.vvm", OPIVV, 0b010001, Form::Vv, Mask::CarryIn),
+    ("vmadc.vv", OPIVV, 0b010001, Form::Vv, Mask::Unmasked),
+    ("vsbc.vvm", OPIVV, 0b010010, Form::Vv, Mask::CarryIn),
+    ("vmsbc.vvm", OPIVV, 0b010011, Form::Vv, Mask::CarryIn),
+    ("vmsbc.vv", OPIVV, 0b010011, Form::Vv, Mask::Unmasked),
+    ("vmerge.vvm", OPIVV, 0b010111, Form::Vv, Mask::CarryIn),
+    ("vmv.v.v", OPIVV, 0b010111, Form::MoveV, Mask::Unmasked),
+    ("vmseq.vv", OPIVV, 0b011000, Form::Vv, Mask::Masked),
+    ("vmsne.vv", OPIVV, 0b011001, Form::Vv, Mask::Masked),
+    ("vmsltu.vv", OPIVV, 0b011010, Form::Vv, Mask::Masked),
+    ("vmslt.vv", OPIVV, 0b011011, Form::Vv, Mask::Masked),
+    ("vmsleu.vv", OPIVV, 0b011100, Form::Vv, Mask::Masked),
+    ("vmsle.vv", OPIVV, 0b011101, Form::Vv, Mask::Masked),
+    ("vsaddu.vv", OPIVV, 0b100000, Form::Vv, Mask::Masked),
+    ("vsadd.vv", OPIVV, 0b100001, Form::Vv, Mask::Masked),
+    ("vssubu.vv", OPIVV, 0b100010, Form::Vv, Mask::Masked),
+    ("vssub.vv", OPIVV, 0b100011, Form::Vv, Mask::Masked),
+    ("vsll.vv", OPIVV, 0b100101, Form::Vv, Mask::Masked),
+    ("vsmul.vv", OPIVV, 0b100111, Form::Vv, Mask::Masked),
+    ("vsrl.vv", OPIVV, 0b101000, Form::Vv, Mask::Masked),
+    ("vsra.vv", OPIVV, 0b101001, Form::Vv, Mask::Masked),
+    ("vssrl.vv", OPIVV, 0b101010, Form::Vv, Mask::Masked),
+    ("vssra.vv", OPIVV, 0b101011, Form::Vv, Mask::Masked),
+    ("vnsrl.wv", OPIVV, 0b101100, Form::Vv, Mask::Masked),
+    ("vnsra.wv", OPIVV, 0b101101, Form::Vv, Mask::Masked),
+    ("vnclipu.wv", OPIVV, 0b101110, Form::Vv, Mask::Masked),
+    ("vnclip.wv", OPIVV, 0b101111, Form::Vv, Mask::Masked),
+    ("vwredsumu.vs", OPIVV, 0b110000, Form::Vv, Mask::Masked),
+    ("vwredsum.vs", OPIVV, 0b110001, Form::Vv, Mask::Masked),
+    ("vadd.vx", OPIVX, 0b000000, Form::Vx, Mask::Masked),
+    ("vsub.vx", OPIVX, 0b000010, Form::Vx, Mask::Masked),
+    ("vrsub.vx", OPIVX, 0b000011, Form::Vx, Mask::Masked),
+    ("vminu.vx", OPIVX, 0b000100, Form::Vx, Mask::Masked),
+    ("vmin.vx", OPIVX, 0b000101, Form::Vx, Mask::Masked),
+    ("vmaxu.vx", OPIVX, 0b000110, Form::Vx, Mask::Masked),
+    ("vmax.vx", OPIVX, 0b000111, Form::Vx, Mask::Masked),
+    ("vand.vx", OPIVX, 0b001001, Form::Vx, Mask::Masked),
+    ("vor.vx", OPIVX, 0b001010, Form::Vx, Mask::Masked),
+    ("vxor.vx", OPIVX, 0b001011, Form::Vx, Mask::Masked),
+    ("vrgather.vx", OPIVX, 0b001100, Form::Vx, Mask::Masked),
+    ("vslideup.vx", OPIVX, 0b001110, Form::Vx, Mask::Masked),
+    ("vslidedown.vx", OPIVX, 0b001111, Form::Vx, Mask::Masked),
+    ("vadc.vxm", OPIVX, 0b010000, Form::Vx, Mask::CarryIn),
+    ("vmadc.vxm", OPIVX, 0b010001, Form::Vx, Mask::CarryIn),
+    ("vmadc.vx", OPIVX, 0b010001, Form::Vx, Mask::Unmasked),
+    ("vsbc.vxm", OPIVX, 0b010010, Form::Vx, Mask::CarryIn),
+    ("vmsbc.vxm", OPIVX, 0b010011, Form::Vx, Mask::CarryIn),
+    ("vmsbc.vx", OPIVX, 0b010011, Form::Vx, Mask::Unmasked),
+    ("vmerge.vxm", OPIVX, 0b010111, Form::Vx, Mask::CarryIn),
+    ("vmv.v.x", OPIVX, 0b010111, Form::MoveX, Mask::Unmasked),
+    ("vmseq.vx", OPIVX, 0b011000, Form::Vx, Mask::Masked),
+    ("vmsne.vx", OPIVX, 0b011001, Form::Vx, Mask::Masked),
+    ("vmsltu.vx", OPIVX, 0b011010, Form::Vx, Mask::Masked),
+    ("vmslt.vx", OPIVX, 0b011011, Form::Vx, Mask::Masked),
+    ("vmsleu.vx", OPIVX, 0b011100, Form::Vx, Mask::Masked),
+    ("vmsle.vx", OPIVX, 0b011101, Form::Vx, Mask::Masked),
+    ("vmsgtu.vx", OPIVX, 0b011110, Form::Vx, Mask::Masked),
+    ("vmsgt.vx", OPIVX, 0b011111, Form::Vx, Mask::Masked),
+    ("vsaddu.vx", OPIVX, 0b100000, Form::Vx, Mask::Masked),
+    ("vsadd.vx", OPIVX, 0b100001, Form::Vx, Mask::Masked),
+    ("vssubu.vx", OPIVX, 0b100010, Form::Vx, Mask::Masked),
+    ("vssub.vx", OPIVX, 0b100011, Form::Vx, Mask::Masked),
+    ("vsll.vx", OPIVX, 0b100101, Form::Vx, Mask::Masked),
+    ("vsmul.vx", OPIVX, 0b100111, Form::Vx, Mask::Masked),
+    ("vsrl.vx", OPIVX, 0b101000, Form::Vx, Mask::Masked),
+    ("vsra.vx", OPIVX, 0b101001, Form::Vx, Mask::Masked),
+    ("vssrl.vx", OPIVX, 0b101010, Form::Vx, Mask::Masked),
+    ("vssra.vx", OPIVX, 0b101011, Form::Vx, Mask::Masked),
+    ("vnsrl.wx", OPIVX, 0b101100, Form::Vx, Mask::Masked),
+    ("vnsra.wx", OPIVX, 0b101101, Form::Vx, Mask::Masked),
+    ("vnclipu.wx", OPIVX, 0b101110, Form::Vx, Mask::Masked),
+    ("vnclip.wx", OPIVX, 0b101111, Form::Vx, Mask::Masked),
+    ("vadd.vi", OPIVI, 0b000000, Form::Vi, Mask::Masked),
+    ("vrsub.vi", OPIVI, 0b000011, Form::Vi, Mask::Masked),
+    ("vand.vi", OPIVI, 0b001001, Form::Vi, Mask::Masked),
+    ("vor.vi", OPIVI, 0b001010, Form::Vi, Mask::Masked),
+    ("vxor.vi", OPIVI, 0b001011, Form::Vi, Mask::Masked),
+    ("vrgather.vi", OPIVI, 0b001100, Form::Vu, Mask::Masked),
+    ("vslideup.vi", OPIVI, 0b001110, Form::Vu, Mask::Masked),
+    ("vslidedown.vi", OPIVI, 0b001111, Form::Vu, Mask::Masked),
+    ("vadc.vim", OPIVI, 0b010000, Form::Vi, Mask::CarryIn),
+    ("vmadc.vim", OPIVI, 0b010001, Form::Vi, Mask::CarryIn),
+    ("vmadc.vi", OPIVI, 0b010001, Form::Vi, Mask::Unmasked),
+    ("vmerge.vim", OPIVI, 0b010111, Form::Vi, Mask::CarryIn),
+    ("vmv.v.i", OPIVI, 0b010111, Form::MoveI, Mask::Unmasked),
+    ("vmseq.vi", OPIVI, 0b011000, Form::Vi, Mask::Masked),
+    ("vmsne.vi", OPIVI, 0b011001, Form::Vi, Mask::Masked),
+    ("vmsleu.vi", OPIVI, 0b011100, Form::Vi, Mask::Masked),
+    ("vmsle.vi", OPIVI, 0b011101, Form::Vi, Mask::Masked),
+    ("vmsgtu.vi", OPIVI, 0b011110, Form::Vi, Mask::Masked),
+    ("vmsgt.vi", OPIVI, 0b011111, Form::Vi, Mask::Masked),
+    ("vsaddu.vi", OPIVI, 0b100000, Form::Vi, Mask::Masked),
+    ("vsadd.vi", OPIVI, 0b100001, Form::Vi, Mask::Masked),
+    ("vsll.vi", OPIVI, 0b100101, Form::Vu, Mask::Masked),
+    (
+        "vmv1r.v",
+        OPIVI,
+        0b100111,
+        Form::MoveWhole(1),
+        Mask::Unmasked,
+    ),
+    (
+        "vmv2r.v",
+        OPIVI,
+        0b100111,
+        Form::MoveWhole(2),
+        Mask::Unmasked,
+    ),
+    (
+        "vmv4r.v",
+        OPIVI,
+        0b100111,
+        Form::MoveWhole(4),
+        Mask::Unmasked,
+    ),
+    (
+        "vmv8r.v",
+        OPIVI,
+        0b100111,
+        Form::MoveWhole(8),
+        Mask::Unmasked,
+    ),
+    ("vsrl.vi", OPIVI, 0b101000, Form::Vu, Mask::Masked),
+    ("vsra.vi", OPIVI, 0b101001, Form::Vu, Mask::Masked),
+    ("vssrl.vi", OPIVI, 0b101010, Form::Vu, Mask::Masked),
+    ("vssra.vi", OPIVI, 0b101011, Form::Vu, Mask::Masked),
+    ("vnsrl.wi", OPIVI, 0b101100, Form::Vu, Mask::Masked),
+    ("vnsra.wi", OPIVI, 0b101101, Form::Vu, Mask::Masked),
+    ("vnclipu.wi", OPIVI, 0b101110, Form::Vu, Mask::Masked),
+    ("vnclip.wi", OPIVI, 0b101111, Form::Vu, Mask::Masked),
+    ("vredsum.vs", OPMVV, 0b000000, Form::Vv, Mask::Masked),
+    ("vredand.vs", OPMVV, 0b000001, Form::Vv, Mask::Masked),
+    ("vredor.vs", OPMVV, 0b000010, Form::Vv, Mask::Masked),
+    ("vredxor.vs", OPMVV, 0b000011, Form::Vv, Mask::Masked),
+    ("vredminu.vs", OPMVV, 0b000100, Form::Vv, Mask::Masked),
+    ("vredmin.vs", OPMVV, 0b000101, Form::Vv, Mask::Masked),
+    ("vredmaxu.vs", OPMVV, 0b000110, Form::Vv, Mask::Masked),
+    ("vredmax.vs", OPMVV, 0b000111, Form::Vv, Mask::Masked),
+    ("vaaddu.vv", OPMVV, 0b001000, Form::Vv, Mask::Masked),
+    ("vaadd.vv", OPMVV, 0b001001, Form::Vv, Mask::Masked),
+    ("vasubu.vv", OPMVV, 0b001010, Form::Vv, Mask::Masked),
+    ("vasub.vv", OPMVV, 0b001011, Form::Vv, Mask::Masked),
+    (
+        "vmv.x.s",
+        OPMVV,
+        0b010000,
+        Form::ToX(0b00000),
+        Mask::Unmasked,
+    ),
+    ("vcpop.m", OPMVV, 0b010000, Form::ToX(0b10000), Mask::Masked),
+    (
+        "vfirst.m",
+        OPMVV,
+        0b010000,
+        Form::ToX(0b10001),
+        Mask::Masked,
+    ),
+    (
+        "vzext.vf8",
+        OPMVV,
+        0b010010,
+        Form::Unary(0b00010),
+        Mask::Masked,
+    ),
+    (
+        "vsext.vf8",
+        OPMVV,
+        0b010010,
+        Form::Unary(0b00011),
+        Mask::Masked,
+    ),
+    (
+        "vzext.vf4",
+        OPMVV,
+        0b010010,
+        Form::Unary(0b00100),
+        Mask::Masked,
+    ),
+    (
+        "vsext.vf4",
+        OPMVV,
+        0b010010,
+        Form::Unary(0b00101),
+        Mask::Masked,
+    ),
+    (
+        "vzext.vf2",
+        OPMVV,
+        0b010010,
+        Form::Unary(0b00110),
+        Mask::Masked,
+    ),
+    (
+        "vsext.vf2",
+        OPMVV,
+        0b010010,
+        Form::Unary(0b00111),
+        Mask::Masked,
+    ),
+    (
+        "vmsbf.m",
+        OPMVV,
+        0b010100,
+        Form::Unary(0b00001),
+        Mask::Masked,
+    ),
+    (
+        "vmsof.m",
+        OPMVV,
+        0b010100,
+        Form::Unary(0b00010),
+        Mask::Masked,
+    ),
+    (
+        "vmsif.m",
+        OPMVV,
+        0b010100,
+        Form::Unary(0b00011),
+        Mask::Masked,
+    ),
+    (
+        "viota.m",
+        OPMVV,
+        0b010100,
+        Form::Unary(0b10000),
+        Mask::Masked,
+    ),
+    (
+        "vid.v",
+        OPMVV,
+        0b010100,
+        Form::Nullary(0b10001),
+        Mask::Masked,
+    ),
+    ("vcompress.vm", OPMVV, 0b010111, Form::Vv, Mask::Unmasked),
+    ("vmandn.mm", OPMVV, 0b011000, Form::Vv, Mask::Unmasked),
+    ("vmmv.m", OPMVV, 0b011001, Form::SameV, Mask::Unmasked),
+    ("vmand.mm", OPMVV, 0b011001, Form::Vv, Mask::Unmasked),
+    ("vmor.mm", OPMVV, 0b011010, Form::Vv, Mask::Unmasked),
+    ("vmclr.m", OPMVV, 0b011011, Form::SameVd, Mask::Unmasked),
+    ("vmxor.mm", OPMVV, 0b011011, Form::Vv, Mask::Unmasked),
+    ("vmorn.mm", OPMVV, 0b011100, Form::Vv, Mask::Unmasked),
+    ("vmnot.m", OPMVV, 0b011101, Form::SameV, Mask::Unmasked),
+    ("vmnand.mm", OPMVV, 0b011101, Form::Vv, Mask::Unmasked),
+    ("vmnor.mm", OPMVV, 0b011110, Form::Vv, Mask::Unmasked),
+    ("vmset.m", OPMVV, 0b011111, Form::SameVd, Mask::Unmasked),
+    ("vmxnor.mm", OPMVV, 0b011111, Form::Vv, Mask::Unmasked),
+    ("vdivu.vv", OPMVV, 0b100000, Form::Vv, Mask::Masked),
+    ("vdiv.vv", OPMVV, 0b100001, Form::Vv, Mask::Masked),
+    ("vremu.vv", OPMVV, 0b100010, Form::Vv, Mask::Masked),
+    ("vrem.vv", OPMVV, 0b100011, Form::Vv, Mask::Masked),
+    ("vmulhu.vv", OPMVV, 0b100100, Form::Vv, Mask::Masked),
+    ("vmul.vv", OPMVV, 0b100101, Form::Vv, Mask::Masked),
+    ("vmulhsu.vv", OPMVV, 0b100110, Form::Vv, Mask::Masked),
+    ("vmulh.vv", OPMVV, 0b100111, Form::Vv, Mask::Masked),
+    ("vmadd.vv", OPMVV, 0b101001, Form::MaccV, Mask::Masked),
+    ("vnmsub.vv", OPMVV, 0b101011, Form::MaccV, Mask::Masked),
+    ("vmacc.vv", OPMVV, 0b101101, Form::MaccV, Mask::Masked),
+    ("vnmsac.vv", OPMVV, 0b101111, Form::MaccV, Mask::Masked),
+    ("vwaddu.vv", OPMVV, 0b110000, Form::Vv, Mask::Masked),
+    ("vwadd.vv", OPMVV, 0b110001, Form::Vv, Mask::Masked),
+    ("vwsubu.vv", OPMVV, 0b110010, Form::Vv, Mask::Masked),
+    ("vwsub.vv", OPMVV, 0b110011, Form::Vv, Mask::Masked),
+    ("vwaddu.wv", OPMVV, 0b110100, Form::Vv, Mask::Masked),
+    ("vwadd.wv", OPMVV, 0b110101, Form::Vv, Mask::Masked),
+    ("vwsubu.wv", OPMVV, 0b110110, Form::Vv, Mask::Masked),
+    ("vwsub.wv", OPMVV, 0b110111, Form::Vv, Mask::Masked),
+    ("vwmulu.vv", OPMVV, 0b111000, Form::Vv, Mask::Masked),
+    ("vwmulsu.vv", OPMVV, 0b111010, Form::Vv, Mask::Masked),
+    ("vwmul.vv", OPMVV, 0b111011, Form::Vv, Mask::Masked),
+    ("vwmaccu.vv", OPMVV, 0b111100, Form::MaccV, Mask::Masked),
+    ("vwmacc.vv", OPMVV, 0b111101, Form::MaccV, Mask::Masked),
+    ("vwmaccsu.vv", OPMVV, 0b111111, Form::MaccV, Mask::Masked),
+    ("vaaddu.vx", OPMVX, 0b001000, Form::Vx, Mask::Masked),
+    ("vaadd.vx", OPMVX, 0b001001, Form::Vx, Mask::Masked),
+    ("vasubu.vx", OPMVX, 0b001010, Form::Vx, Mask::Masked),
+    ("vasub.vx", OPMVX, 0b001011, Form::Vx, Mask::Masked),
+    ("vslide1up.vx", OPMVX, 0b001110, Form::Vx, Mask::Masked),
+    ("vslide1down.vx", OPMVX, 0b001111, Form::Vx, Mask::Masked),
+    ("vmv.s.x", OPMVX, 0b010000, Form::MoveX, Mask::Unmasked),
+    ("vdivu.vx", OPMVX, 0b100000, Form::Vx, Mask::Masked),
+    ("vdiv.vx", OPMVX, 0b100001, Form::Vx, Mask::Masked),
+    ("vremu.vx", OPMVX, 0b100010, Form::Vx, Mask::Masked),
+    ("vrem.vx", OPMVX, 0b100011, Form::Vx, Mask::Masked),
+    ("vmulhu.vx", OPMVX, 0b100100, Form::Vx, Mask::Masked),
+    ("vmul.vx", OPMVX, 0b100101, Form::Vx, Mask::Masked),
+    ("vmulhsu.vx", OPMVX, 0b100110, Form::Vx, Mask::Masked),
+    ("vmulh.vx", OPMVX, 0b100111, Form::Vx, Mask::Masked),
+    ("vmadd.vx", OPMVX, 0b101001, Form::MaccX, Mask::Masked),
+    ("vnmsub.vx", OPMVX, 0b101011, Form::MaccX, Mask::Masked),
+    ("vmacc.vx", OPMVX, 0b101101, Form::MaccX, Mask::Masked),
+    ("vnmsac.vx", OPMVX, 0b101111, Form::MaccX, Mask::Masked),
+    ("vwaddu.vx", OPMVX, 0b110000, Form::Vx, Mask::Masked),
+    ("vwadd.vx", OPMVX, 0b110001, Form::Vx, Mask::Masked),
+    ("vwsubu.vx", OPMVX, 0b110010, Form::Vx, Mask::Masked),
+    ("vwsub.vx", OPMVX, 0b110011, Form::Vx, Mask::Masked),
+    ("vwaddu.wx", OPMVX, 0b110100, Form::Vx, Mask::Masked),
+    ("vwadd.wx", OPMVX, 0b110101, Form::Vx, Mask::Masked),
+    ("vwsubu.wx", OPMVX, 0b110110, Form::Vx, Mask::Masked),
+    ("vwsub.wx", OPMVX, 0b110111, Form::Vx, Mask::Masked),
+    ("vwmulu.vx", OPMVX, 0b111000, Form::Vx, Mask::Masked),
+    ("vwmulsu.vx", OPMVX, 0b111010, Form::Vx, Mask::Masked),
+    ("vwmul.vx", OPMVX, 0b111011, Form::Vx, Mask::Masked),
+    ("vwmaccu.vx", OPMVX, 0b111100, Form::MaccX, Mask::Masked),
+    ("vwmacc.vx", OPMVX, 0b111101, Form::MaccX, Mask::Masked),
+    ("vwmaccus.vx", OPMVX, 0b111110, Form::MaccX, Mask::Masked),
+    ("vwmaccsu.vx", OPMVX, 0b111111, Form::MaccX, Mask::Masked),
+    ("vfadd.vv", OPFVV, 0b000000, Form::Vv, Mask::Masked),
+    ("vfredusum.vs", OPFVV, 0b000001, Form::Vv, Mask::Masked),
+    ("vfsub.vv", OPFVV, 0b000010, Form::Vv, Mask::Masked),
+    ("vfredosum.vs", OPFVV, 0b000011, Form::Vv, Mask::Masked),
+    ("vfmin.vv", OPFVV, 0b000100, Form::Vv, Mask::Masked),
+    ("vfredmin.vs", OPFVV, 0b000101, Form::Vv, Mask::Masked),
+    ("vfmax.vv", OPFVV, 0b000110, Form::Vv, Mask::Masked),
+    ("vfredmax.vs", OPFVV, 0b000111, Form::Vv, Mask::Masked),
+    ("vfsgnj.vv", OPFVV, 0b001000, Form::Vv, Mask::Masked),
+    ("vfsgnjn.vv", OPFVV, 0b001001, Form::Vv, Mask::Masked),
+    ("vfsgnjx.vv", OPFVV, 0b001010, Form::Vv, Mask::Masked),
+    (
+        "vfmv.f.s",
+        OPFVV,
+        0b010000,
+        Form::ToF(0b00000),
+        Mask::Unmasked,
+    ),
+    (
+        "vfcvt.xu.f.v",
+        OPFVV,
+        0b010010,
+        Form::Unary(0b00000),
+        Mask::Masked,
+    ),
+    (
+        "vfcvt.x.f.v",
+        OPFVV,
+        0b010010,
+        Form::Unary(0b00001),
+        Mask::Masked,
+    ),
+    (
+        "vfcvt.f.xu.v",
+        OPFVV,
+        0b010010,
+        Form::Unary(0b00010),
+        Mask::Masked,
+    ),
+    (
+        "vfcvt.f.x.v",
+        OPFVV,
+        0b010010,
+        Form::Unary(0b00011),
+        Mask::Masked,
+    ),
+    (
+        "vfcvt.rtz.xu.f.v",
+        OPFVV,
+        0b010010,
+        Form::Unary(0b00110),
+        Mask::Masked,
+    ),
+    (
+        "vfcvt.rtz.x.f.v",
+        OPFVV,
+        0b010010,
+        Form::Unary(0b00111),
+        Mask::Masked,
+    ),
+    (
+        "vfwcvt.xu.f.v",
+        OPFVV,
+        0b010010,
+        Form::Unary(0b01000),
+        Mask::Masked,
+    ),
+    (
+        "vfwcvt.x.f.v",
+        OPFVV,
+        0b010010,
+        Form::Unary(0b01001),
+        Mask::Masked,
+    ),
+    (
+        "vfwcvt.f.xu.v",
+        OPFVV,
+        0b010010,
+        Form::Unary(0b01010),
+        Mask::Masked,
+    ),
+    (
+        "vfwcvt.f.x.v",
+        OPFVV,
+        0b010010,
+        Form::Unary(0b01011),
+        Mask::Masked,
+    ),
+    (
+        "vfwcvt.f.f.v",
+        OPFVV,
+        0b010010,
+        Form::Unary(0b01100),
+        Mask::Masked,
+    ),
+    (
+        "vfwcvt.rtz.xu.f.v",
+        OPFVV,
+        0b010010,
+        Form::Unary(0b01110),
+        Mask::Masked,
+    ),
+    (
+        "vfwcvt.rtz.x.f.v",
+        OPFVV,
+        0b010010,
+        Form::Unary(0b01111),
+        Mask::Masked,
+    ),
+    (
+        "vfncvt.xu.f.w",
+        OPFVV,
+        0b010010,
+        Form::Unary(0b10000),
+        Mask::Masked,
+    ),
+    (
+        "vfncvt.x.f.w",
+        OPFVV,
+        0b010010,
+        Form::Unary(0b10001),
+        Mask::Masked,
+    ),
+    (
+        "vfncvt.f.xu.w",
+        OPFVV,
+        0b010010,
+        Form::Unary(0b10010),
+        Mask::Masked,
+    ),
+    (
+        "vfncvt.f.x.w",
+        OPFVV,
+        0b010010,
+        Form::Unary(0b10011),
+        Mask::Masked,
+    ),
+    (
+        "vfncvt.f.f.w",
+        OPFVV,
+        0b010010,
+        Form::Unary(0b10100),
+        Mask::Masked,
+    ),
+    (
+        "vfncvt.rod.f.f.w",
+        OPFVV,
+        0b010010,
+        Form::Unary(0b10101),
+        Mask::Masked,
+    ),
+    (
+        "vfncvt.rtz.xu.f.w",
+        OPFVV,
+        0b010010,
+        Form::Unary(0b10110),
+        Mask::Masked,
+    ),
+    (
+        "vfncvt.rtz.x.f.w",
+        OPFVV,
+        0b010010,
+        Form::Unary(0b10111),
+        Mask::Masked,
+    ),
+    (
+        "vfsqrt.v",
+        OPFVV,
+        0b010011,
+        Form::Unary(0b00000),
+        Mask::Masked,
+    ),
+    (
+        "vfrsqrt7.v",
+        OPFVV,
+        0b010011,
+        Form::Unary(0b00100),
+        Mask::Masked,
+    ),
+    (
+        "vfrec7.v",
+        OPFVV,
+        0b010011,
+        Form::Unary(0b00101),
+        Mask::Masked,
+    ),
+    (
+        "vfclass.v",
+        OPFVV,
+        0b010011,
+        Form::Unary(0b10000),
+        Mask::Masked,
+    ),
+    ("vmfeq.vv", OPFVV, 0b011000, Form::Vv, Mask::Masked),
+    ("vmfle.vv", OPFVV, 0b011001, Form::Vv, Mask::Masked),
+    ("vmflt.vv", OPFVV, 0b011011, Form::Vv, Mask::Masked),
+    ("vmfne.vv", OPFVV, 0b011100, Form::Vv, Mask::Masked),
+    ("vfdiv.vv", OPFVV, 0b100000, Form::Vv, Mask::Masked),
+    ("vfmul.vv", OPFVV, 0b100100, Form::Vv, Mask::Masked),
+    ("vfmadd.vv", OPFVV, 0b101000, Form::MaccV, Mask::Masked),
+    ("vfnmadd.vv", OPFVV, 0b101001, Form::MaccV, Mask::Masked),
+    ("vfmsub.vv", OPFVV, 0b101010, Form::MaccV, Mask::Masked),
+    ("vfnmsub.vv", OPFVV, 0b101011, Form::MaccV, Mask::Masked),
+    ("vfmacc.vv", OPFVV, 0b101100, Form::MaccV, Mask::Masked),
+    ("vfnmacc.vv", OPFVV, 0b101101, Form::MaccV, Mask::Masked),
+    ("vfmsac.vv", OPFVV, 0b101110, Form::MaccV, Mask::Masked),
+    ("vfnmsac.vv", OPFVV, 0b101111, Form::MaccV, Mask::Masked),
+    ("vfwadd.vv", OPFVV, 0b110000, Form::Vv, Mask::Masked),
+    ("vfwredusum.vs", OPFVV, 0b110001, Form::Vv, Mask::Masked),
+    ("vfwsub.vv", OPFVV, 0b110010, Form::Vv, Mask::Masked),
+    ("vfwredosum.vs", OPFVV, 0b110011, Form::Vv, Mask::Masked),
+    ("vfwadd.wv", OPFVV, 0b110100, Form::Vv, Mask::Masked),
+    ("vfwsub.wv", OPFVV, 0b110110, Form::Vv, Mask::Masked),
+    ("vfwmul.vv", OPFVV, 0b111000, Form::Vv, Mask::Masked),
+    ("vfwmacc.vv", OPFVV, 0b111100, Form::MaccV, Mask::Masked),
+    ("vfwnmacc.vv", OPFVV, 0b111101, Form::MaccV, Mask::Masked),
+    ("vfwmsac.vv", OPFVV, 0b111110, Form::MaccV, Mask::Masked),
+    ("vfwnmsac.vv", OPFVV, 0b111111, Form::MaccV, Mask::Masked),
+    ("vfadd.vf", OPFVF, 0b000000, Form::Vf, Mask::Masked),
+    ("vfsub.vf", OPFVF, 0b000010, Form::Vf, Mask::Masked),
+    ("vfmin.vf", OPFVF, 0b000100, Form::Vf, Mask::Masked),
+    ("vfmax.vf", OPFVF, 0b000110, Form::Vf, Mask::Masked),
+    ("vfsgnj.vf", OPFVF, 0b001000, Form::Vf, Mask::Masked),
+    ("vfsgnjn.vf", OPFVF, 0b001001, Form::Vf, Mask::Masked),
+    ("vfsgnjx.vf", OPFVF, 0b001010, Form::Vf, Mask::Masked),
+    ("vfslide1up.vf", OPFVF, 0b001110, Form::Vf, Mask::Masked),
+    ("vfslide1down.vf", OPFVF, 0b001111, Form::Vf, Mask::Masked),
+    ("vfmv.s.f", OPFVF, 0b010000, Form::MoveF, Mask::Unmasked),
+    ("vfmerge.vfm", OPFVF, 0b010111, Form::Vf, Mask::CarryIn),
+    ("vfmv.v.f", OPFVF, 0b010111, Form::MoveF, Mask::Unmasked),
+    ("vmfeq.vf", OPFVF, 0b011000, Form::Vf, Mask::Masked),
+    ("vmfle.vf", OPFVF, 0b011001, Form::Vf, Mask::Masked),
+    ("vmflt.vf", OPFVF, 0b011011, Form::Vf, Mask::Masked),
+    ("vmfne.vf", OPFVF, 0b011100, Form::Vf, Mask::Masked),
+    ("vmfgt.vf", OPFVF, 0b011101, Form::Vf, Mask::Masked),
+    ("vmfge.vf", OPFVF, 0b011111, Form::Vf, Mask::Masked),
+    ("vfdiv.vf", OPFVF, 0b100000, Form::Vf, Mask::Masked),
+    ("vfrdiv.vf", OPFVF, 0b100001, Form::Vf, Mask::Masked),
+    ("vfmul.vf", OPFVF, 0b100100, Form::Vf, Mask::Masked),
+    ("vfrsub.vf", OPFVF, 0b100111, Form::Vf, Mask::Masked),
+    ("vfmadd.vf", OPFVF, 0b101000, Form::MaccF, Mask::Masked),
+    ("vfnmadd.vf", OPFVF, 0b101001, Form::MaccF, Mask::Masked),
+    ("vfmsub.vf", OPFVF, 0b101010, Form::MaccF, Mask::Masked),
+    ("vfnmsub.vf", OPFVF, 0b101011, Form::MaccF, Mask::Masked),
+    ("vfmacc.vf", OPFVF, 0b101100, Form::MaccF, Mask::Masked),
+    ("vfnmacc.vf", OPFVF, 0b101101, Form::MaccF, Mask::Masked),
+    ("vfmsac.vf", OPFVF, 0b101110, Form::MaccF, Mask::Masked),
+    ("vfnmsac.vf", OPFVF, 0b101111, Form::MaccF, Mask::Masked),
+    ("vfwadd.vf", OPFVF, 0b110000, Form::Vf, Mask::Masked),
+    ("vfwsub.vf", OPFVF, 0b110010, Form::Vf, Mask::Masked),
+    ("vfwadd.wf", OPFVF, 0b110100, Form::Vf, Mask::Masked),
+    ("vfwsub.wf", OPFVF, 0b110110, Form::Vf, Mask::Masked),
+    ("vfwmul.vf", OPFVF, 0b111000, Form::Vf, Mask::Masked),
+    ("vfwmacc.vf", OPFVF, 0b111100, Form::MaccF, Mask::Masked),
+    ("vfwnmacc.vf", OPFVF, 0b111101, Form::MaccF, Mask::Masked),
+    ("vfwmsac.vf", OPFVF, 0b111110, Form::MaccF, Mask::Masked),
+    ("vfwnmsac.vf", OPFVF, 0b111111, Form::MaccF, Mask::Masked),
+];
+
+impl Vsetvli {
+    /// The 11-bit `vtype` immediate.
+    #[inline]
+    pub fn vtypei(&self) -> u16 {
+        ((self.0 >> 20) & 0x7ff) as u16
+    }
+}
+
+impl Vsetivli {
+    /// The 10-bit `vtype` immediate.
+    #[inline]
+    pub fn vtypei(&self) -> u16 {
+        ((self.0 >> 20) & 0x3ff) as u16
+    }
+}
+
+impl AsmInstruction for Vsetvli {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "vsetvli"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        let mut arguments = vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::reg(self.rs1()).unwrap(),
+        ];
+        arguments.extend(vtype_operands(self.vtypei()));
+        arguments
+    }
+}
+
+impl AsmInstruction for Vsetivli {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "vsetivli"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        let mut arguments = vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::Immediate(self.uimm().into()),
+        ];
+        arguments.extend(vtype_operands(self.vtypei()));
+        arguments
+    }
+}
+
+impl AsmInstruction for Vsetvl {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        "vsetvl"
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        vec![
+            AsmArgument::reg(self.rd()).unwrap(),
+            AsmArgument::reg(self.rs1()).unwrap(),
+            AsmArgument::reg(self.rs2()).unwrap(),
+        ]
+    }
+}
+
+impl AsmInstruction for VectorLoad {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        load_verb(self.0).unwrap()
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        memory_operands(self.0)
+    }
+}
+
+impl AsmInstruction for VectorStore {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        store_verb(self.0).unwrap()
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        memory_operands(self.0)
+    }
+}
+
+impl AsmInstruction for VectorArithmetic {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        operation(self.0).unwrap().0
+    }
+
+    fn arguments(&self) -> Vec<AsmArgument> {
+        let (_, form, mask) = operation(self.0).unwrap();
+        let vd = AsmArgument::vreg(self.vd()).unwrap();
+        let vs1 = AsmArgument::vreg(self.vs1()).unwrap();
+        let vs2 = AsmArgument::vreg(self.vs2()).unwrap();
+        let rs1 = AsmArgument::reg(self.vs1()).unwrap();
+        let fs1 = AsmArgument::freg(self.vs1()).unwrap();
+        let simm = AsmArgument::Immediate(sext(self.vs1().into(), 5).into());
+        let uimm = AsmArgument::Immediate(self.vs1().into());
+
+        let mut arguments = match form {
+            Form::Vv => vec![vd, vs2, vs1],
+            Form::Vx => vec![vd, vs2, rs1],
+            Form::Vf => vec![vd, vs2, fs1],
+            Form::Vi => vec![vd, vs2, simm],
+            Form::Vu => vec![vd, vs2, uimm],
+            Form::MaccV => vec![vd, vs1, vs2],
+            Form::MaccX => vec![vd, rs1, vs2],
+            Form::MaccF => vec![vd, fs1, vs2],
+            Form::Unary(_) | Form::MoveWhole(_) | Form::SameV => vec![vd, vs2],
+            Form::Nullary(_) | Form::SameVd => vec![vd],
+            Form::ToX(_) => vec![AsmArgument::reg(self.vd()).unwrap(), vs2],
+            Form::ToF(_) => vec![AsmArgument::freg(self.vd()).unwrap(), vs2],
+            Form::MoveV => vec![vd, vs1],
+            Form::MoveX => vec![vd, rs1],
+            Form::MoveF => vec![vd, fs1],
+            Form::MoveI => vec![vd, simm],
+        };
+        match mask {
+            Mask::Masked => arguments.extend(mask_operand(self.vm())),
+            Mask::CarryIn => arguments.push(AsmArgument::vreg(0).unwrap()),
+            Mask::Unmasked => {}
+        }
+        arguments
+    }
+}
+
+#[test]
+fn display() {
+    use crate::AsmFormatOptions;
+
+    let display = |word| {
+        RvvInstruction::try_from(word).map(|instruction| {
+            instruction
+                .display(AsmFormatOptions::default().arg_spacing(" "))
+                .to_string()
+        })
+    };
+
+    assert_eq!(
+        display(0x0d2572d7).as_deref(),
+        Ok("vsetvli\tt0, a0, e32, m4, ta, ma")
+    );
+    assert_eq!(
+        display(0xc58272d7).as_deref(),
+        Ok("vsetivli\tt0, 0x4, e64, m1, ta, mu")
+    );
+    assert_eq!(
+        display(0x7005_72d7).as_deref(),
+        Ok("vsetvli\tt0, a0, 0x700")
+    );
+    assert_eq!(display(0x0205e407).as_deref(), Ok("vle32.v\tv8, (a1)"));
+    assert_eq!(
+        display(0x4c458407).as_deref(),
+        Ok("vloxseg3ei8.v\tv8, (a1), v4, v0.t")
+    );
+    assert_eq!(display(0x6285_8427).as_deref(), Ok("vs4r.v\tv8, (a1)"));
+    assert_eq!(
+        display(0x00880457).as_deref(),
+        Ok("vadd.vv\tv8, v8, v16, v0.t")
+    );
+    assert_eq!(
+        display(0x5c41b457).as_deref(),
+        Ok("vmerge.vim\tv8, v4, 0x3, v0")
+    );
+    assert_eq!(display(0xb6256457).as_deref(), Ok("vmacc.vx\tv8, a0, v2"));
+    assert_eq!(display(0x42401557).as_deref(), Ok("vfmv.f.s\tfa0, v4"));
+    assert_eq!(display(0x6e842457).as_deref(), Ok("vmclr.m\tv8"));
+    // `vmv.x.s` with a mask is reserved
+    assert_eq!(display(0x40401557), Err(()));
+}
@@ -0,0 +1,255 @@
+//! Symbol tables for naming addresses in disassembly output.
+//!
+//! Tables can be read from the output of `nm` (with or without `-S`) or from a GNU `ld` map file,
+//! or filled in by hand.
+
+use std::fmt::Display;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SymbolKind {
+    Function,
+    Object,
+    Other,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Symbol {
+    pub name: String,
+    pub address: u32,
+    /// Size in bytes, `0` if unknown.
+    pub size: u32,
+    pub kind: SymbolKind,
+    pub global: bool,
+}
+
+impl Symbol {
+    #[inline]
+    pub fn new(name: impl Into<String>, address: u32) -> Self {
+        Self {
+            name: name.into(),
+            address,
+            size: 0,
+            kind: SymbolKind::Other,
+            global: true,
+        }
+    }
+
+    /// Whether `address` lies within the symbol. Symbols of unknown size only contain their own
+    /// address.
+    #[inline]
+    pub fn contains(&self, address: u32) -> bool {
+        address.wrapping_sub(self.address) < self.size.max(1)
+    }
+}
+
+/// Symbols ordered by address.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SymbolTable {
+    symbols: Vec<Symbol>,
+}
+
+impl SymbolTable {
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&mut self, symbol: Symbol) {
+        // After any symbols at the same address, so the first one inserted is preferred
+        let index = self
+            .symbols
+            .partition_point(|other| other.address <= symbol.address);
+        self.symbols.insert(index, symbol);
+    }
+
+    /// Parse the output of `nm` or `nm -S`. Undefined symbols, debugging symbols and lines that
+    /// are not symbols, such as the file headers of `nm` on several files, are skipped.
+    pub fn from_nm(text: &str) -> Self {
+        let mut table = Self::new();
+
+        for line in text.lines() {
+            let fields = line.split_whitespace().collect::<Vec<_>>();
+            let (address, size, kind, name) = match fields[..] {
+                [address, kind, name] => (address, None, kind, name),
+                [address, size, kind, name] => (address, Some(size), kind, name),
+                _ => continue,
+            };
+
+            let Ok(address) = u32::from_str_radix(address, 16) else {
+                continue;
+            };
+            let Some(size) = size.map_or(Some(0), |size| u32::from_str_radix(size, 16).ok()) else {
+                continue;
+            };
+            let Some(kind @ ('A'..='Z' | 'a'..='z')) =
+                kind.chars().next().filter(|_| kind.len() == 1)
+            else {
+                continue;
+            };
+
+            table.insert(Symbol {
+                name: name.to_string(),
+                address,
+                size,
+                kind: match kind.to_ascii_uppercase() {
+                    'T' => SymbolKind::Function,
+                    'B' | 'D' | 'G' | 'R' | 'S' | 'V' => SymbolKind::Object,
+                    'N' | 'U' => continue,
+                    _ => SymbolKind::Other,
+                },
+                global: kind.is_ascii_uppercase(),
+            });
+        }
+
+        table
+    }
+
+    /// Parse the symbol assignments of a GNU `ld` map file, the lines consisting of only an
+    /// address and a name.
+    pub fn from_linker_map(text: &str) -> Self {
+        let mut table = Self::new();
+
+        for line in text.lines() {
+            let fields = line.split_whitespace().collect::<Vec<_>>();
+            let [address, name] = fields[..] else {
+                continue;
+            };
+
+            let address = address
+                .strip_prefix("0x")
+                .and_then(|address| u64::from_str_radix(address, 16).ok())
+                .and_then(|address| u32::try_from(address).ok());
+            let is_name = name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '$'));
+
+            if let (Some(address), true) = (address, is_name) {
+                table.insert(Symbol::new(name, address));
+            }
+        }
+
+        table
+    }
+
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = &Symbol> {
+        self.symbols.iter()
+    }
+
+    #[inline]
+    pub fn get(&self, name: &str) -> Option<&Symbol> {
+        self.symbols.iter().find(|symbol| symbol.name == name)
+    }
+
+    /// The preferred symbol starting exactly at `address`. Global symbols are preferred over
+    /// local ones.
+    pub fn at(&self, address: u32) -> Option<&Symbol> {
+        let start = self
+            .symbols
+            .partition_point(|symbol| symbol.address < address);
+        let candidates = self.symbols[start..]
+            .iter()
+            .take_while(|symbol| symbol.address == address);
+
+        candidates
+            .clone()
+            .find(|symbol| symbol.global)
+            .or_else(|| candidates.clone().next())
+    }
+
+    /// The symbol `address` belongs to, with the offset of `address` from its start.
+    ///
+    /// This is the closest symbol at or before `address`, unless its size is known and
+    /// `address` lies beyond it.
+    pub fn lookup(&self, address: u32) -> Option<(&Symbol, u32)> {
+        let end = self
+            .symbols
+            .partition_point(|symbol| symbol.address <= address);
+        let closest = self.symbols[..end].last()?;
+        let symbol = self.at(closest.address)?;
+
+        if symbol.size != 0 && !symbol.contains(address) {
+            return None;
+        }
+
+        Some((symbol, address - symbol.address))
+    }
+
+    /// `address <symbol+offset>`, as objdump prints addresses.
+    #[inline]
+    pub fn describe(&self, address: u32) -> impl Display + '_ {
+        Describe {
+            address,
+            symbol: self.lookup(address),
+        }
+    }
+}
+
+impl FromIterator<Symbol> for SymbolTable {
+    fn from_iter<T: IntoIterator<Item = Symbol>>(iter: T) -> Self {
+        let mut table = Self::new();
+        for symbol in iter {
+            table.insert(symbol);
+        }
+        table
+    }
+}
+
+struct Describe<'s> {
+    address: u32,
+    symbol: Option<(&'s Symbol, u32)>,
+}
+
+impl Display for Describe<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:x}", self.address)?;
+        match self.symbol {
+            Some((symbol, 0)) => write!(f, " <{}>", symbol.name),
+            Some((symbol, offset)) => write!(f, " <{}+0x{offset:x}>", symbol.name),
+            None => Ok(()),
+        }
+    }
+}
+
+#[test]
+fn parse() {
+    let nm = SymbolTable::from_nm(
+        "
+firmware.elf:
+80000000 T _start
+80000040 00000020 T main
+80000040 t .Lmain
+80001000 00000010 D buffer
+         U undefined
+        ",
+    );
+
+    assert_eq!(nm.iter().count(), 4);
+    assert_eq!(nm.at(0x8000_0040).unwrap().name, "main");
+    assert_eq!(
+        nm.lookup(0x8000_0044).map(|(s, off)| (&*s.name, off)),
+        Some(("main", 4))
+    );
+    assert_eq!(nm.lookup(0x8000_0060), None);
+    assert_eq!(
+        nm.describe(0x8000_1004).to_string(),
+        "80001004 <buffer+0x4>"
+    );
+
+    let map = SymbolTable::from_linker_map(
+        "
+ .text          0x0000000080000000      0x1a4 build/start.o
+                0x0000000080000000                _start
+                0x0000000080000040                main
+                0x0000000080000100                . = ALIGN (0x4)
+        ",
+    );
+    assert_eq!(
+        map.iter().map(|symbol| &*symbol.name).collect::<Vec<_>>(),
+        ["_start", "main"]
+    );
+    assert_eq!(
+        map.describe(0x8000_0010).to_string(),
+        "80000010 <_start+0x10>"
+    );
+}
@@ -0,0 +1,271 @@
+//! The control and status register instructions of the Zicsr extension.
+//!
+//! CSRs are shown by their name from [`csr`](crate::csr), and the instructions by the standard
+//! aliases where one applies, such as `csrr`, `rdcycle` or `fsrm`.
+
+use crate::encode::OP_SYSTEM;
+use crate::fields::instruction;
+use crate::{AsmArgument, AsmInstruction};
+
+// csrrw                REG,CSR,REG
+// csrrs                REG,CSR,REG
+// csrrc                REG,CSR,REG
+// csrrwi               REG,CSR,IMM
+// csrrsi               REG,CSR,IMM
+// csrrci               REG,CSR,IMM
+
+instruction!(Csrrw { rd, rs1, csr });
+instruction!(Csrrs { rd, rs1, csr });
+instruction!(Csrrc { rd, rs1, csr });
+instruction!(Csrrwi { rd, uimm, csr });
+instruction!(Csrrsi { rd, uimm, csr });
+instruction!(Csrrci { rd, uimm, csr });
+
+const FFLAGS: u16 = 0x001;
+const FRM: u16 = 0x002;
+const FCSR: u16 = 0x003;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ZicsrInstruction {
+    Csrrw(Csrrw),
+    Csrrs(Csrrs),
+    Csrrc(Csrrc),
+    Csrrwi(Csrrwi),
+    Csrrsi(Csrrsi),
+    Csrrci(Csrrci),
+}
+
+impl TryFrom<u32> for ZicsrInstruction {
+    type Error = ();
+
+    fn try_from(word: u32) -> Result<Self, Self::Error> {
+        let funct3 = (word >> 12) & 0b111;
+
+        Ok(match (word & 0x7f, funct3) {
+            (OP_SYSTEM, 0b001) => Self::Csrrw(Csrrw(word)),
+            (OP_SYSTEM, 0b010) => Self::Csrrs(Csrrs(word)),
+            (OP_SYSTEM, 0b011) => Self::Csrrc(Csrrc(word)),
+            (OP_SYSTEM, 0b101) => Self::Csrrwi(Csrrwi(word)),
+            (OP_SYSTEM, 0b110) => Self::Csrrsi(Csrrsi(word)),
+            (OP_SYSTEM, 0b111) => Self::Csrrci(Csrrci(word)),
+            _ => return Err(()),
+        })
+    }
+}
+
+impl AsmInstruction for ZicsrInstruction {
+    fn verb(&self) -> &'static str {
+        match self {
+            Self::Csrrw(i) => i.verb(),
+            Self::Csrrs(i) => i.verb(),
+            Self::Csrrc(i) => i.verb(),
+            Self::Csrrwi(i) => i.verb(),
+            Self::Csrrsi(i) => i.verb(),
+            Self::Csrrci(i) => i.verb(),
+        }
+    }
+
+    fn arguments(&self) -> Vec<AsmArgument> {
+        match self {
+            Self::Csrrw(i) => i.arguments(),
+            Self::Csrrs(i) => i.arguments(),
+            Self::Csrrc(i) => i.arguments(),
+            Self::Csrrwi(i) => i.arguments(),
+            Self::Csrrsi(i) => i.arguments(),
+            Self::Csrrci(i) => i.arguments(),
+        }
+    }
+}
+
+impl AsmInstruction for Csrrw {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        match (self.rd(), self.csr()) {
+            (_, FCSR) => "fscsr",
+            (_, FRM) => "fsrm",
+            (_, FFLAGS) => "fsflags",
+            (0, _) => "csrw",
+            _ => "csrrw",
+        }
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        let rd = AsmArgument::reg(self.rd()).unwrap();
+        let rs1 = AsmArgument::reg(self.rs1()).unwrap();
+        let csr = AsmArgument::Csr(self.csr());
+        match (self.rd(), self.csr()) {
+            (0, FFLAGS..=FCSR) => vec![rs1],
+            (_, FFLAGS..=FCSR) => vec![rd, rs1],
+            (0, _) => vec![csr, rs1],
+            _ => vec![rd, csr, rs1],
+        }
+    }
+}
+
+impl AsmInstruction for Csrrs {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        match (self.rd(), self.rs1(), self.csr()) {
+            (_, 0, 0xc00) => "rdcycle",
+            (_, 0, 0xc01) => "rdtime",
+            (_, 0, 0xc02) => "rdinstret",
+            (_, 0, 0xc80) => "rdcycleh",
+            (_, 0, 0xc81) => "rdtimeh",
+            (_, 0, 0xc82) => "rdinstreth",
+            (_, 0, FCSR) => "frcsr",
+            (_, 0, FRM) => "frrm",
+            (_, 0, FFLAGS) => "frflags",
+            (_, 0, _) => "csrr",
+            (0, _, _) => "csrs",
+            _ => "csrrs",
+        }
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        let rd = AsmArgument::reg(self.rd()).unwrap();
+        let rs1 = AsmArgument::reg(self.rs1()).unwrap();
+        let csr = AsmArgument::Csr(self.csr());
+        match (self.rd(), self.rs1(), self.csr()) {
+            (_, 0, 0xc00..=0xc02 | 0xc80..=0xc82 | FFLAGS..=FCSR) => vec![rd],
+            (_, 0, _) => vec![rd, csr],
+            (0, _, _) => vec![csr, rs1],
+            _ => vec![rd, csr, rs1],
+        }
+    }
+}
+
+impl AsmInstruction for Csrrc {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        match self.rd() {
+            0 => "csrc",
+            _ => "csrrc",
+        }
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        let csr = AsmArgument::Csr(self.csr());
+        let rs1 = AsmArgument::reg(self.rs1()).unwrap();
+        match self.rd() {
+            0 => vec![csr, rs1],
+            rd => vec![AsmArgument::reg(rd).unwrap(), csr, rs1],
+        }
+    }
+}
+
+impl AsmInstruction for Csrrwi {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        match (self.rd(), self.csr()) {
+            (_, FRM) => "fsrmi",
+            (_, FFLAGS) => "fsflagsi",
+            (0, _) => "csrwi",
+            _ => "csrrwi",
+        }
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        let rd = AsmArgument::reg(self.rd()).unwrap();
+        let uimm = AsmArgument::Immediate(i64::from(self.uimm()));
+        let csr = AsmArgument::Csr(self.csr());
+        match (self.rd(), self.csr()) {
+            (0, FFLAGS | FRM) => vec![uimm],
+            (_, FFLAGS | FRM) => vec![rd, uimm],
+            (0, _) => vec![csr, uimm],
+            _ => vec![rd, csr, uimm],
+        }
+    }
+}
+
+impl AsmInstruction for Csrrsi {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        match self.rd() {
+            0 => "csrsi",
+            _ => "csrrsi",
+        }
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        let csr = AsmArgument::Csr(self.csr());
+        let uimm = AsmArgument::Immediate(i64::from(self.uimm()));
+        match self.rd() {
+            0 => vec![csr, uimm],
+            rd => vec![AsmArgument::reg(rd).unwrap(), csr, uimm],
+        }
+    }
+}
+
+impl AsmInstruction for Csrrci {
+    #[inline]
+    fn verb(&self) -> &'static str {
+        match self.rd() {
+            0 => "csrci",
+            _ => "csrrci",
+        }
+    }
+
+    #[inline]
+    fn arguments(&self) -> Vec<AsmArgument> {
+        let csr = AsmArgument::Csr(self.csr());
+        let uimm = AsmArgument::Immediate(i64::from(self.uimm()));
+        match self.rd() {
+            0 => vec![csr, uimm],
+            rd => vec![AsmArgument::reg(rd).unwrap(), csr, uimm],
+        }
+    }
+}
+
+#[test]
+fn aliases() {
+    use crate::assembler::Assembler;
+    use crate::{AsmFormatOptions, Xlen};
+
+    let display = |word| {
+        ZicsrInstruction::try_from(word)
+            .unwrap()
+            .display(AsmFormatOptions::default().arg_spacing(" "))
+            .to_string()
+    };
+
+    assert_eq!(display(0x30529073), "csrw\tmtvec, t0");
+    assert_eq!(display(0xc0002573), "rdcycle\ta0");
+    assert_eq!(display(0x3b3025f3), "csrr\ta1, pmpaddr3");
+    assert_eq!(display(0xb8502573), "csrr\ta0, mhpmcounter5h");
+    assert_eq!(display(0x7c002573), "csrr\ta0, 0x7c0");
+    assert_eq!(display(0x00259573), "fsrm\ta0, a1");
+    assert_eq!(display(0x3412a573), "csrrs\ta0, mepc, t0");
+    assert_eq!(display(0x30047073), "csrci\tmstatus, 0x8");
+
+    let program = Assembler::new(0)
+        .assemble("csrr a0, mstatus\ncsrrwi zero, 0x7c0, 3\nfrcsr t0\ncsrs mie, a1")
+        .unwrap();
+    assert_eq!(
+        program.words(),
+        [0x30002573, 0x7c01d073, 0x003022f3, 0x3045a073]
+    );
+    assert!(Assembler::new(0).assemble("csrr a0, nosuchcsr").is_err());
+
+    // The upper halves of 64-bit CSRs only exist on RV32
+    let options = AsmFormatOptions::default().arg_spacing(" ");
+    let instruction = ZicsrInstruction::try_from(0x729e34f3).unwrap();
+    assert_eq!(
+        instruction.display(options.clone()).to_string(),
+        "csrrc\ts1, mhpmevent9h, t3"
+    );
+    assert_eq!(
+        instruction.display(options.xlen(Xlen::Rv64)).to_string(),
+        "csrrc\ts1, 0x729, t3"
+    );
+    let rv64 = || Assembler::new(0).xlen(Xlen::Rv64);
+    assert!(rv64().assemble("csrr a0, cycleh").is_err());
+    assert_eq!(
+        rv64().assemble("csrr a0, 0xc80").unwrap().words(),
+        [0xc8002573]
+    );
+}